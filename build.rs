@@ -0,0 +1,15 @@
+//! Generates the `sunnyday.v1` gRPC types/server trait from
+//! `proto/suggestion.proto` for `src/bin/grpc.rs`, behind the "grpc"
+//! feature -- a no-op build when that feature is off.
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("locating vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_prost_build::configure()
+            .build_server(true)
+            .build_client(false)
+            .compile_protos(&["proto/suggestion.proto"], &["proto"])
+            .expect("compiling proto/suggestion.proto");
+    }
+}
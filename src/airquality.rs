@@ -0,0 +1,52 @@
+//! PM2.5 air quality, from the community-run OpenAQ network.
+//!
+//! Rain/wind/heat/snow are all JMA's own job; air quality isn't something
+//! JMA publishes at all, so this reads OpenAQ instead -- the same
+//! "independent signal, folded into the walking decision" shape as
+//! [`crate::wbgt`], just from a different source.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+/// Root of the OpenAQ API.
+pub const OPENAQ_BASE: &str = "https://api.openaq.org/v2";
+
+/// How far (km) from the point of interest to look for a reporting
+/// station -- OpenAQ's own station density is much sparser than JMA's
+/// AMeDAS/WBGT networks, so this is generous.
+const SEARCH_RADIUS_M: u32 = 25_000;
+
+#[derive(Debug, Deserialize)]
+struct LatestResponse {
+    results: Vec<LatestResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestResult {
+    measurements: Vec<Measurement>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Measurement {
+    parameter: String,
+    value: f64,
+}
+
+/// Fetch the nearest reporting station's latest PM2.5 reading (µg/m³)
+/// within [`SEARCH_RADIUS_M`] of `(lat, lon)`. OpenAQ's `/latest` endpoint
+/// already sorts by distance when `coordinates`/`radius` are given, so
+/// there's no separate nearest-station lookup the way AMeDAS/WBGT need --
+/// the first result with a `pm25` measurement is the one to use.
+pub async fn current_pm25(client: &reqwest::Client, lat: f64, lon: f64) -> Result<f64> {
+    let url = format!("{OPENAQ_BASE}/latest?coordinates={lat},{lon}&radius={SEARCH_RADIUS_M}&parameter=pm25&order_by=distance&limit=1");
+    let response: LatestResponse =
+        client.get(&url).send().await?.error_for_status()?.json().await.context("parsing OpenAQ response")?;
+
+    response
+        .results
+        .iter()
+        .flat_map(|r| &r.measurements)
+        .find(|m| m.parameter == "pm25")
+        .map(|m| m.value)
+        .ok_or_else(|| anyhow!("no PM2.5 reading near ({lat}, {lon})"))
+}
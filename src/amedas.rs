@@ -0,0 +1,141 @@
+//! AMeDAS (Automated Meteorological Data Acquisition System) observations.
+//!
+//! Forecasts can say "dry" while it is, in fact, pouring outside; this
+//! module reports what the nearest AMeDAS station actually measured in the
+//! last ten minutes, as a sanity check alongside the forecast.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use crate::jma::JMA_BASE;
+
+/// A snapshot of the most recent reading from one AMeDAS station.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observation {
+    /// Precipitation in the last hour, mm.
+    pub precipitation1h: f64,
+    /// Air temperature, degrees Celsius, if reported by this station.
+    pub temperature: Option<f64>,
+    /// Wind speed, m/s, if reported by this station.
+    pub wind_speed: Option<f64>,
+}
+
+impl Observation {
+    /// Whether there is measurable rain right now, independent of what the
+    /// forecast said.
+    pub fn is_raining(&self) -> bool {
+        self.precipitation1h > 0.0
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StationInfo {
+    lat: [f64; 2],
+    lon: [f64; 2],
+}
+
+impl StationInfo {
+    fn lat_deg(&self) -> f64 {
+        self.lat[0] + self.lat[1] / 60.0
+    }
+
+    fn lon_deg(&self) -> f64 {
+        self.lon[0] + self.lon[1] / 60.0
+    }
+}
+
+/// Raw per-station fields as published in AMeDAS's `map/{time}.json`. Each
+/// is `[value, quality]`, or absent if the station doesn't report it.
+#[derive(Debug, Deserialize, Default)]
+struct RawObservation {
+    #[serde(default)]
+    precipitation10m: Option<[f64; 2]>,
+    #[serde(default)]
+    precipitation1h: Option<[f64; 2]>,
+    #[serde(default)]
+    temp: Option<[f64; 2]>,
+    #[serde(default)]
+    wind: Option<[f64; 2]>,
+}
+
+/// Find the AMeDAS station nearest `(lat, lon)` by simple planar distance
+/// (adequate over the short distances AMeDAS stations are spaced at).
+pub async fn nearest_station(client: &reqwest::Client, lat: f64, lon: f64) -> Result<String> {
+    let url = format!("{JMA_BASE}/amedas/const/amedastable.json");
+    let table: std::collections::HashMap<String, StationInfo> =
+        client.get(&url).send().await?.error_for_status()?.json().await?;
+
+    table
+        .iter()
+        .min_by(|(_, a), (_, b)| {
+            let da = (a.lat_deg() - lat).powi(2) + (a.lon_deg() - lon).powi(2);
+            let db = (b.lat_deg() - lat).powi(2) + (b.lon_deg() - lon).powi(2);
+            da.partial_cmp(&db).unwrap()
+        })
+        .map(|(code, _)| code.clone())
+        .ok_or_else(|| anyhow!("amedastable.json is empty"))
+}
+
+/// Raw per-day fields as published in AMeDAS's `point/{station}/{yyyymm}_daily.json`,
+/// keyed by `"YYYYMMDD"`. Same `[value, quality]` shape as [`RawObservation`].
+#[derive(Debug, Deserialize, Default)]
+struct RawDaily {
+    #[serde(default)]
+    precipitation24h: Option<[f64; 2]>,
+}
+
+/// Fetch `station`'s observed daily precipitation total (mm) for every day
+/// JMA has published in `yyyymm`'s (`"YYYYMM"`) file, for
+/// `sunnyday backtest`. A day with no `precipitation24h` entry at all
+/// (station down, or hasn't reported yet) is left out rather than
+/// defaulting to zero, so callers can tell "no rain" from "no data".
+pub async fn daily_precipitation(
+    client: &reqwest::Client,
+    station: &str,
+    yyyymm: &str,
+) -> Result<std::collections::HashMap<chrono::NaiveDate, f64>> {
+    let url = format!("{JMA_BASE}/amedas/data/point/{station}/{yyyymm}_daily.json");
+    let by_day: std::collections::HashMap<String, RawDaily> =
+        client.get(&url).send().await?.error_for_status()?.json().await?;
+
+    Ok(by_day
+        .into_iter()
+        .filter_map(|(date_str, raw)| {
+            let date = chrono::NaiveDate::parse_from_str(&date_str, "%Y%m%d").ok()?;
+            let [mm, _quality] = raw.precipitation24h?;
+            Some((date, mm))
+        })
+        .collect())
+}
+
+/// Fetch `station`'s latest observation.
+pub async fn latest_observation(client: &reqwest::Client, station: &str) -> Result<Observation> {
+    let latest_time = client
+        .get(format!("{JMA_BASE}/amedas/data/latest_time.txt"))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?
+        .trim()
+        .to_string();
+
+    let url = format!("{JMA_BASE}/amedas/data/map/{latest_time}.json");
+    let by_station: std::collections::HashMap<String, RawObservation> =
+        client.get(&url).send().await?.error_for_status()?.json().await?;
+
+    let raw = by_station
+        .get(station)
+        .ok_or_else(|| anyhow!("no AMeDAS observation for station {station}"))
+        .context("latest_observation")?;
+
+    Ok(Observation {
+        precipitation1h: raw
+            .precipitation1h
+            .or(raw.precipitation10m)
+            .map(|[value, _quality]| value)
+            .unwrap_or(0.0),
+        temperature: raw.temp.map(|[value, _quality]| value),
+        wind_speed: raw.wind.map(|[value, _quality]| value),
+    })
+}
@@ -0,0 +1,66 @@
+//! Epsilon-greedy bias toward places actually accepted when suggested (see
+//! [`crate::visit`]/[`crate::stats::acceptance_rates`]), layered on top of
+//! [`crate::place::Places::candidates`]/[`crate::place::Places::rank`]
+//! rather than baked into their weighting -- a caller with no acceptance
+//! data (e.g. the `rank`/`week` debug paths) just keeps today's plain
+//! [`crate::place::Place::weight`] behavior.
+
+use serde::Deserialize;
+
+/// The `[learning]` table in `place.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LearningConfig {
+    /// Off by default: a place's plain [`crate::place::Place::weight`] is
+    /// used as-is until there's a visit log to learn from.
+    pub enabled: bool,
+    /// How much of a place's weight ignores its learned acceptance rate
+    /// and stays exploratory, from 0.0 (fully trust the log) to 1.0
+    /// (ignore it entirely, same as `enabled = false`). A place with no
+    /// log entries of its own always keeps its full weight regardless of
+    /// this, so new places still appear.
+    pub exploration: f64,
+}
+
+impl Default for LearningConfig {
+    fn default() -> Self {
+        LearningConfig { enabled: false, exploration: 0.2 }
+    }
+}
+
+impl LearningConfig {
+    /// Blend `weight` with `acceptance_rate` (see
+    /// [`crate::stats::acceptance_rates`]): with no observed rate, `weight`
+    /// passes through unchanged; otherwise interpolate between trusting the
+    /// rate fully and ignoring it, by [`Self::exploration`].
+    pub fn weigh(&self, weight: f64, acceptance_rate: Option<f64>) -> f64 {
+        match acceptance_rate {
+            Some(rate) => weight * (self.exploration + (1.0 - self.exploration) * rate),
+            None => weight,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unobserved_places_keep_their_full_weight() {
+        let learning = LearningConfig { enabled: true, exploration: 0.2 };
+        assert_eq!(learning.weigh(2.0, None), 2.0);
+    }
+
+    #[test]
+    fn low_exploration_mostly_tracks_the_acceptance_rate() {
+        let learning = LearningConfig { enabled: true, exploration: 0.0 };
+        assert_eq!(learning.weigh(1.0, Some(1.0)), 1.0);
+        assert_eq!(learning.weigh(1.0, Some(0.0)), 0.0);
+    }
+
+    #[test]
+    fn full_exploration_ignores_the_acceptance_rate() {
+        let learning = LearningConfig { enabled: true, exploration: 1.0 };
+        assert_eq!(learning.weigh(1.5, Some(0.0)), 1.5);
+    }
+}
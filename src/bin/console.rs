@@ -0,0 +1,1013 @@
+//! `sunnyday`: command-line suggestion for "should I go out, and where".
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use anyhow::{bail, Context, Result};
+use chrono::Datelike;
+use clap::{Parser, Subcommand};
+use sunnyday::airquality;
+use sunnyday::amedas;
+#[cfg(feature = "notify")]
+use sunnyday::calendar;
+use sunnyday::jma;
+use sunnyday::typhoon;
+use sunnyday::warning;
+use sunnyday::{
+    Duration, Energy, ForecastJournal, Mood, PartOfDay, Places, RecentPlace, Stats, Suggester, TravelMode, VisitLog, VisitOutcome, VisitRecord, ALL_DAY,
+};
+
+#[derive(Parser)]
+#[command(name = "sunnyday", about = "Suggest somewhere to go, weather permitting")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to the place list.
+    #[arg(long, default_value = "place.toml", global = true)]
+    places: String,
+
+    /// Path to the rotation history file.
+    #[arg(long, default_value = "recent.toml", global = true)]
+    recent: String,
+
+    /// Path to the append-only visit log (see `accept`/`skip`).
+    #[arg(long, default_value = "visits.jsonl", global = true)]
+    visits: String,
+
+    /// Path to the append-only forecast journal -- every weather fetch and
+    /// the walking decision it reached, regardless of whether a suggestion
+    /// was ever answered.
+    #[arg(long, default_value = "forecast_journal.jsonl", global = true)]
+    journal: String,
+
+    /// Only suggest places suitable for walking.
+    #[arg(long, global = true)]
+    walking: bool,
+
+    /// Only suggest places suitable for cycling.
+    #[arg(long, global = true)]
+    cycling: bool,
+
+    /// Only suggest places with food available.
+    #[arg(long, global = true)]
+    food: bool,
+
+    /// Only suggest indoor places. Weather forcing a "stay in" day already
+    /// requires this regardless.
+    #[arg(long, global = true)]
+    indoor: bool,
+
+    /// Only suggest places tagged with this (repeatable).
+    #[arg(long, global = true)]
+    tag: Vec<String>,
+
+    /// Exclude places tagged with this (repeatable).
+    #[arg(long, global = true)]
+    not_tag: Vec<String>,
+
+    /// Only suggest places within this many km of `[home]` (see
+    /// place.toml), straight-line distance. Places with no coordinates of
+    /// their own, or no `[home]` configured, aren't filtered by this.
+    #[arg(long, global = true)]
+    max_distance: Option<f64>,
+
+    /// Only suggest places within this many minutes of `[home]` at a
+    /// walking or cycling pace, picked by the place's own `cycling` flag.
+    /// Same fallback as `--max-distance`.
+    #[arg(long, global = true)]
+    max_minutes: Option<f64>,
+
+    /// How you're getting around today; only suggests places whose
+    /// `access` list includes this mode (places with no `access` list are
+    /// unrestricted). Driving also implies requiring parking.
+    #[arg(long, global = true)]
+    by: Option<TravelMode>,
+
+    /// Only suggest places with a `price` at or below this (1-4). Places
+    /// with no price of their own aren't filtered by this.
+    #[arg(long, global = true)]
+    budget: Option<u8>,
+
+    /// Only suggest places suitable for bringing children along.
+    #[arg(long, global = true)]
+    with_kids: bool,
+
+    /// Only suggest places suitable for bringing a pet along.
+    #[arg(long, global = true)]
+    with_dog: bool,
+
+    /// Only suggest places with a shop catering to this dietary option,
+    /// e.g. `--diet vegetarian` (repeatable -- a place must satisfy all).
+    #[arg(long, global = true)]
+    diet: Vec<String>,
+
+    /// Only suggest places matching this typical visit length.
+    #[arg(long, global = true)]
+    duration: Option<Duration>,
+
+    /// Only suggest places matching this energy level.
+    #[arg(long, global = true)]
+    energy: Option<Energy>,
+
+    /// Set every mood dimension at once via a compact string, e.g.
+    /// `--mood "food=yes,walking=no,parking=-"` (see `Mood`'s `FromStr` for
+    /// the full key list). Overrides all the other mood flags when given.
+    #[arg(long, global = true)]
+    mood: Option<Mood>,
+
+    /// Still print parts of the day that have already passed, marked
+    /// "(past)", instead of silently skipping them.
+    #[arg(long, global = true)]
+    show_past: bool,
+
+    /// Also print a suggestion's note, url, and map_url, if set.
+    #[arg(long, global = true)]
+    verbose: bool,
+
+    /// Also print why every other place was left out -- the precipitation/
+    /// wind values checked against the area's thresholds, plus each
+    /// excluded place's reason (see `sunnyday::Reasoning`).
+    #[arg(long, global = true)]
+    explain: bool,
+
+    /// Also print a QR code encoding the suggested place's `map_url`, for
+    /// scanning straight onto a phone. Does nothing for a place with no
+    /// `map_url` configured.
+    #[arg(long, global = true)]
+    qr: bool,
+
+    /// Override the place list's configured timezone (IANA name, e.g.
+    /// "Asia/Tokyo").
+    #[arg(long, global = true)]
+    timezone: Option<chrono_tz::Tz>,
+
+    /// Pick again even if a part of day already has a suggestion decided
+    /// for today, instead of returning that same one.
+    #[arg(long, global = true)]
+    reroll: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Suggest a tentative place for each day of the week ahead, using
+    /// JMA's weekly probability-of-precipitation forecast.
+    Week,
+    /// Check the place list for configuration mistakes (duplicate names,
+    /// unknown shop/itinerary references, bad area codes, out-of-range
+    /// coordinates, ...) and report them. `Places::read` already runs
+    /// this on every startup; this just lets you check without otherwise
+    /// running the tool.
+    Validate,
+    /// List every place matching the current mood, scored and sorted by
+    /// `sunnyday::Places::rank`, instead of committing to a single
+    /// suggestion. Doesn't touch the rotation history.
+    Rank,
+    /// Record that you actually went to today's suggestion for `part`, in
+    /// the append-only visit log (see `sunnyday::VisitLog`). Looks the
+    /// place up from the rotation history, so run this after a plain
+    /// `sunnyday` call already suggested one.
+    Accept {
+        part: PartOfDay,
+    },
+    /// Like `accept`, but records that you passed on today's suggestion
+    /// for `part` instead.
+    Skip {
+        part: PartOfDay,
+    },
+    /// Print statistics over the visit log: most/least visited places,
+    /// visit counts per month, acceptance rate, and how often a decline
+    /// came with rain in the forecast.
+    Stats {
+        /// Print as JSON instead of a table.
+        #[arg(long)]
+        json: bool,
+        #[command(subcommand)]
+        command: Option<StatsCommand>,
+    },
+    /// Operate on the place list itself, rather than asking for a
+    /// suggestion.
+    Places {
+        #[command(subcommand)]
+        command: PlacesCommand,
+    },
+    /// Operate on the rotation history itself, rather than asking for a
+    /// suggestion.
+    Recent {
+        #[command(subcommand)]
+        command: RecentCommand,
+    },
+    /// Replay AMeDAS's observed daily precipitation for the nearest
+    /// station over `[from, to]` against `[area] precipitation`, and
+    /// report how often that threshold would have said "walk" on a day it
+    /// actually rained -- for tuning `precipitation`/`pop_limit` against
+    /// what actually happened, instead of guessing.
+    Backtest {
+        #[arg(long)]
+        from: chrono::NaiveDate,
+        #[arg(long)]
+        to: chrono::NaiveDate,
+    },
+    /// Fetch and print today's forecast for `part` (every part that
+    /// hasn't passed, if omitted) from the configured weather provider.
+    /// With `--compare`, every provider `[weather]` knows how to build
+    /// (the configured one plus any others that have what they need --
+    /// an API key, a fixture directory, ...) is queried and printed side
+    /// by side, for deciding which one to trust for this area.
+    Forecast {
+        part: Option<PartOfDay>,
+        #[arg(long)]
+        compare: bool,
+    },
+    /// Print `name`'s walking route as GPX, for loading into a watch or
+    /// phone app -- its own `gpx` file if configured, otherwise a single
+    /// waypoint at its coordinates. See `sunnyday::gpx::route_for`.
+    Route {
+        name: String,
+    },
+    /// Run forever, re-fetching the configured area's rain tiles on a
+    /// timer so they're already cached by the time a new JMA basetime
+    /// appears -- the CLI's equivalent of `sunnyday-web`'s background
+    /// prefetch task. Only worth running as its own long-lived process
+    /// (e.g. under systemd); it shares nothing with a plain `sunnyday`
+    /// invocation, which starts with an empty cache of its own. Exits
+    /// with Ctrl-C or a signal, like any other daemon.
+    Daemon {
+        /// Seconds between prefetch passes.
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum RecentCommand {
+    /// Remove the most recent rotation entry for `part` (and its visit log
+    /// entry, if any), for when the tool was run by mistake or its
+    /// suggestion wasn't actually followed.
+    Undo {
+        part: PartOfDay,
+    },
+    /// Print the rotation history as JSON, for syncing to another device
+    /// via `sunnyday recent import`.
+    Export {
+        /// Where to write it: a file path, an http(s) URL (e.g. a WebDAV
+        /// share, PUT with the JSON as the body), or stdout if omitted.
+        /// Plain file paths and WebDAV-style PUT are supported; S3 isn't.
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Merge another device's exported history into this one (see
+    /// `sunnyday recent export`); rotation entries, snoozes, and today's
+    /// picks are unioned by date and part, the imported side winning a
+    /// conflict.
+    Import {
+        /// A file path or http(s) URL (fetched with a plain GET) to read
+        /// the exported JSON from.
+        location: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PlacesCommand {
+    /// Print the place list in another format, for visualizing on a map.
+    Export {
+        /// Output format.
+        #[arg(long, value_enum, default_value = "geojson")]
+        format: ExportFormat,
+    },
+    /// Convert a GeoJSON or KML export (e.g. Google Takeout "saved
+    /// places") into `[[place]]` entries, printed for you to review and
+    /// paste into place.toml. Prompts for each place's walking/parking,
+    /// since an export has no way to know them.
+    Import {
+        /// Path to the GeoJSON or KML file.
+        file: std::path::PathBuf,
+    },
+    /// Force `name` to be the very next suggestion, regardless of mood or
+    /// weather. Good for one suggestion only.
+    Pin {
+        name: String,
+    },
+    /// Exclude `name` from suggestion for the next `--days` days.
+    /// Re-snoozing a place replaces its existing snooze rather than
+    /// stacking.
+    Snooze {
+        name: String,
+        #[arg(long)]
+        days: u32,
+    },
+    /// Exclude `name` from suggestion indefinitely.
+    Blacklist {
+        name: String,
+    },
+    /// Guess the `offices`/`class10s` codes for a point, for pasting into
+    /// `[area]` instead of looking them up by hand -- see
+    /// `sunnyday::jma::area_codes_for`. An approximation: it follows the
+    /// numbering most prefectures use and confirms the guess against
+    /// JMA's own area list, but can't disambiguate prefectures (Hokkaido
+    /// chief among them) split into several JMA areas.
+    GuessArea {
+        lat: f64,
+        lon: f64,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ExportFormat {
+    Geojson,
+}
+
+#[derive(Subcommand)]
+enum StatsCommand {
+    /// Dump the raw visit log (date, part, place, forecast values,
+    /// accepted flag), one row per visit, instead of the summary `stats`
+    /// prints by default -- for further analysis in a spreadsheet.
+    Export {
+        #[arg(long, value_enum, default_value = "csv")]
+        format: StatsExportFormat,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum StatsExportFormat {
+    Csv,
+}
+
+/// Read a `sunnyday recent export`/`import` location: an http(s) URL
+/// (plain GET, e.g. a WebDAV share) or a local file path.
+async fn read_location(location: &str) -> Result<String> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        let response = reqwest::get(location).await.with_context(|| format!("fetching {location}"))?;
+        let response = response.error_for_status().with_context(|| format!("fetching {location}"))?;
+        response.text().await.with_context(|| format!("reading response body from {location}"))
+    } else {
+        std::fs::read_to_string(location).with_context(|| format!("reading {location}"))
+    }
+}
+
+/// Write a `sunnyday recent export` location: an http(s) URL (plain PUT,
+/// e.g. a WebDAV share) or a local file path. S3 isn't supported -- it
+/// needs request signing, not just a PUT.
+async fn write_location(location: &str, text: &str) -> Result<()> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        let client = sunnyday::http::client();
+        let response = client.put(location).body(text.to_string()).send().await.with_context(|| format!("uploading to {location}"))?;
+        response.error_for_status().with_context(|| format!("uploading to {location}"))?;
+        Ok(())
+    } else {
+        std::fs::write(location, text).with_context(|| format!("writing {location}"))
+    }
+}
+
+/// Best-effort current-conditions check: any failure (no network, station
+/// not found, ...) is treated as "nothing to report" rather than an error,
+/// since this is a supplement to the forecast, not a requirement.
+async fn current_observation(places: &Places) -> Option<amedas::Observation> {
+    let client = sunnyday::http::client();
+    let station = amedas::nearest_station(&client, places.area.lat, places.area.lon).await.ok()?;
+    amedas::latest_observation(&client, &station).await.ok()
+}
+
+/// Best-effort active-warnings check; see `current_observation` for why
+/// failures are swallowed rather than surfaced as an error.
+async fn active_warnings(places: &Places) -> Vec<warning::Warning> {
+    let client = sunnyday::http::client();
+    warning::active_warnings(&client, &places.area.offices).await.unwrap_or_default()
+}
+
+/// Best-effort current WBGT heat-stress reading, skipped outright outside
+/// `sunnyday::wbgt::in_season`'s window; see `current_observation` for why
+/// failures are otherwise swallowed rather than surfaced as an error.
+async fn current_wbgt(places: &Places) -> Option<f64> {
+    if !sunnyday::wbgt::in_season(places.area.now().date_naive()) {
+        return None;
+    }
+    let client = sunnyday::http::client();
+    let station = sunnyday::wbgt::nearest_station(&client, places.area.lat, places.area.lon).await.ok()?;
+    sunnyday::wbgt::current_wbgt(&client, &station).await.ok()
+}
+
+/// Best-effort typhoon-track advisory check: `None` unless a typhoon's
+/// forecast track comes within `[area] typhoon_distance_km`, which also
+/// gates whether this bothers fetching at all. See `current_observation`
+/// for why failures are otherwise swallowed rather than surfaced as an
+/// error.
+async fn current_typhoon_advisory(places: &Places) -> Option<(String, f64)> {
+    let limit = places.area.typhoon_distance_km?;
+    let client = sunnyday::http::client();
+    let (name, km) = typhoon::nearest_approach(&client, places.area.lat, places.area.lon).await.ok()??;
+    (km <= limit).then_some((name, km))
+}
+
+/// Best-effort current PM2.5 reading, skipped outright when `[area]
+/// max_pm25` is unset; see `current_observation` for why failures are
+/// otherwise swallowed rather than surfaced as an error.
+async fn current_pm25(places: &Places) -> Option<f64> {
+    places.area.max_pm25?;
+    let client = sunnyday::http::client();
+    airquality::current_pm25(&client, places.area.lat, places.area.lon).await.ok()
+}
+
+/// Print a suggested place's name, its concrete shop pick if any, plus the
+/// place's `note`/`url`/`map_url` when `verbose` is set and they're
+/// present. With `qr`, also prints a QR code encoding `map_url` (see
+/// `sunnyday::qr::terminal_qr`), regardless of `verbose`.
+fn print_suggestion(place: &sunnyday::Place, shop: Option<&sunnyday::Shop>, verbose: bool, qr: bool) {
+    println!("  suggestion: {}", place.name);
+    if let Some(shop) = shop {
+        println!("    at: {}", shop.name);
+    }
+    if qr {
+        if let Some(map_url) = &place.map_url {
+            match sunnyday::qr::terminal_qr(map_url) {
+                Ok(code) => println!("{code}"),
+                Err(e) => eprintln!("warning: failed to render QR code for {map_url:?}: {e}"),
+            }
+        }
+    }
+    if !verbose {
+        return;
+    }
+    if let Some(note) = &place.note {
+        println!("    note: {note}");
+    }
+    if let Some(url) = &place.url {
+        println!("    url: {url}");
+    }
+    if let Some(map_url) = &place.map_url {
+        println!("    map: {map_url}");
+    }
+}
+
+/// Print `--verbose`'s per-frame detail: every validtime in the rain-tile
+/// window fetched for this part, via `jma::precipitation_timeline` directly
+/// (regardless of the configured provider, same as `run_week`'s use of
+/// `jma::weekly_pops`). Silently prints nothing on failure -- e.g. a
+/// provider other than JMA, or a transient fetch error -- rather than
+/// failing an otherwise-successful suggestion over this extra detail.
+async fn print_timeline(area: &jma::AreaCode, part: PartOfDay) {
+    if let Ok(frames) = jma::precipitation_timeline(area, part, area.lat, area.lon).await {
+        for frame in &frames {
+            println!("  timeline: {} precipitation={:.1}mm/h ({})", frame.validtime, frame.precipitation, frame.tile_url);
+        }
+    }
+}
+
+/// Print `--verbose`'s data-provenance line: which JMA run this part's
+/// forecast came from, whether its tiles were already cached, and a
+/// staleness warning if `area.max_forecast_age_minutes` is set and the
+/// basetime is older than that. Fetched via `jma::weather_report` directly,
+/// same as `print_timeline`, and silently prints nothing on failure.
+async fn print_report(area: &jma::AreaCode, part: PartOfDay) {
+    if let Ok(report) = jma::weather_report(area, part, area.lat, area.lon).await {
+        let basetime = report.basetime.map(|b| b.to_rfc3339()).unwrap_or_else(|| "unknown".to_string());
+        println!(
+            "  report: provider={} basetime={basetime} cache={}",
+            report.provider,
+            if report.from_cache { "hit" } else { "miss" }
+        );
+        if let Some(max_age) = area.max_forecast_age_minutes {
+            if report.is_stale(max_age) {
+                println!("  warning: forecast basetime is older than {max_age} minutes -- data may be stale");
+            }
+        }
+    }
+}
+
+/// Print `--explain`'s detail: the precipitation/wind values checked
+/// against the area's thresholds, and why every other place was left out.
+fn print_explanation(explanation: &sunnyday::Reasoning) {
+    println!(
+        "  explain: precipitation={:.1}{unit} (threshold {:.1}{unit}) wind={:.1}m/s (threshold {:.1})",
+        explanation.precipitation,
+        explanation.precipitation_threshold,
+        explanation.wind_speed,
+        explanation.wind_threshold,
+        unit = explanation.precipitation_unit,
+    );
+    for excluded in &explanation.excluded {
+        println!("  explain: {:?} -- {}", excluded.place, excluded.reason);
+    }
+}
+
+/// Best-effort forecast lookup for the visit log; see `current_observation`
+/// for why failures are swallowed rather than surfaced as an error. Always
+/// a fresh lookup, not whatever `suggest` originally saw.
+async fn current_forecast(places: &Places, part: PartOfDay) -> Option<sunnyday::provider::Forecast> {
+    places.weather.build().ok()?.forecast(&places.area, part, None).await.ok()
+}
+
+/// Record the outcome of today's suggestion for `part` in the visit log,
+/// looking the place up from `recent` (the rotation history already knows
+/// what was last suggested for each part). Prints a message instead of
+/// erroring if nothing was suggested for `part` today.
+async fn log_visit(places: &Places, recent: &RecentPlace, visits_path: &str, part: PartOfDay, outcome: VisitOutcome) -> Result<()> {
+    let date = places.area.now().date_naive();
+    let Some(place) = recent.last_for(date, part) else {
+        println!("no suggestion recorded for {part:?} today -- run `sunnyday` first");
+        return Ok(());
+    };
+    let place = place.to_string();
+    let walkable = !current_observation(places).await.map(|o| o.is_raining()).unwrap_or(false);
+    let forecast = current_forecast(places, part).await.unwrap_or(sunnyday::provider::Forecast { pop: 0, precipitation: 0.0, wind_speed: 0.0 });
+    let record = VisitRecord {
+        date,
+        part,
+        place: place.clone(),
+        outcome,
+        walkable,
+        pop: forecast.pop,
+        precipitation: forecast.precipitation,
+        wind_speed: forecast.wind_speed,
+    };
+    VisitLog::open(visits_path).append(&record)?;
+    println!("recorded {outcome:?} for {place} ({part:?})");
+    notify_accepted(places, &record).await;
+    Ok(())
+}
+
+/// Best-effort: create a calendar event for an accepted suggestion (see
+/// [`sunnyday::calendar`]), if `[calendar]` is configured in `place.toml`.
+/// A failure here is printed, not propagated -- the visit is already
+/// recorded either way, same as this crate's other "missing optional
+/// integration degrades gracefully" behavior (WBGT, PM2.5, `decision_script`...).
+#[cfg(feature = "notify")]
+async fn notify_accepted(places: &Places, record: &VisitRecord) {
+    if record.outcome != VisitOutcome::Accepted {
+        return;
+    }
+    let Some(config) = &places.calendar else { return };
+    let backend = match config.build() {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("warning: failed to set up calendar backend: {e:#}");
+            return;
+        }
+    };
+    let (start, end) = calendar::event_window(record.part, record.date, places.area.timezone);
+    let event = calendar::Event { summary: format!("sunnyday: {}", record.place), location: record.place.clone(), start, end };
+    if let Err(e) = backend.create_event(&event).await {
+        eprintln!("warning: failed to create calendar event: {e:#}");
+    }
+}
+
+#[cfg(not(feature = "notify"))]
+async fn notify_accepted(_places: &Places, _record: &VisitRecord) {}
+
+/// Print [`Stats`] as a table: totals, acceptance/rain-decline rates, then
+/// per-place and per-month visit counts.
+fn print_stats(stats: &Stats) {
+    println!("{} visits logged ({} accepted, {} declined)", stats.total, stats.accepted, stats.declined);
+    println!("acceptance rate: {:.0}%", stats.acceptance_rate * 100.0);
+    println!("declines blamed on rain: {:.0}%", stats.rain_decline_rate * 100.0);
+    if let Some(most) = stats.most_visited() {
+        println!("most visited: {} ({})", most.place, most.count);
+    }
+    if let Some(least) = stats.least_visited() {
+        println!("least visited: {} ({})", least.place, least.count);
+    }
+    println!("by place:");
+    for place in &stats.by_place {
+        println!("  {:>4}  {}", place.count, place.place);
+    }
+    println!("by month:");
+    for month in &stats.by_month {
+        println!("  {:>4}  {}", month.count, month.month);
+    }
+}
+
+/// Ask a yes/no question on stdin, for `places import` since an export has
+/// no way to know these. Defaults to "no" on an empty, unrecognized, or
+/// unreadable (e.g. stdin closed) answer.
+fn ask_yes_no(question: &str) -> bool {
+    print!("{question} [y/N] ");
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+async fn run_today(
+    suggester: &mut Suggester,
+    mood: &Mood,
+    show_past: bool,
+    verbose: bool,
+    explain: bool,
+    reroll: bool,
+    qr: bool,
+) -> Result<()> {
+    let now = suggester.places.area.now();
+
+    if let Some(observation) = current_observation(&suggester.places).await {
+        if observation.is_raining() {
+            println!("it is raining right now ({:.1}mm/h)", observation.precipitation1h);
+        }
+    }
+
+    let warnings = active_warnings(&suggester.places).await;
+    for w in &warnings {
+        println!("JMA warning in effect: {}", w.name);
+    }
+
+    let wbgt = current_wbgt(&suggester.places).await;
+    if let Some(value) = wbgt {
+        println!("WBGT (heat index): {value:.1}°C");
+    }
+    let wbgt_exceeded = match (wbgt, suggester.places.area.wbgt_limit) {
+        (Some(value), Some(limit)) => value >= limit,
+        _ => false,
+    };
+    if wbgt_exceeded {
+        println!("heat-stroke risk is high -- staying in");
+    }
+
+    let typhoon_advisory = current_typhoon_advisory(&suggester.places).await;
+    if let Some((name, km)) = &typhoon_advisory {
+        println!("typhoon advisory: {name} forecast within {km:.0}km -- staying in");
+    }
+
+    let pm25 = current_pm25(&suggester.places).await;
+    if let Some(value) = pm25 {
+        println!("PM2.5: {value:.0}\u{b5}g/m\u{b3}");
+    }
+    let pm25_exceeded = match (pm25, suggester.places.area.max_pm25) {
+        (Some(value), Some(limit)) => value >= limit,
+        _ => false,
+    };
+    if pm25_exceeded {
+        println!("air quality is poor -- staying in");
+    }
+
+    let stay_in_forced = (suggester.places.area.respect_warnings && warnings.iter().any(|w| w.forces_stay_in))
+        || wbgt_exceeded
+        || typhoon_advisory.is_some()
+        || pm25_exceeded;
+
+    for part in ALL_DAY {
+        if suggester.places.area.part_has_passed(part, now) {
+            if show_past {
+                println!("{part:?} (past)");
+            }
+            continue;
+        }
+
+        let snow_forced = match suggester.places.area.snow_limit {
+            Some(limit) => match jma::snowfall(&suggester.places.area, part).await {
+                Ok(cm) if cm >= limit => {
+                    println!("heavy snow forecast ({cm:.0}cm) -- staying in");
+                    true
+                }
+                _ => false,
+            },
+            None => false,
+        };
+
+        let suggestion = suggester.suggest(part, mood, stay_in_forced || snow_forced, reroll).await?;
+        for line in &suggestion.reasoning {
+            println!("{line}");
+        }
+        if verbose {
+            print_timeline(&suggester.places.area, part).await;
+            print_report(&suggester.places.area, part).await;
+        }
+        if explain && suggestion.weather_available {
+            print_explanation(&suggestion.explanation);
+        }
+
+        if let Some((itinerary, legs)) = &suggestion.itinerary {
+            println!("  itinerary: {}", itinerary.name);
+            for leg in legs {
+                println!("    - {}", leg.name);
+            }
+        } else if let Some(place) = &suggestion.place {
+            let shop = if mood.food == Some(true) {
+                suggester.places.pickup_shop(place, &suggester.recent, now.date_naive(), Some(part))
+            } else {
+                None
+            };
+            print_suggestion(place, shop.as_ref(), verbose, qr);
+            if let Some(shop) = &shop {
+                suggester.recent.push_shop(&shop.name);
+            }
+        } else {
+            println!("  no place matches your mood right now");
+        }
+    }
+
+    Ok(())
+}
+
+/// Suggest a tentative place for each day of the week, using JMA's weekly
+/// pops forecast in place of the tile-derived precipitation/wind the
+/// morning/afternoon suggestions use. See [`jma::WEEKLY_WALKABLE_POP`].
+async fn run_week(places: &Places, mood: &Mood, recent: &mut RecentPlace, verbose: bool, qr: bool) -> Result<()> {
+    let days = jma::weekly_pops(&places.area).await?;
+    for (date, pop) in days {
+        let walkable = pop < jma::WEEKLY_WALKABLE_POP;
+        let icon = sunnyday::provider::Forecast { pop, precipitation: 0.0, wind_speed: 0.0 }.icon();
+        println!("{date}: {icon} pop={pop}% -> {}", if walkable { "walkable" } else { "stay in" });
+
+        let day_mood = if walkable {
+            mood.clone()
+        } else {
+            Mood {
+                indoor: mood.indoor.or(Some(true)),
+                ..mood.clone()
+            }
+        };
+        if let Some(place) = places.pickup(&day_mood, recent, date, None, None) {
+            let shop = if day_mood.food == Some(true) { places.pickup_shop(&place, recent, date, None) } else { None };
+            print_suggestion(&place, shop.as_ref(), verbose, qr);
+            recent.push(&place.name, date, None);
+            recent.set_last_cluster(place.cluster.as_deref());
+            if let Some(shop) = &shop {
+                recent.push_shop(&shop.name);
+            }
+        } else {
+            println!("  no place matches your mood right now");
+        }
+    }
+    Ok(())
+}
+
+/// Replay observed daily precipitation over `[from, to]` against `area`'s
+/// configured `precipitation` threshold, and report how often it would
+/// have said "walk" on a day it actually rained. Approximates: the real
+/// decision checks tile-derived mm/h against a single part of the day,
+/// while this checks AMeDAS's 24h daily total against the same mm/h
+/// number -- close enough to tell whether a threshold is in the right
+/// ballpark, not a bit-for-bit replay of `is_rainy`.
+async fn run_backtest(area: &jma::AreaCode, from: chrono::NaiveDate, to: chrono::NaiveDate) -> Result<()> {
+    if from > to {
+        return Err(anyhow::anyhow!("--from ({from}) is after --to ({to})"));
+    }
+
+    let client = sunnyday::http::client();
+    let station = amedas::nearest_station(&client, area.lat, area.lon).await?;
+
+    let mut daily = HashMap::new();
+    let mut month = chrono::NaiveDate::from_ymd_opt(from.year(), from.month(), 1).unwrap();
+    let stop = chrono::NaiveDate::from_ymd_opt(to.year(), to.month(), 1).unwrap();
+    while month <= stop {
+        daily.extend(amedas::daily_precipitation(&client, &station, &month.format("%Y%m").to_string()).await?);
+        month = if month.month() == 12 {
+            chrono::NaiveDate::from_ymd_opt(month.year() + 1, 1, 1).unwrap()
+        } else {
+            chrono::NaiveDate::from_ymd_opt(month.year(), month.month() + 1, 1).unwrap()
+        };
+    }
+
+    let mut days = 0u32;
+    let mut rainy_days = 0u32;
+    let mut false_positives = 0u32;
+    let mut missing = 0u32;
+    let mut date = from;
+    while date <= to {
+        match daily.get(&date) {
+            Some(&mm) => {
+                days += 1;
+                let rained = mm > 0.0;
+                let would_walk = mm < area.precipitation;
+                if rained {
+                    rainy_days += 1;
+                    if would_walk {
+                        false_positives += 1;
+                        println!("{date}: said walk, but {mm:.1}mm fell");
+                    }
+                }
+            }
+            None => missing += 1,
+        }
+        date += chrono::Duration::days(1);
+    }
+
+    println!("{days} day(s) with data ({missing} missing), {rainy_days} rainy");
+    if rainy_days > 0 {
+        println!(
+            "{false_positives}/{rainy_days} rainy day(s) ({:.0}%) would have said \"walk\" at precipitation = {}",
+            100.0 * false_positives as f64 / rainy_days as f64,
+            area.precipitation
+        );
+    }
+    Ok(())
+}
+
+/// The parts of the day `sunnyday forecast` should cover when `part` is
+/// omitted: everything that hasn't passed yet today.
+fn forecast_parts(area: &jma::AreaCode, part: Option<PartOfDay>) -> Vec<PartOfDay> {
+    match part {
+        Some(part) => vec![part],
+        None => {
+            let now = area.now();
+            ALL_DAY.into_iter().filter(|p| !area.part_has_passed(*p, now)).collect()
+        }
+    }
+}
+
+/// `sunnyday forecast`/`sunnyday forecast --compare`: see `Command::Forecast`.
+async fn run_forecast(places: &Places, part: Option<PartOfDay>, compare: bool) -> Result<()> {
+    let parts = forecast_parts(&places.area, part);
+    let provider_names: &[&str] = if compare { &sunnyday::config::KNOWN_PROVIDERS } else { &[] };
+
+    if !compare {
+        let provider = places.weather.build()?;
+        for part in &parts {
+            let forecast = provider.forecast(&places.area, *part, None).await?;
+            println!(
+                "{:?}: {} pop={}% precipitation={:.1}{} wind={:.1}m/s",
+                part,
+                forecast.icon(),
+                forecast.pop,
+                places.area.display_precipitation(forecast.precipitation),
+                places.area.precipitation_unit(),
+                forecast.wind_speed
+            );
+        }
+        return Ok(());
+    }
+
+    for &name in provider_names {
+        let provider = match places.weather.build_named(name) {
+            Ok(provider) => provider,
+            Err(e) => {
+                println!("{name}: unavailable ({e})");
+                continue;
+            }
+        };
+        for part in &parts {
+            match provider.forecast(&places.area, *part, None).await {
+                Ok(forecast) => println!(
+                    "{name} {part:?}: {} pop={}% precipitation={:.1}{} wind={:.1}m/s",
+                    forecast.icon(),
+                    forecast.pop,
+                    places.area.display_precipitation(forecast.precipitation),
+                    places.area.precipitation_unit(),
+                    forecast.wind_speed
+                ),
+                Err(e) => println!("{name} {part:?}: failed ({e})"),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().with_env_filter(tracing_subscriber::EnvFilter::from_default_env()).init();
+
+    let cli = Cli::parse();
+    let mut places = Places::read(&cli.places)?;
+    if let Some(timezone) = cli.timezone {
+        places.area.timezone = timezone;
+    }
+    jma::configure_cache(places.cache);
+    sunnyday::http::configure(places.http.clone());
+    for warning in places.unknown_shops() {
+        tracing::warn!(%warning, "unknown shop reference");
+    }
+    for warning in places.unknown_itinerary_places() {
+        tracing::warn!(%warning, "unknown itinerary place reference");
+    }
+    let mut recent = RecentPlace::read(&cli.recent)?;
+
+    let mood = cli.mood.unwrap_or(Mood {
+        walking: cli.walking.then_some(true),
+        food: cli.food.then_some(true),
+        // Driving somewhere without parking defeats the purpose.
+        parking: (cli.by == Some(TravelMode::Car)).then_some(true),
+        cycling: cli.cycling.then_some(true),
+        indoor: cli.indoor.then_some(true),
+        tags: cli.tag,
+        not_tags: cli.not_tag,
+        max_distance_km: cli.max_distance,
+        max_minutes: cli.max_minutes,
+        by: cli.by,
+        max_budget: cli.budget,
+        kids: cli.with_kids.then_some(true),
+        pets: cli.with_dog.then_some(true),
+        diet: cli.diet,
+        duration: cli.duration,
+        energy: cli.energy,
+        exclude: Vec::new(),
+    });
+
+    match cli.command {
+        Some(Command::Week) => run_week(&places, &mood, &mut recent, cli.verbose, cli.qr).await?,
+        Some(Command::Validate) => {
+            println!(
+                "{} looks good ({} places, {} shops, {} itineraries)",
+                cli.places,
+                places.place.len(),
+                places.shop.len(),
+                places.itinerary.len()
+            );
+        }
+        Some(Command::Rank) => {
+            let acceptance = places.learning.enabled.then(|| {
+                sunnyday::stats::acceptance_rates(&VisitLog::open(&cli.visits).read_all().unwrap_or_default())
+            });
+            for scored in places.rank(&mood, &recent, places.area.now().date_naive(), None, acceptance.as_ref()) {
+                println!("{:>6.2}  {}", scored.score, scored.place.name);
+            }
+        }
+        Some(Command::Accept { part }) => log_visit(&places, &recent, &cli.visits, part, VisitOutcome::Accepted).await?,
+        Some(Command::Skip { part }) => log_visit(&places, &recent, &cli.visits, part, VisitOutcome::Declined).await?,
+        Some(Command::Stats { command: Some(StatsCommand::Export { format: StatsExportFormat::Csv }), .. }) => {
+            let records = VisitLog::open(&cli.visits).read_all()?;
+            print!("{}", sunnyday::stats::to_csv(&records));
+        }
+        Some(Command::Stats { json, command: None }) => {
+            let records = VisitLog::open(&cli.visits).read_all()?;
+            let stats = Stats::compute(&records);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                print_stats(&stats);
+            }
+        }
+        Some(Command::Places { command: PlacesCommand::Export { format: ExportFormat::Geojson } }) => {
+            let geojson = sunnyday::geojson::to_feature_collection(&places);
+            println!("{}", serde_json::to_string_pretty(&geojson)?);
+        }
+        Some(Command::Places { command: PlacesCommand::Import { file } }) => {
+            let text = std::fs::read_to_string(&file).with_context(|| format!("reading {}", file.display()))?;
+            for imported in sunnyday::import::parse(&file, &text)? {
+                let walking = ask_yes_no(&format!("{}: walking?", imported.name));
+                let parking = ask_yes_no(&format!("{}: parking?", imported.name));
+                println!("{}", sunnyday::import::to_toml_fragment(&imported.into_place(walking, parking)));
+            }
+        }
+        Some(Command::Places { command: PlacesCommand::Pin { name } }) => {
+            if !places.place.iter().any(|p| p.name == name) {
+                eprintln!("warning: {name:?} doesn't match any place in {}", cli.places);
+            }
+            recent.pin(&name);
+            println!("pinned {name:?} for the next suggestion");
+        }
+        Some(Command::Places { command: PlacesCommand::Snooze { name, days } }) => {
+            if !places.place.iter().any(|p| p.name == name) {
+                eprintln!("warning: {name:?} doesn't match any place in {}", cli.places);
+            }
+            let until = places.area.now().date_naive() + chrono::Duration::days(i64::from(days));
+            recent.snooze(&name, until);
+            println!("snoozed {name:?} until {until}");
+        }
+        Some(Command::Recent { command: RecentCommand::Undo { part } }) => match recent.undo(part) {
+            Some((date, name)) => {
+                let removed_visit = VisitLog::open(&cli.visits).undo(date, part)?;
+                println!(
+                    "undid {name:?} for {part:?} on {date}{}",
+                    if removed_visit { " (and its visit log entry)" } else { "" }
+                );
+            }
+            None => println!("nothing to undo for {part:?}"),
+        },
+        Some(Command::Recent { command: RecentCommand::Export { output } }) => {
+            let text = serde_json::to_string_pretty(&recent)?;
+            match output {
+                Some(location) => write_location(&location, &text).await?,
+                None => println!("{text}"),
+            }
+        }
+        Some(Command::Recent { command: RecentCommand::Import { location } }) => {
+            let text = read_location(&location).await?;
+            let imported: RecentPlace = serde_json::from_str(&text).with_context(|| format!("parsing {location}"))?;
+            recent.merge(imported);
+            println!("merged history from {location}");
+        }
+        Some(Command::Places { command: PlacesCommand::Blacklist { name } }) => {
+            if !places.place.iter().any(|p| p.name == name) {
+                eprintln!("warning: {name:?} doesn't match any place in {}", cli.places);
+            }
+            recent.blacklist(&name);
+            println!("blacklisted {name:?}");
+        }
+        Some(Command::Places { command: PlacesCommand::GuessArea { lat, lon } }) => {
+            let (offices, class10s) = jma::area_codes_for(lat, lon, std::path::Path::new("area_cache.json")).await?;
+            println!("offices = {offices:?}");
+            println!("class10s = {class10s:?}");
+        }
+        Some(Command::Backtest { from, to }) => run_backtest(&places.area, from, to).await?,
+        Some(Command::Forecast { part, compare }) => run_forecast(&places, part, compare).await?,
+        Some(Command::Route { name }) => {
+            let place = places.place_by_name(&name).with_context(|| format!("no place named {name:?}"))?;
+            print!("{}", sunnyday::gpx::route_for(place)?);
+        }
+        Some(Command::Daemon { interval }) => {
+            if places.weather.provider_name() != "jma" {
+                bail!("daemon only has tiles to prefetch for provider \"jma\", not {:?}", places.weather.provider_name());
+            }
+            jma::run_tile_prefetch_daemon(places.area, std::time::Duration::from_secs(interval)).await;
+        }
+        None => {
+            let mut suggester = Suggester::from_parts(places, recent, VisitLog::open(&cli.visits), ForecastJournal::open(&cli.journal))?;
+            run_today(&mut suggester, &mood, cli.show_past, cli.verbose, cli.explain, cli.reroll, cli.qr).await?;
+            return suggester.save();
+        }
+    }
+
+    recent.save()?;
+    Ok(())
+}
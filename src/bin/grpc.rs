@@ -0,0 +1,174 @@
+//! `sunnyday-grpc`: the same suggestion logic as the console binary and
+//! `sunnyday-web`, served over gRPC (`Suggest`/`Forecast`/`RecordVisit`,
+//! see proto/suggestion.proto) instead of HTTP+JSON, for integrating
+//! sunnyday into a microservice home setup.
+
+use sunnyday::{amedas, Duration, Energy, ForecastJournal, Mood, PartOfDay, Places, RecentPlace, Suggester, TravelMode, VisitLog, VisitOutcome, VisitRecord};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+mod pb {
+    tonic::include_proto!("sunnyday.v1");
+}
+
+use pb::suggestion_service_server::{SuggestionService, SuggestionServiceServer};
+use pb::{ForecastRequest, ForecastResponse, RecordVisitRequest, RecordVisitResponse, SuggestRequest, SuggestResponse};
+
+fn parse_part(s: &str) -> Result<PartOfDay, Status> {
+    <PartOfDay as clap::ValueEnum>::from_str(s, true).map_err(Status::invalid_argument)
+}
+
+fn parse_outcome(s: &str) -> Result<VisitOutcome, Status> {
+    serde_json::from_value(serde_json::Value::String(s.to_lowercase())).map_err(|_| Status::invalid_argument(format!("invalid outcome: {s}")))
+}
+
+fn split_tags(tags: &[String]) -> Vec<String> {
+    tags.iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect()
+}
+
+fn mood_from_request(req: &SuggestRequest) -> Result<Mood, Status> {
+    let by = req
+        .by
+        .as_deref()
+        .map(|s| <TravelMode as clap::ValueEnum>::from_str(s, true))
+        .transpose()
+        .map_err(Status::invalid_argument)?;
+    let duration = req
+        .duration
+        .as_deref()
+        .map(|s| <Duration as clap::ValueEnum>::from_str(s, true))
+        .transpose()
+        .map_err(Status::invalid_argument)?;
+    let energy = req
+        .energy
+        .as_deref()
+        .map(|s| <Energy as clap::ValueEnum>::from_str(s, true))
+        .transpose()
+        .map_err(Status::invalid_argument)?;
+    Ok(Mood {
+        tags: split_tags(&req.tags),
+        not_tags: split_tags(&req.not_tags),
+        max_distance_km: req.max_distance_km,
+        max_minutes: req.max_minutes,
+        // Driving somewhere without parking defeats the purpose.
+        parking: (by == Some(TravelMode::Car)).then_some(true),
+        by,
+        max_budget: req.budget.map(|b| b as u8),
+        indoor: req.indoor.then_some(true),
+        kids: req.with_kids.then_some(true),
+        pets: req.with_dog.then_some(true),
+        diet: split_tags(&req.diet),
+        duration,
+        energy,
+        ..Mood::default()
+    })
+}
+
+/// Best-effort current-conditions check, same caveat as the same-named
+/// helper in `src/bin/home.rs`: failures read as "not raining" rather than
+/// failing the whole call.
+async fn is_raining_now(places: &Places) -> bool {
+    let client = sunnyday::http::client();
+    let Ok(station) = amedas::nearest_station(&client, places.area.lat, places.area.lon).await else {
+        return false;
+    };
+    amedas::latest_observation(&client, &station).await.map(|o| o.is_raining()).unwrap_or(false)
+}
+
+/// Implements the three RPCs against `place.toml`/`recent.toml`/
+/// `visits.jsonl`/`forecast_journal.jsonl` in the working directory -- the
+/// same files the console binary and `sunnyday-web` use.
+#[derive(Default)]
+struct Service;
+
+#[tonic::async_trait]
+impl SuggestionService for Service {
+    async fn suggest(&self, request: Request<SuggestRequest>) -> Result<Response<SuggestResponse>, Status> {
+        let req = request.into_inner();
+        let part = parse_part(&req.part)?;
+        let mood = mood_from_request(&req)?;
+
+        let places = Places::read("place.toml").map_err(|e| Status::internal(format!("loading place.toml: {e}")))?;
+        let recent = RecentPlace::read("recent.toml").map_err(|e| Status::internal(format!("loading recent.toml: {e}")))?;
+        let mut suggester = Suggester::from_parts(places, recent, VisitLog::open("visits.jsonl"), ForecastJournal::open("forecast_journal.jsonl"))
+            .map_err(|e| Status::internal(format!("setting up weather provider: {e}")))?;
+
+        let suggestion = suggester
+            .suggest(part, &mood, false, req.reroll)
+            .await
+            .map_err(|e| Status::internal(format!("suggesting: {e}")))?;
+
+        let (itinerary_name, itinerary_legs) = match &suggestion.itinerary {
+            Some((itinerary, legs)) => (Some(itinerary.name.clone()), legs.iter().map(|p| p.name.clone()).collect()),
+            None => (None, Vec::new()),
+        };
+        let place_name = suggestion.place.as_ref().map(|p| p.name.clone());
+        let _ = suggester.save();
+
+        Ok(Response::new(SuggestResponse {
+            walkable: suggestion.walkable,
+            pop: suggestion.forecast.pop,
+            precipitation: suggestion.forecast.precipitation,
+            place_name,
+            itinerary_name,
+            itinerary_legs,
+            explanation: Some(suggestion.reasoning.join("\n")),
+        }))
+    }
+
+    async fn forecast(&self, request: Request<ForecastRequest>) -> Result<Response<ForecastResponse>, Status> {
+        let req = request.into_inner();
+        let part = parse_part(&req.part)?;
+
+        let places = Places::read("place.toml").map_err(|e| Status::internal(format!("loading place.toml: {e}")))?;
+        let provider = places.weather.build().map_err(|e| Status::internal(format!("setting up weather provider: {e}")))?;
+        let forecast = provider
+            .forecast(&places.area, part, None)
+            .await
+            .map_err(|e| Status::internal(format!("fetching forecast: {e}")))?;
+
+        Ok(Response::new(ForecastResponse { pop: forecast.pop, precipitation: forecast.precipitation, wind_speed: forecast.wind_speed }))
+    }
+
+    async fn record_visit(&self, request: Request<RecordVisitRequest>) -> Result<Response<RecordVisitResponse>, Status> {
+        let req = request.into_inner();
+        let part = parse_part(&req.part)?;
+        let outcome = parse_outcome(&req.outcome)?;
+
+        let places = Places::read("place.toml").map_err(|e| Status::internal(format!("loading place.toml: {e}")))?;
+        let recent = RecentPlace::read("recent.toml").map_err(|e| Status::internal(format!("loading recent.toml: {e}")))?;
+        let date = places.area.now().date_naive();
+        let Some(place) = recent.last_for(date, part) else {
+            return Err(Status::not_found("no suggestion recorded for that part today"));
+        };
+        let place = place.to_string();
+
+        let provider = places.weather.build().map_err(|e| Status::internal(format!("setting up weather provider: {e}")))?;
+        let forecast = provider
+            .forecast(&places.area, part, None)
+            .await
+            .map_err(|e| Status::internal(format!("fetching forecast: {e}")))?;
+        let walkable = !is_raining_now(&places).await;
+        let record = VisitRecord { date, part, place, outcome, walkable, pop: forecast.pop, precipitation: forecast.precipitation, wind_speed: forecast.wind_speed };
+
+        VisitLog::open("visits.jsonl").append(&record).map_err(|e| Status::internal(format!("recording visit: {e}")))?;
+
+        Ok(Response::new(RecordVisitResponse { place: record.place }))
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt().with_env_filter(tracing_subscriber::EnvFilter::from_default_env()).init();
+
+    if let Ok(places) = Places::read("place.toml") {
+        sunnyday::jma::configure_cache(places.cache);
+        sunnyday::http::configure(places.http);
+    }
+
+    let addr = std::env::var("SUNNYDAY_GRPC_ADDR").unwrap_or_else(|_| "127.0.0.1:50051".to_string()).parse()?;
+    tracing::info!(%addr, "sunnyday-grpc listening");
+    let mut server = Server::builder();
+    server.add_service(SuggestionServiceServer::new(Service)).serve(addr).await?;
+    Ok(())
+}
@@ -0,0 +1,1628 @@
+//! `sunnyday-web`: the same suggestion logic as the CLI, served over HTTP.
+
+use std::collections::HashMap;
+
+use askama::Template;
+use async_trait::async_trait;
+use axum::extract::{Path, Query};
+use axum::response::{Html, IntoResponse, Json};
+use axum::routing::{get, post};
+use axum::Router;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "notify")]
+use sunnyday::calendar;
+use sunnyday::{
+    airquality, amedas, jma, share, typhoon, warning, wbgt, Duration, Energy, ForecastJournal, Mood, PartOfDay, Places, RecentPlace,
+    Suggester, TravelMode, VisitLog, VisitOutcome, VisitRecord, ALL_DAY,
+};
+
+/// Command-line arguments. Everything else is configured through
+/// `place.toml`/env vars, not flags.
+#[derive(Parser)]
+#[command(name = "sunnyday-web", about = "Serve suggestions over HTTP")]
+struct Cli {
+    /// Directory to read/write `place.toml` (unless overridden by
+    /// [`PLACES_TOML_ENV`]/[`PLACES_PATH_ENV`]) and `recent.toml`/
+    /// `visits.jsonl`/`forecast_journal.jsonl` under, instead of the
+    /// working directory -- equivalent to setting [`STATE_DIR_ENV`]
+    /// directly, but handier on a systemd `ExecStart=` line or a
+    /// container's command.
+    #[arg(long)]
+    state_dir: Option<String>,
+    /// Print an example systemd `.socket`/`.service` unit pair (see
+    /// `sunnyday::systemd::example_units`) and exit without starting the
+    /// server.
+    #[arg(long)]
+    print_systemd_unit: bool,
+}
+
+/// Query parameters for `/`.
+#[derive(Deserialize, Default)]
+struct HomeParams {
+    /// Still include parts of the day that have already passed, marked
+    /// "(past)", instead of silently skipping them.
+    #[serde(default)]
+    show_past: bool,
+    /// Comma-separated tags a suggestion must have, e.g. `?tag=park,kids`.
+    #[serde(default)]
+    tag: Option<String>,
+    /// Comma-separated tags a suggestion must not have.
+    #[serde(default)]
+    not_tag: Option<String>,
+    /// Only suggest places within this many km of `[home]` (see
+    /// place.toml).
+    #[serde(default)]
+    max_distance: Option<f64>,
+    /// Only suggest places within this many minutes of `[home]`.
+    #[serde(default)]
+    max_minutes: Option<f64>,
+    /// How you're getting around today, e.g. `?by=car`.
+    #[serde(default)]
+    by: Option<TravelMode>,
+    /// Only suggest places with a `price` at or below this (1-4).
+    #[serde(default)]
+    budget: Option<u8>,
+    /// Only suggest places suitable for bringing children along.
+    #[serde(default)]
+    with_kids: bool,
+    /// Only suggest places suitable for bringing a pet along.
+    #[serde(default)]
+    with_dog: bool,
+    /// Comma-separated dietary options a shop must cater to, e.g.
+    /// `?diet=vegetarian,halal`.
+    #[serde(default)]
+    diet: Option<String>,
+    /// Only suggest places matching this typical visit length, e.g.
+    /// `?duration=short`.
+    #[serde(default)]
+    duration: Option<Duration>,
+    /// Only suggest places matching this energy level, e.g. `?energy=lazy`.
+    #[serde(default)]
+    energy: Option<Energy>,
+    /// Set every mood dimension at once via a compact string, e.g.
+    /// `?mood=food=yes,walking=no` (see `sunnyday::Mood`'s `FromStr` for
+    /// the full key list). Overrides all the other mood params when given.
+    #[serde(default)]
+    mood: Option<Mood>,
+    /// Only suggest indoor places. A "stay in" part already requires this
+    /// regardless.
+    #[serde(default)]
+    indoor: bool,
+    /// Pick again even if a part of day already has a suggestion decided
+    /// for today, instead of returning that same one, e.g. `?reroll=true`.
+    #[serde(default)]
+    reroll: bool,
+    /// Comma-separated place/itinerary names to veto outright, regardless
+    /// of mood -- the page's "another one" button re-requests with the
+    /// suggestion it just showed added here, paired with `reroll=true`.
+    #[serde(default)]
+    exclude: Option<String>,
+}
+
+/// Overrides where `/`/`/history` load their templates from, e.g. a
+/// restyled `home.html` dropped next to the binary -- parallel to
+/// `[weather]`'s own `SUNNYDAY_PROVIDER`/`SUNNYDAY_FIXTURE_DIR` overrides
+/// in `sunnyday::config`. Checked on every request, not just at startup,
+/// so editing the override doesn't need a restart.
+const TEMPLATE_DIR_ENV: &str = "SUNNYDAY_TEMPLATE_DIR";
+
+/// Directory `place.toml`/`recent.toml`/`visits.jsonl`/
+/// `forecast_journal.jsonl` are read/written under, instead of the
+/// working directory -- set directly, or via `--state-dir` (see [`Cli`]).
+/// Doesn't affect the place list when [`PLACES_TOML_ENV`]/
+/// [`PLACES_PATH_ENV`] are set instead.
+const STATE_DIR_ENV: &str = "SUNNYDAY_STATE_DIR";
+
+/// The place list as literal TOML, instead of a path to read it from --
+/// for running with no file on disk at all (e.g. mounted as a single env
+/// var in a container). Takes priority over [`PLACES_PATH_ENV`] and
+/// `STATE_DIR_ENV`. `[area]`'s own fields (offices, lat/lon, ...) are
+/// configured the normal way, as part of this same blob -- there's no
+/// separate per-field env var scheme.
+///
+/// `include` isn't supported here (see [`sunnyday::Places::parse_toml`]),
+/// since there's no directory to resolve it relative to.
+const PLACES_TOML_ENV: &str = "SUNNYDAY_PLACES_TOML";
+
+/// Path to read the place list from, instead of `place.toml` under
+/// [`STATE_DIR_ENV`] -- for a file mounted somewhere else entirely (e.g. a
+/// container secret/configmap path). Ignored if [`PLACES_TOML_ENV`] is
+/// set.
+const PLACES_PATH_ENV: &str = "SUNNYDAY_PLACES_PATH";
+
+/// Port to listen on, instead of 3000. Irrelevant under systemd socket
+/// activation, where the listening socket is already bound; see
+/// `sunnyday::systemd::listener_from_env`.
+const PORT_ENV: &str = "SUNNYDAY_PORT";
+
+/// Resolve `name` against `STATE_DIR_ENV`, or just `name` itself in the
+/// working directory if that's unset.
+fn state_path(name: &str) -> std::path::PathBuf {
+    match std::env::var(STATE_DIR_ENV) {
+        Ok(dir) => std::path::Path::new(&dir).join(name),
+        Err(_) => std::path::PathBuf::from(name),
+    }
+}
+
+/// Load the place list the way every handler needs it: from
+/// `PLACES_TOML_ENV` if set, else from the path `PLACES_PATH_ENV` names,
+/// else `place.toml` under `STATE_DIR_ENV` (or the working directory).
+fn load_places() -> anyhow::Result<Places> {
+    if let Ok(toml) = std::env::var(PLACES_TOML_ENV) {
+        return Places::parse_toml(&toml);
+    }
+    let path = std::env::var(PLACES_PATH_ENV).map(std::path::PathBuf::from).unwrap_or_else(|_| state_path("place.toml"));
+    Places::read(path)
+}
+
+/// How long a cached entry in [`USER_PLACES_CACHE`] is served without
+/// re-reading its file -- long enough that browsing around a friend's
+/// page doesn't re-parse their `places/{user}.toml` on every request, short
+/// enough that an edit shows up without restarting the server.
+const USER_PLACES_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Per-user [`Places`], lazily loaded and cached for multi-tenant mode
+/// (`/u/{user}/`, see [`user_place_handler`]) -- one server instance
+/// serving several friends' configurations, each in their own
+/// `places/{user}.toml`/`recent/{user}.toml` under [`STATE_DIR_ENV`],
+/// without running a process per friend. Only the suggestion page and its
+/// accept/skip action are covered this way; the rest of the API
+/// (`/api/week`, maps, history, ...) stays single-tenant, since nobody's
+/// asked to share those yet and duplicating every route would be a lot of
+/// surface for no current use.
+static USER_PLACES_CACHE: std::sync::LazyLock<std::sync::Mutex<HashMap<String, (Places, std::time::Instant)>>> =
+    std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Whether `user` (a `/u/{user}/...` path segment, already percent-decoded
+/// by axum by the time a handler sees it) is safe to interpolate into
+/// `user_places_path`/`user_recent_path`/`user_visits_path`. Every
+/// multi-tenant handler must check this *before* calling any of those --
+/// anything else (`..`, `/`, a bare empty string) would let a crafted
+/// request read or write files outside `STATE_DIR_ENV`.
+fn valid_user(user: &str) -> bool {
+    !user.is_empty() && user.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Where `user`'s place list lives in multi-tenant mode.
+fn user_places_path(user: &str) -> std::path::PathBuf {
+    state_path(&format!("places/{user}.toml"))
+}
+
+/// Where `user`'s rotation history lives in multi-tenant mode. Unlike
+/// `places/`, which holds files the operator provides up front, `recent/`
+/// is purely state this binary writes -- create it on first use, the same
+/// as `RecentPlace`/`VisitLog` already create the file itself.
+fn user_recent_path(user: &str) -> std::path::PathBuf {
+    let path = state_path(&format!("recent/{user}.toml"));
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    path
+}
+
+/// Where `user`'s visit log lives in multi-tenant mode; see
+/// [`user_recent_path`].
+fn user_visits_path(user: &str) -> std::path::PathBuf {
+    let path = state_path(&format!("visits/{user}.jsonl"));
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    path
+}
+
+/// Load `user`'s place list, serving a cached copy younger than
+/// [`USER_PLACES_CACHE_TTL`] instead of re-reading/re-parsing the file.
+fn load_user_places(user: &str) -> anyhow::Result<Places> {
+    if let Some((places, loaded_at)) = USER_PLACES_CACHE.lock().unwrap().get(user) {
+        if loaded_at.elapsed() < USER_PLACES_CACHE_TTL {
+            return Ok(places.clone());
+        }
+    }
+    let places = Places::read(user_places_path(user))?;
+    USER_PLACES_CACHE.lock().unwrap().insert(user.to_string(), (places.clone(), std::time::Instant::now()));
+    Ok(places)
+}
+
+/// Render `template` via its compiled-in askama template, unless
+/// `TEMPLATE_DIR_ENV` names a directory containing a file called `name` --
+/// askama bakes templates into the binary at compile time, so an override
+/// is instead rendered by `minijinja` against the same context, falling
+/// back to the embedded template if the override is missing or fails to
+/// read.
+fn render<T: askama::Template + Serialize>(name: &str, template: &T) -> String {
+    let overridden = std::env::var(TEMPLATE_DIR_ENV)
+        .ok()
+        .and_then(|dir| std::fs::read_to_string(std::path::Path::new(&dir).join(name)).ok())
+        .map(|source| render_override(name, &source, template));
+    match overridden {
+        Some(Ok(html)) => html,
+        Some(Err(e)) => format!("template override error: {e}"),
+        None => template.render().unwrap_or_else(|e| format!("template error: {e}")),
+    }
+}
+
+/// `minijinja` rendering for [`render`]'s override path -- a fresh
+/// [`minijinja::Environment`] per call, since the override file can change
+/// between requests.
+fn render_override<T: Serialize>(name: &str, source: &str, template: &T) -> Result<String, minijinja::Error> {
+    let mut env = minijinja::Environment::new();
+    env.add_template(name, source)?;
+    env.get_template(name)?.render(template)
+}
+
+/// Split a comma-separated query param into tags, dropping empty entries.
+fn split_tags(tags: &Option<String>) -> Vec<String> {
+    tags.as_deref()
+        .map(|s| s.split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Build a [`Mood`] from `params`'s individual fields, unless `params.mood`
+/// was given outright (which overrides all the others at once). Shared by
+/// every handler that builds a mood from query params.
+fn mood_from_params(params: &HomeParams) -> Mood {
+    params.mood.clone().unwrap_or(Mood {
+        tags: split_tags(&params.tag),
+        not_tags: split_tags(&params.not_tag),
+        max_distance_km: params.max_distance,
+        max_minutes: params.max_minutes,
+        // Driving somewhere without parking defeats the purpose.
+        parking: (params.by == Some(TravelMode::Car)).then_some(true),
+        by: params.by,
+        max_budget: params.budget,
+        indoor: params.indoor.then_some(true),
+        kids: params.with_kids.then_some(true),
+        pets: params.with_dog.then_some(true),
+        diet: split_tags(&params.diet),
+        duration: params.duration,
+        energy: params.energy,
+        exclude: split_tags(&params.exclude),
+        ..Mood::default()
+    })
+}
+
+/// The bits of a suggested [`sunnyday::Place`] the template shows, plus the
+/// concrete [`sunnyday::Shop`] picked at it, if any.
+#[derive(Serialize)]
+struct PlaceSuggestion {
+    name: String,
+    note: Option<String>,
+    url: Option<String>,
+    map_url: Option<String>,
+    /// `/api/map.png` URL for an OSM tile thumbnail marking this place, if
+    /// it has its own coordinates (see `sunnyday::Place::lat_lon`).
+    thumbnail_url: Option<String>,
+    /// `/api/qr.png` URL for a QR code encoding `map_url`, for scanning the
+    /// suggestion straight onto a phone, if `map_url` is set.
+    qr_url: Option<String>,
+    shop: Option<String>,
+}
+
+impl PlaceSuggestion {
+    fn new(place: sunnyday::Place, shop: Option<sunnyday::Shop>) -> Self {
+        let thumbnail_url = place.lat_lon().map(|(lat, lon)| format!("/api/map.png?lat={lat}&lon={lon}"));
+        let qr_url = place.map_url.as_ref().map(|url| {
+            format!("/api/qr.png?url={}", percent_encoding::utf8_percent_encode(url, percent_encoding::NON_ALPHANUMERIC))
+        });
+        PlaceSuggestion {
+            name: place.name,
+            note: place.note,
+            url: place.url,
+            map_url: place.map_url,
+            thumbnail_url,
+            qr_url,
+            shop: shop.map(|s| s.name),
+        }
+    }
+}
+
+/// A suggested [`sunnyday::Itinerary`], with its legs resolved to names.
+#[derive(Serialize)]
+struct ItinerarySuggestion {
+    name: String,
+    legs: Vec<String>,
+}
+
+/// Template-friendly form of [`sunnyday::Exclusion`], for the "why?"
+/// collapsible section.
+#[derive(Serialize)]
+struct ExcludedPlace {
+    place: String,
+    reason: String,
+}
+
+/// Template-friendly form of [`sunnyday::Reasoning`], shown in a collapsible
+/// `<details>` section so "why did it pick that?" doesn't clutter the page
+/// by default.
+#[derive(Serialize)]
+struct Explanation {
+    precipitation: f64,
+    precipitation_threshold: f64,
+    precipitation_unit: &'static str,
+    wind_speed: f64,
+    wind_threshold: f64,
+    excluded: Vec<ExcludedPlace>,
+}
+
+impl From<sunnyday::Reasoning> for Explanation {
+    fn from(reasoning: sunnyday::Reasoning) -> Self {
+        Explanation {
+            precipitation: reasoning.precipitation,
+            precipitation_threshold: reasoning.precipitation_threshold,
+            precipitation_unit: reasoning.precipitation_unit,
+            wind_speed: reasoning.wind_speed,
+            wind_threshold: reasoning.wind_threshold,
+            excluded: reasoning.excluded.into_iter().map(|e| ExcludedPlace { place: e.place, reason: e.reason }).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PartSuggestion {
+    part: String,
+    /// Lowercase form of `part`, matching `sunnyday::PartOfDay`'s
+    /// serialization -- what `/api/visit`'s `part` query param expects.
+    part_param: String,
+    past: bool,
+    walkable: bool,
+    /// `false` when the weather lookup for this part failed and the
+    /// suggestion was picked by mood alone -- see
+    /// [`sunnyday::Suggestion::weather_available`]. `pop`/`precipitation`
+    /// are meaningless (zeroed) in that case; the template shows a banner
+    /// instead of them.
+    weather_available: bool,
+    pop: u32,
+    precipitation: f64,
+    precipitation_unit: &'static str,
+    icon: &'static str,
+    place: Option<PlaceSuggestion>,
+    itinerary: Option<ItinerarySuggestion>,
+    /// The place or itinerary name currently shown, if any -- what
+    /// "another one" adds to `?exclude=` on its re-request.
+    suggestion_name: Option<String>,
+    explanation: Option<Explanation>,
+}
+
+#[derive(Template, Serialize)]
+#[template(path = "home.html")]
+struct HomeTemplate {
+    raining_now: bool,
+    warnings: Vec<String>,
+    /// Today's conditions combined into one icon via
+    /// [`sunnyday::provider::WeatherCondition::combine`] -- `None` if every
+    /// part of the day has already passed (or failed to load).
+    today_icon: Option<&'static str>,
+    parts: Vec<PartSuggestion>,
+    himawari_url: Option<String>,
+    wbgt: Option<f64>,
+    typhoon_advisory: Option<(String, f64)>,
+    pm25: Option<f64>,
+    /// Prefix for this page's own `/api/...` calls -- empty for the
+    /// default single-tenant page, `/u/{user}` in multi-tenant mode, so
+    /// the "Go" button records the visit against the right friend's log
+    /// instead of always the default one.
+    api_base: String,
+}
+
+/// Best-effort current-conditions check; see the console binary for why
+/// failures are swallowed rather than surfaced as page errors.
+async fn is_raining_now(places: &Places) -> bool {
+    let client = sunnyday::http::client();
+    let Ok(station) = amedas::nearest_station(&client, places.area.lat, places.area.lon).await else {
+        return false;
+    };
+    amedas::latest_observation(&client, &station)
+        .await
+        .map(|o| o.is_raining())
+        .unwrap_or(false)
+}
+
+/// Best-effort forecast lookup for `/api/visit`; a fresh lookup, not
+/// whatever `suggest` originally saw, same caveat as `is_raining_now`.
+async fn current_forecast(places: &Places, part: PartOfDay) -> sunnyday::provider::Forecast {
+    let fallback = sunnyday::provider::Forecast { pop: 0, precipitation: 0.0, wind_speed: 0.0 };
+    let Ok(provider) = places.weather.build() else {
+        return fallback;
+    };
+    provider.forecast(&places.area, part, None).await.unwrap_or(fallback)
+}
+
+/// How long a completed forecast fetch is still shared with requests that
+/// show up just after it finishes, on top of sharing it with requests
+/// that were already waiting on it -- e.g. a page auto-refresh a few
+/// seconds after someone else's load already paid for the fetch. Once a
+/// cached value is older than this, [`CoalescingProvider`] still serves it
+/// immediately (see [`FORECAST_CACHE`]) but kicks off a background refresh.
+const FORECAST_DEBOUNCE: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How often the background task spawned at startup re-checks whether
+/// `jma`'s tile cache needs refreshing; see
+/// [`sunnyday::jma::run_tile_prefetch_daemon`].
+const TILE_PREFETCH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+type ForecastKey = (String, String, PartOfDay);
+type ForecastEntry = (std::sync::Arc<tokio::sync::OnceCell<sunnyday::provider::Forecast>>, std::time::Instant);
+
+/// Process-wide single-flight table backing [`CoalescingProvider`]: dedupes
+/// concurrent real fetches for the same (area, part), whether triggered by a
+/// cold-start request blocking on one or a stale entry's background refresh.
+/// Bounded by the number of distinct (area, part) combinations this
+/// process ever sees -- one place list, so at most a handful of entries.
+static FORECAST_INFLIGHT: std::sync::LazyLock<std::sync::Mutex<HashMap<ForecastKey, ForecastEntry>>> = std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Process-wide last-known-good forecast per (area, part), backing
+/// [`CoalescingProvider`]'s stale-while-revalidate serving: a request never
+/// blocks on a real fetch once *something* has been cached for its key, even
+/// past `FORECAST_DEBOUNCE` -- only the very first request for a key (or one
+/// after the process restarts) pays for a synchronous fetch.
+static FORECAST_CACHE: std::sync::LazyLock<std::sync::Mutex<HashMap<ForecastKey, ForecastEntry>>> = std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Wraps a [`sunnyday::WeatherProvider`] with stale-while-revalidate caching
+/// keyed on (area, part): the first request for a key blocks on a real
+/// fetch (deduped against concurrent twins via [`FORECAST_INFLIGHT`]), but
+/// every later request -- even once the cached value is older than
+/// `FORECAST_DEBOUNCE`, e.g. a new JMA basetime having appeared -- gets it
+/// back immediately while a background task refreshes it for whoever asks
+/// next. Bypassed for `lat_lon`-specific lookups (itinerary legs at their
+/// own coordinates) -- those vary per place, so caching wouldn't help.
+/// Keys only on area/part, not the JMA tile run's own basetime -- nothing
+/// reaches this wrapper with one, only whatever
+/// [`sunnyday::WeatherProvider::forecast`]'s generic signature carries.
+struct CoalescingProvider {
+    inner: std::sync::Arc<dyn sunnyday::WeatherProvider>,
+}
+
+/// Fetches `area`/`part` through `inner`, deduping against any fetch
+/// already in flight for the same key via [`FORECAST_INFLIGHT`], and caches
+/// a successful result in [`FORECAST_CACHE`] for later stale-while-revalidate
+/// reads. Shared by [`CoalescingProvider::forecast`]'s cold-start path and
+/// its background-refresh task -- both just need "the real fetch, only
+/// once, please."
+async fn fetch_and_cache(inner: &std::sync::Arc<dyn sunnyday::WeatherProvider>, key: ForecastKey, area: &jma::AreaCode, part: PartOfDay) -> anyhow::Result<sunnyday::provider::Forecast> {
+    let cell = {
+        let mut inflight = FORECAST_INFLIGHT.lock().unwrap();
+        let stale = inflight.get(&key).map(|(_, started)| started.elapsed() >= FORECAST_DEBOUNCE).unwrap_or(true);
+        if stale {
+            inflight.insert(key.clone(), (std::sync::Arc::new(tokio::sync::OnceCell::new()), std::time::Instant::now()));
+        }
+        inflight.get(&key).unwrap().0.clone()
+    };
+    let forecast = *cell.get_or_try_init(|| inner.forecast(area, part, None)).await?;
+    FORECAST_CACHE.lock().unwrap().insert(key, (std::sync::Arc::new(tokio::sync::OnceCell::new_with(Some(forecast))), std::time::Instant::now()));
+    Ok(forecast)
+}
+
+/// Background half of stale-while-revalidate: re-fetches `area`/`part` and
+/// updates [`FORECAST_CACHE`] on success. Runs detached from the request
+/// that triggered it, so a failure here just means the stale value keeps
+/// being served until a later refresh succeeds -- logged, not surfaced,
+/// same best-effort spirit as `active_warnings`/`current_wbgt`.
+fn refresh_in_background(inner: std::sync::Arc<dyn sunnyday::WeatherProvider>, key: ForecastKey, area: jma::AreaCode, part: PartOfDay) {
+    tokio::spawn(async move {
+        if let Err(e) = fetch_and_cache(&inner, key, &area, part).await {
+            tracing::warn!("background forecast refresh for {area:?}/{part:?} failed: {e:#}");
+        }
+    });
+}
+
+#[async_trait]
+impl sunnyday::WeatherProvider for CoalescingProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn forecast(&self, area: &jma::AreaCode, part: PartOfDay, lat_lon: Option<(f64, f64)>) -> anyhow::Result<sunnyday::provider::Forecast> {
+        if lat_lon.is_some() {
+            return self.inner.forecast(area, part, lat_lon).await;
+        }
+        let key: ForecastKey = (area.offices.clone(), area.class10s.clone(), part);
+        let cached = FORECAST_CACHE.lock().unwrap().get(&key).cloned();
+        if let Some((cell, cached_at)) = cached {
+            let forecast = *cell.get().expect("FORECAST_CACHE entries are only ever inserted already-populated");
+            if cached_at.elapsed() >= FORECAST_DEBOUNCE {
+                refresh_in_background(self.inner.clone(), key, area.clone(), part);
+            }
+            return Ok(forecast);
+        }
+        fetch_and_cache(&self.inner, key, area, part).await
+    }
+}
+
+/// Build a [`Suggester`] over `places`/`recent`, with its weather provider
+/// wrapped in [`CoalescingProvider`] -- shared by every handler that needs
+/// a suggestion, so they all benefit from the same stale-while-revalidate
+/// caching.
+fn build_suggester(places: Places, recent: RecentPlace) -> anyhow::Result<Suggester> {
+    let provider = Box::new(CoalescingProvider { inner: std::sync::Arc::from(places.weather.build()?) });
+    Ok(Suggester::from_parts_with_provider(
+        places,
+        recent,
+        VisitLog::open(state_path("visits.jsonl")),
+        ForecastJournal::open(state_path("forecast_journal.jsonl")),
+        provider,
+    ))
+}
+
+/// Best-effort active-warnings check; see `is_raining_now` for why failures
+/// are swallowed rather than surfaced as a page error.
+async fn active_warnings(places: &Places) -> Vec<warning::Warning> {
+    let client = sunnyday::http::client();
+    warning::active_warnings(&client, &places.area.offices).await.unwrap_or_default()
+}
+
+/// Best-effort current WBGT heat-stress reading, skipped outright outside
+/// `wbgt::in_season`'s window; see `is_raining_now` for why failures are
+/// otherwise swallowed rather than surfaced as a page error.
+async fn current_wbgt(places: &Places) -> Option<f64> {
+    if !wbgt::in_season(places.area.now().date_naive()) {
+        return None;
+    }
+    let client = sunnyday::http::client();
+    let station = wbgt::nearest_station(&client, places.area.lat, places.area.lon).await.ok()?;
+    wbgt::current_wbgt(&client, &station).await.ok()
+}
+
+/// Best-effort typhoon-track advisory check: `None` unless a typhoon's
+/// forecast track comes within `[area] typhoon_distance_km`, which also
+/// gates whether this bothers fetching at all; see `is_raining_now` for
+/// why failures are otherwise swallowed rather than surfaced as a page
+/// error.
+async fn current_typhoon_advisory(places: &Places) -> Option<(String, f64)> {
+    let limit = places.area.typhoon_distance_km?;
+    let client = sunnyday::http::client();
+    let (name, km) = typhoon::nearest_approach(&client, places.area.lat, places.area.lon).await.ok()??;
+    (km <= limit).then_some((name, km))
+}
+
+/// Best-effort current PM2.5 reading, skipped outright when `[area]
+/// max_pm25` is unset; see `is_raining_now` for why failures are
+/// otherwise swallowed rather than surfaced as a page error.
+async fn current_pm25(places: &Places) -> Option<f64> {
+    places.area.max_pm25?;
+    let client = sunnyday::http::client();
+    airquality::current_pm25(&client, places.area.lat, places.area.lon).await.ok()
+}
+
+async fn place_handler(Query(params): Query<HomeParams>) -> impl IntoResponse {
+    render_place_page(load_places(), RecentPlace::read(state_path("recent.toml")), String::new(), params).await
+}
+
+/// Per-friend equivalent of `place_handler`, for [multi-tenant mode](
+/// user_places_path); see there for what's and isn't covered.
+async fn user_place_handler(Path(user): Path<String>, Query(params): Query<HomeParams>) -> impl IntoResponse {
+    if !valid_user(&user) {
+        return (axum::http::StatusCode::BAD_REQUEST, "invalid user".to_string()).into_response();
+    }
+    let api_base = format!("/u/{user}");
+    render_place_page(load_user_places(&user), RecentPlace::read(user_recent_path(&user)), api_base, params).await
+}
+
+/// Shared body of `place_handler`/`user_place_handler`: everything past
+/// loading the place list and recent-place state is identical either way.
+/// `api_base` is this page's own `/api/...` prefix -- see
+/// `HomeTemplate::api_base`.
+async fn render_place_page(
+    places: anyhow::Result<Places>,
+    recent: anyhow::Result<RecentPlace>,
+    api_base: String,
+    params: HomeParams,
+) -> axum::response::Response {
+    let places = match places {
+        Ok(p) => p,
+        Err(e) => return Html(format!("failed to load place list: {e}")).into_response(),
+    };
+    for warning in places.unknown_shops() {
+        tracing::warn!(%warning, "unknown shop reference");
+    }
+    for warning in places.unknown_itinerary_places() {
+        tracing::warn!(%warning, "unknown itinerary place reference");
+    }
+    let recent = match recent {
+        Ok(r) => r,
+        Err(e) => return Html(format!("failed to load recent-place state: {e}")).into_response(),
+    };
+    let active = active_warnings(&places).await;
+    let wbgt = current_wbgt(&places).await;
+    let wbgt_exceeded = match (wbgt, places.area.wbgt_limit) {
+        (Some(value), Some(limit)) => value >= limit,
+        _ => false,
+    };
+    let typhoon_advisory = current_typhoon_advisory(&places).await;
+    let pm25 = current_pm25(&places).await;
+    let pm25_exceeded = match (pm25, places.area.max_pm25) {
+        (Some(value), Some(limit)) => value >= limit,
+        _ => false,
+    };
+    let stay_in_forced = (places.area.respect_warnings && active.iter().any(|w| w.forces_stay_in))
+        || wbgt_exceeded
+        || typhoon_advisory.is_some()
+        || pm25_exceeded;
+    let warnings = active.iter().map(|w| w.name.clone()).collect();
+    let raining_now = is_raining_now(&places).await;
+    let himawari_url = jma::himawari_tile_url(&places.area, places.area.lat, places.area.lon).await.ok();
+
+    let mut suggester = match build_suggester(places, recent) {
+        Ok(s) => s,
+        Err(e) => return Html(format!("failed to set up weather provider: {e}")).into_response(),
+    };
+    let mood = mood_from_params(&params);
+    let now = suggester.places.area.now();
+
+    let upcoming_parts: Vec<PartOfDay> = ALL_DAY.into_iter().filter(|&part| !suggester.places.area.part_has_passed(part, now)).collect();
+    suggester.prefetch(&upcoming_parts).await;
+
+    let mut parts = Vec::new();
+    let mut today_conditions = Vec::new();
+    for part in ALL_DAY {
+        let label = format!("{:?}", part);
+        if suggester.places.area.part_has_passed(part, now) {
+            if params.show_past {
+                parts.push(PartSuggestion {
+                    part_param: label.to_lowercase(),
+                    part: label,
+                    past: true,
+                    walkable: false,
+                    weather_available: true,
+                    pop: 0,
+                    precipitation: 0.0,
+                    precipitation_unit: suggester.places.area.precipitation_unit(),
+                    icon: sunnyday::provider::WeatherCondition::Sunny.icon(),
+                    place: None,
+                    itinerary: None,
+                    suggestion_name: None,
+                    explanation: None,
+                });
+            }
+            continue;
+        }
+
+        let snow_forced = match suggester.places.area.snow_limit {
+            Some(limit) => jma::snowfall(&suggester.places.area, part).await.is_ok_and(|cm| cm >= limit),
+            None => false,
+        };
+
+        let suggestion = match suggester.suggest(part, &mood, stay_in_forced || snow_forced, params.reroll).await {
+            Ok(s) => s,
+            Err(_) => {
+                parts.push(PartSuggestion {
+                    part_param: label.to_lowercase(),
+                    part: label,
+                    past: false,
+                    walkable: false,
+                    weather_available: false,
+                    pop: 0,
+                    precipitation: 0.0,
+                    precipitation_unit: suggester.places.area.precipitation_unit(),
+                    icon: sunnyday::provider::WeatherCondition::Sunny.icon(),
+                    place: None,
+                    itinerary: None,
+                    suggestion_name: None,
+                    explanation: None,
+                });
+                continue;
+            }
+        };
+        let itinerary = suggestion.itinerary.map(|(itinerary, legs)| ItinerarySuggestion {
+            name: itinerary.name,
+            legs: legs.into_iter().map(|p| p.name).collect(),
+        });
+        let place = if itinerary.is_some() {
+            None
+        } else {
+            suggestion.place.map(|p| {
+                let shop = if mood.food == Some(true) {
+                    suggester.places.pickup_shop(&p, &suggester.recent, now.date_naive(), Some(part))
+                } else {
+                    None
+                };
+                if let Some(shop) = &shop {
+                    suggester.recent.push_shop(&shop.name);
+                }
+                PlaceSuggestion::new(p, shop)
+            })
+        };
+        let condition = if snow_forced { sunnyday::provider::WeatherCondition::Snow } else { suggestion.forecast.condition() };
+        today_conditions.push(condition);
+        let suggestion_name = itinerary.as_ref().map(|i| i.name.clone()).or_else(|| place.as_ref().map(|p| p.name.clone()));
+        parts.push(PartSuggestion {
+            part_param: label.to_lowercase(),
+            part: label,
+            past: false,
+            walkable: suggestion.walkable,
+            weather_available: suggestion.weather_available,
+            pop: suggestion.forecast.pop,
+            precipitation: suggester.places.area.display_precipitation(suggestion.forecast.precipitation),
+            precipitation_unit: suggester.places.area.precipitation_unit(),
+            icon: condition.icon(),
+            place,
+            itinerary,
+            suggestion_name,
+            explanation: Some(suggestion.explanation.into()),
+        });
+    }
+    let today_icon = sunnyday::provider::WeatherCondition::combine(today_conditions).map(|c| c.icon());
+    let any_weather_unavailable = parts.iter().any(|p| !p.weather_available);
+    let _ = suggester.save();
+
+    let template = HomeTemplate {
+        raining_now,
+        warnings,
+        today_icon,
+        parts,
+        himawari_url,
+        wbgt,
+        typhoon_advisory,
+        pm25,
+        api_base,
+    };
+    let body = Html(render("home.html", &template));
+    if any_weather_unavailable {
+        // Don't let a CDN or the browser cache a mood-only page past the
+        // outage that produced it.
+        ([(axum::http::header::CACHE_CONTROL, "no-store")], body).into_response()
+    } else {
+        body.into_response()
+    }
+}
+
+/// JSON equivalent of `HomeTemplate.himawari_url`: the latest Himawari
+/// satellite tile covering the configured area, as a URL -- see
+/// `sunnyday::jma::himawari_tile_url`.
+async fn himawari_handler() -> impl IntoResponse {
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    match jma::himawari_tile_url(&places.area, places.area.lat, places.area.lon).await {
+        Ok(tile_url) => Json(serde_json::json!({"tile_url": tile_url})),
+        Err(e) => Json(serde_json::json!({"error": format!("failed to fetch himawari tile: {e}")})),
+    }
+}
+
+/// JSON equivalent of `HomeTemplate.wbgt`: the current WBGT heat-stress
+/// reading near the configured area, plus whether it's at or above
+/// `[area] wbgt_limit` -- see `sunnyday::wbgt::current_wbgt`.
+async fn wbgt_handler() -> impl IntoResponse {
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    let wbgt = current_wbgt(&places).await;
+    let exceeded = match (wbgt, places.area.wbgt_limit) {
+        (Some(value), Some(limit)) => value >= limit,
+        _ => false,
+    };
+    Json(serde_json::json!({"wbgt": wbgt, "exceeded": exceeded}))
+}
+
+/// JSON equivalent of `HomeTemplate.typhoon_advisory`: the nearest
+/// currently-tracked typhoon's forecast approach, if any is within
+/// `[area] typhoon_distance_km` -- see
+/// `sunnyday::typhoon::nearest_approach`.
+async fn typhoon_handler() -> impl IntoResponse {
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    let advisory = current_typhoon_advisory(&places).await;
+    Json(serde_json::json!({"name": advisory.as_ref().map(|(name, _)| name), "distance_km": advisory.map(|(_, km)| km)}))
+}
+
+/// JSON equivalent of `HomeTemplate.pm25`: the current PM2.5 reading near
+/// the configured area, plus whether it's at or above `[area] max_pm25`
+/// -- see `sunnyday::airquality::current_pm25`.
+async fn airquality_handler() -> impl IntoResponse {
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    let pm25 = current_pm25(&places).await;
+    let exceeded = match (pm25, places.area.max_pm25) {
+        (Some(value), Some(limit)) => value >= limit,
+        _ => false,
+    };
+    Json(serde_json::json!({"pm25": pm25, "exceeded": exceeded}))
+}
+
+/// One day in the `/api/week` response.
+#[derive(Serialize)]
+struct WeekDay {
+    date: String,
+    pop: u32,
+    condition: sunnyday::provider::WeatherCondition,
+    icon: &'static str,
+    walkable: bool,
+    place: Option<String>,
+}
+
+/// JSON equivalent of the console's `week` subcommand: a tentative place
+/// per day, using JMA's weekly pops forecast.
+async fn week_handler() -> impl IntoResponse {
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    let mut recent = match RecentPlace::read(state_path("recent.toml")) {
+        Ok(r) => r,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load recent-place state: {e}")})),
+    };
+    let days = match jma::weekly_pops(&places.area).await {
+        Ok(d) => d,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to fetch weekly forecast: {e}")})),
+    };
+
+    let mood = Mood::default();
+    let mut week = Vec::new();
+    for (date, pop) in days {
+        let walkable = pop < jma::WEEKLY_WALKABLE_POP;
+        let place = if walkable {
+            places.pickup(&mood, &recent, date, None, None).map(|p| {
+                recent.push(&p.name, date, None);
+                recent.set_last_cluster(p.cluster.as_deref());
+                p.name
+            })
+        } else {
+            None
+        };
+        let condition = sunnyday::provider::Forecast { pop, precipitation: 0.0, wind_speed: 0.0 }.condition();
+        week.push(WeekDay {
+            date: date.to_string(),
+            pop,
+            condition,
+            icon: condition.icon(),
+            walkable,
+            place,
+        });
+    }
+    let _ = recent.save();
+
+    Json(serde_json::json!(week))
+}
+
+/// Query parameters for `/api/timeline`.
+#[derive(Deserialize)]
+struct TimelineParams {
+    part: PartOfDay,
+}
+
+/// JSON per-validtime precipitation series for `part`, straight from JMA's
+/// rain tiles, regardless of the configured provider (same as
+/// `week_handler`'s use of `jma::weekly_pops`) -- see
+/// `sunnyday::jma::precipitation_timeline`.
+async fn timeline_handler(Query(params): Query<TimelineParams>) -> impl IntoResponse {
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    match jma::precipitation_timeline(&places.area, params.part, places.area.lat, places.area.lon).await {
+        Ok(frames) => Json(serde_json::json!(frames)),
+        Err(e) => Json(serde_json::json!({"error": format!("failed to fetch precipitation timeline: {e}")})),
+    }
+}
+
+/// JSON data-provenance report for `part`'s forecast: basetime, fetch
+/// time, provider, and tile-cache status (see
+/// `sunnyday::jma::weather_report`), plus a `stale` flag computed against
+/// `[area] max_forecast_age_minutes` -- same bypass-the-provider approach
+/// as `timeline_handler`.
+async fn report_handler(Query(params): Query<TimelineParams>) -> impl IntoResponse {
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    match jma::weather_report(&places.area, params.part, places.area.lat, places.area.lon).await {
+        Ok(report) => {
+            let stale = places.area.max_forecast_age_minutes.map(|max_age| report.is_stale(max_age)).unwrap_or(false);
+            Json(serde_json::json!({"report": report, "stale": stale}))
+        }
+        Err(e) => Json(serde_json::json!({"error": format!("failed to fetch weather report: {e}")})),
+    }
+}
+
+/// One entry in the `/api/rank` response.
+#[derive(Serialize)]
+struct RankedPlace {
+    name: String,
+    score: f64,
+}
+
+/// JSON equivalent of the console's `rank` subcommand: every place matching
+/// the mood, scored by `sunnyday::Places::rank`, instead of a single pick.
+/// Doesn't touch the rotation history.
+async fn rank_handler(Query(params): Query<HomeParams>) -> impl IntoResponse {
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    let recent = match RecentPlace::read(state_path("recent.toml")) {
+        Ok(r) => r,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load recent-place state: {e}")})),
+    };
+
+    let mood = mood_from_params(&params);
+    let now = places.area.now();
+    let acceptance = places.learning.enabled.then(|| {
+        sunnyday::stats::acceptance_rates(&VisitLog::open(state_path("visits.jsonl")).read_all().unwrap_or_default())
+    });
+    let ranked: Vec<RankedPlace> = places
+        .rank(&mood, &recent, now.date_naive(), None, acceptance.as_ref())
+        .into_iter()
+        .map(|scored| RankedPlace { name: scored.place.name, score: scored.score })
+        .collect();
+
+    Json(serde_json::json!(ranked))
+}
+
+/// Query/form parameters for `/api/visit`.
+#[derive(Deserialize)]
+struct VisitParams {
+    part: PartOfDay,
+    outcome: VisitOutcome,
+}
+
+/// Web equivalent of the console's `accept`/`skip` subcommands: records the
+/// outcome of today's suggestion for `part` in the visit log, looking the
+/// place up from `recent.toml`. Meant to be called from the page itself, or
+/// a notification action button.
+async fn visit_handler(Query(params): Query<VisitParams>) -> impl IntoResponse {
+    render_visit(load_places(), RecentPlace::read(state_path("recent.toml")), state_path("visits.jsonl"), params).await
+}
+
+/// Per-friend equivalent of `visit_handler`; see [`user_place_handler`] for
+/// multi-tenant mode.
+async fn user_visit_handler(Path(user): Path<String>, Query(params): Query<VisitParams>) -> impl IntoResponse {
+    if !valid_user(&user) {
+        return Json(serde_json::json!({"error": "invalid user"}));
+    }
+    render_visit(load_user_places(&user), RecentPlace::read(user_recent_path(&user)), user_visits_path(&user), params).await
+}
+
+/// Shared body of `visit_handler`/`user_visit_handler`.
+async fn render_visit(
+    places: anyhow::Result<Places>,
+    recent: anyhow::Result<RecentPlace>,
+    visits_path: std::path::PathBuf,
+    params: VisitParams,
+) -> Json<serde_json::Value> {
+    let places = match places {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    let recent = match recent {
+        Ok(r) => r,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load recent-place state: {e}")})),
+    };
+    let date = places.area.now().date_naive();
+    let Some(place) = recent.last_for(date, params.part) else {
+        return Json(serde_json::json!({"error": "no suggestion recorded for that part today"}));
+    };
+    let walkable = !is_raining_now(&places).await;
+    let forecast = current_forecast(&places, params.part).await;
+    let record = VisitRecord {
+        date,
+        part: params.part,
+        place: place.to_string(),
+        outcome: params.outcome,
+        walkable,
+        pop: forecast.pop,
+        precipitation: forecast.precipitation,
+        wind_speed: forecast.wind_speed,
+    };
+    if let Err(e) = VisitLog::open(visits_path).append(&record) {
+        return Json(serde_json::json!({"error": format!("failed to record visit: {e}")}));
+    }
+    notify_accepted(&places, &record).await;
+    Json(serde_json::json!({"recorded": record.place}))
+}
+
+/// Best-effort: create a calendar event for an accepted suggestion (see
+/// [`sunnyday::calendar`]), if `[calendar]` is configured in `place.toml`.
+/// A failure here is logged, not surfaced to the caller -- the visit is
+/// already recorded either way, same as this crate's other "missing
+/// optional integration degrades gracefully" behavior (WBGT, PM2.5,
+/// `decision_script`...).
+#[cfg(feature = "notify")]
+async fn notify_accepted(places: &Places, record: &VisitRecord) {
+    if record.outcome != VisitOutcome::Accepted {
+        return;
+    }
+    let Some(config) = &places.calendar else { return };
+    let backend = match config.build() {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to set up calendar backend");
+            return;
+        }
+    };
+    let (start, end) = calendar::event_window(record.part, record.date, places.area.timezone);
+    let event = calendar::Event { summary: format!("sunnyday: {}", record.place), location: record.place.clone(), start, end };
+    if let Err(e) = backend.create_event(&event).await {
+        tracing::warn!(error = %e, "failed to create calendar event");
+    }
+}
+
+#[cfg(not(feature = "notify"))]
+async fn notify_accepted(_places: &Places, _record: &VisitRecord) {}
+
+/// Secret `/api/share`/`/share/{token}` sign and verify share tokens with
+/// (see [`share::sign`]/[`share::verify`]). Required for either to work --
+/// there's no built-in default, since a guessable default would make
+/// every deployment's share links forgeable.
+const SHARE_SECRET_ENV: &str = "SUNNYDAY_SHARE_SECRET";
+
+fn share_secret() -> anyhow::Result<String> {
+    std::env::var(SHARE_SECRET_ENV).map_err(|_| anyhow::anyhow!("sharing requires {SHARE_SECRET_ENV} to be set"))
+}
+
+/// `POST /api/share`: mint a signed link to today's suggestion, for the
+/// page's own "Share" button to hand to `window.navigator.share`/a copy
+/// button. See [`user_share_handler`] for the multi-tenant equivalent.
+async fn share_handler(Query(params): Query<HomeParams>) -> impl IntoResponse {
+    render_share(load_places(), None, params).await
+}
+
+/// Per-friend equivalent of `share_handler`; see [`user_place_handler`] for
+/// multi-tenant mode.
+async fn user_share_handler(Path(user): Path<String>, Query(params): Query<HomeParams>) -> impl IntoResponse {
+    if !valid_user(&user) {
+        return Json(serde_json::json!({"error": "invalid user"}));
+    }
+    render_share(load_user_places(&user), Some(user), params).await
+}
+
+async fn render_share(places: anyhow::Result<Places>, user: Option<String>, params: HomeParams) -> Json<serde_json::Value> {
+    let secret = match share_secret() {
+        Ok(s) => s,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let places = match places {
+        Ok(p) => p,
+        Err(e) => return Json(serde_json::json!({"error": format!("failed to load place list: {e}")})),
+    };
+    let payload = share::SharePayload { date: places.area.now().date_naive(), mood: mood_from_params(&params), user };
+    match share::sign(&payload, &secret) {
+        Ok(token) => Json(serde_json::json!({"url": format!("/share/{token}")})),
+        Err(e) => Json(serde_json::json!({"error": format!("failed to sign share token: {e}")})),
+    }
+}
+
+/// One part of the day on the read-only `/share/{token}` page -- just
+/// enough to show what was suggested, not the `explain`/map/QR detail
+/// `PartSuggestion` carries for the owner's own page.
+#[derive(Serialize)]
+struct SharePart {
+    part: String,
+    icon: &'static str,
+    suggestion_name: Option<String>,
+}
+
+#[derive(Template, Serialize)]
+#[template(path = "share.html")]
+struct ShareTemplate {
+    date: String,
+    /// `None` once `date` is no longer today -- a share link is only ever
+    /// good for the day it was made, so a late open says so instead of
+    /// silently showing a different day's (or no) suggestion.
+    parts: Option<Vec<SharePart>>,
+}
+
+/// `GET /share/{token}`: the read-only page a share link points to.
+/// Recomputes today's suggestion the normal way (mood/weather/rotation
+/// all apply as usual, and [`Suggester::suggest`]'s own same-day
+/// idempotency means a token shared after the owner's page already
+/// decided today's pick just echoes it back) -- deliberately not storing
+/// the suggestion itself in the token, so a link opened hours later still
+/// reflects live weather. Shows only the picked place's name: no map/QR,
+/// no explanation, no link back to `/`, `/history`, or any `/api/...`
+/// route, so a recipient never sees more than the single suggestion they
+/// were sent.
+async fn share_page_handler(Path(token): Path<String>) -> impl IntoResponse {
+    let secret = match share_secret() {
+        Ok(s) => s,
+        Err(e) => return Html(format!("sharing is not enabled on this server: {e}")).into_response(),
+    };
+    let payload = match share::verify(&token, &secret) {
+        Ok(p) => p,
+        Err(e) => return Html(format!("this share link is invalid: {e}")).into_response(),
+    };
+    let places = match match &payload.user {
+        Some(user) => load_user_places(user),
+        None => load_places(),
+    } {
+        Ok(p) => p,
+        Err(e) => return Html(format!("failed to load place list: {e}")).into_response(),
+    };
+    let today = places.area.now().date_naive();
+    if payload.date != today {
+        let template = ShareTemplate { date: payload.date.to_string(), parts: None };
+        return Html(render("share.html", &template)).into_response();
+    }
+    let recent = match match &payload.user {
+        Some(user) => RecentPlace::read(user_recent_path(user)),
+        None => RecentPlace::read(state_path("recent.toml")),
+    } {
+        Ok(r) => r,
+        Err(e) => return Html(format!("failed to load recent-place state: {e}")).into_response(),
+    };
+    let mut suggester = match build_suggester(places, recent) {
+        Ok(s) => s,
+        Err(e) => return Html(format!("failed to set up weather provider: {e}")).into_response(),
+    };
+    let now = suggester.places.area.now();
+    let upcoming_parts: Vec<PartOfDay> = ALL_DAY.into_iter().filter(|&part| !suggester.places.area.part_has_passed(part, now)).collect();
+    suggester.prefetch(&upcoming_parts).await;
+
+    let mut parts = Vec::new();
+    for part in upcoming_parts {
+        let Ok(suggestion) = suggester.suggest(part, &payload.mood, false, false).await else {
+            continue;
+        };
+        let suggestion_name =
+            suggestion.itinerary.map(|(itinerary, _)| itinerary.name).or_else(|| suggestion.place.map(|p| p.name));
+        parts.push(SharePart { part: format!("{part:?}"), icon: suggestion.forecast.condition().icon(), suggestion_name });
+    }
+    let _ = suggester.save();
+
+    Html(render("share.html", &ShareTemplate { date: today.to_string(), parts: Some(parts) })).into_response()
+}
+
+/// Request body for `POST /assistant`: a deliberately minimal "intent in,
+/// speech text out" shape, not either vendor's actual request/response
+/// schema -- turning this into a real Alexa skill or Google Assistant
+/// (Dialogflow) webhook is a thin platform-side adapter translating their
+/// request into this one and their expected response out of
+/// [`AssistantResponse`], not something to hand-roll both of here.
+#[derive(Deserialize)]
+struct AssistantRequest {
+    intent: String,
+    #[serde(default)]
+    slots: HashMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct AssistantResponse {
+    speech: String,
+}
+
+/// The `part` slot, defaulting to [`PartOfDay::Afternoon`] to match the
+/// sample question this endpoint was asked to answer ("what should we do
+/// this afternoon?").
+fn parse_part_slot(slots: &HashMap<String, String>) -> Result<PartOfDay, String> {
+    match slots.get("part").map(|s| s.to_lowercase()).as_deref() {
+        None => Ok(PartOfDay::Afternoon),
+        Some("morning") => Ok(PartOfDay::Morning),
+        Some("afternoon") => Ok(PartOfDay::Afternoon),
+        Some("evening") | Some("tonight") => Ok(PartOfDay::Evening),
+        Some(other) => Err(format!("I don't know about {other} -- only morning, afternoon, or evening.")),
+    }
+}
+
+/// Render a suggestion as a spoken-style sentence rather than the web UI's
+/// structured fields.
+fn speech_for_suggestion(part: PartOfDay, suggestion: &sunnyday::Suggestion) -> String {
+    let part_name = match part {
+        PartOfDay::Morning => "this morning",
+        PartOfDay::Afternoon => "this afternoon",
+        PartOfDay::Evening => "this evening",
+    };
+    if !suggestion.walkable {
+        return format!("Better stay in {part_name} -- the weather doesn't look good for going out.");
+    }
+    let weather = if !suggestion.weather_available {
+        "Weather data isn't available right now, so this is by mood alone."
+    } else {
+        match suggestion.forecast.condition() {
+            sunnyday::provider::WeatherCondition::Sunny => "It looks sunny.",
+            sunnyday::provider::WeatherCondition::Cloudy => "It looks cloudy but dry.",
+            sunnyday::provider::WeatherCondition::LightRain | sunnyday::provider::WeatherCondition::HeavyRain => {
+                "There's a chance of rain."
+            }
+            sunnyday::provider::WeatherCondition::Snow | sunnyday::provider::WeatherCondition::MixedByPart => {
+                "The weather's a bit uncertain."
+            }
+        }
+    };
+    let name = suggestion.itinerary.as_ref().map(|(i, _)| i.name.clone()).or_else(|| suggestion.place.as_ref().map(|p| p.name.clone()));
+    match name {
+        Some(name) => format!("{weather} How about {name} {part_name}?"),
+        None => format!("{weather} I don't have a good suggestion for {part_name}, sorry."),
+    }
+}
+
+/// `POST /assistant`: a simple fulfillment webhook for a voice assistant
+/// (Alexa/Google Assistant) -- "what should we do this afternoon?" in, a
+/// spoken-style suggestion out. Reuses the same [`Suggester`] core and
+/// same-day idempotency as the web UI, so asking twice in one day gets the
+/// same answer unless the web UI itself rerolled it meanwhile.
+/// Single-tenant only -- a voice assistant skill is normally wired to one
+/// household's account, not asked to pick a friend by name; see
+/// [`user_place_handler`] for multi-tenant mode elsewhere in this file.
+async fn assistant_handler(Json(request): Json<AssistantRequest>) -> impl IntoResponse {
+    if !request.intent.eq_ignore_ascii_case("WhatShouldWeDo") {
+        return Json(AssistantResponse { speech: format!("I don't know how to handle the {} intent.", request.intent) });
+    }
+    let part = match parse_part_slot(&request.slots) {
+        Ok(part) => part,
+        Err(speech) => return Json(AssistantResponse { speech }),
+    };
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return Json(AssistantResponse { speech: format!("Sorry, I couldn't load the place list: {e}") }),
+    };
+    let recent = match RecentPlace::read(state_path("recent.toml")) {
+        Ok(r) => r,
+        Err(e) => return Json(AssistantResponse { speech: format!("Sorry, I couldn't load recent-place history: {e}") }),
+    };
+    let mut suggester = match build_suggester(places, recent) {
+        Ok(s) => s,
+        Err(e) => return Json(AssistantResponse { speech: format!("Sorry, I couldn't set up the weather provider: {e}") }),
+    };
+    suggester.prefetch(&[part]).await;
+    let suggestion = match suggester.suggest(part, &Mood::default(), false, false).await {
+        Ok(s) => s,
+        Err(e) => return Json(AssistantResponse { speech: format!("Sorry, I couldn't come up with a suggestion: {e}") }),
+    };
+    let speech = speech_for_suggestion(part, &suggestion);
+    let _ = suggester.save();
+    Json(AssistantResponse { speech })
+}
+
+/// Query parameters for `/api/map.png`.
+#[derive(Deserialize)]
+struct MapParams {
+    lat: f64,
+    lon: f64,
+}
+
+/// OSM tile thumbnail (PNG bytes) marking `(lat, lon)` -- see
+/// `sunnyday::staticmap::place_thumbnail`. What `PlaceSuggestion::thumbnail_url`
+/// points at.
+async fn map_handler(Query(params): Query<MapParams>) -> impl IntoResponse {
+    match sunnyday::staticmap::place_thumbnail(params.lat, params.lon).await {
+        Ok(bytes) => ([(axum::http::header::CONTENT_TYPE, "image/png")], bytes).into_response(),
+        Err(e) => (axum::http::StatusCode::BAD_GATEWAY, format!("failed to fetch map tile: {e}")).into_response(),
+    }
+}
+
+/// Query parameters for `/api/qr.png`.
+#[derive(Deserialize)]
+struct QrParams {
+    url: String,
+}
+
+/// QR code (PNG bytes) encoding `url` -- see `sunnyday::qr::png_qr`. What
+/// `PlaceSuggestion::qr_url` points at, for scanning a suggestion's
+/// `map_url` straight onto a phone.
+async fn qr_handler(Query(params): Query<QrParams>) -> impl IntoResponse {
+    match sunnyday::qr::png_qr(&params.url) {
+        Ok(bytes) => ([(axum::http::header::CONTENT_TYPE, "image/png")], bytes).into_response(),
+        Err(e) => (axum::http::StatusCode::BAD_REQUEST, format!("failed to build QR code: {e}")).into_response(),
+    }
+}
+
+/// Query parameters for `/kiosk.png`.
+#[derive(Deserialize)]
+struct KioskParams {
+    #[serde(default = "default_kiosk_width")]
+    width: u32,
+    #[serde(default = "default_kiosk_height")]
+    height: u32,
+}
+
+fn default_kiosk_width() -> u32 {
+    sunnyday::kiosk::DEFAULT_WIDTH
+}
+
+fn default_kiosk_height() -> u32 {
+    sunnyday::kiosk::DEFAULT_HEIGHT
+}
+
+/// `GET /kiosk.png`: the day's suggestions and weather rendered as a
+/// monochrome PNG, `width`x`height` (default 800x480, a common e-paper
+/// panel's resolution) -- see `sunnyday::kiosk::render`. For a frame (e.g.
+/// an ESP32 with an e-paper display) that can only fetch and blit a
+/// bitmap, not run a browser.
+async fn kiosk_handler(Query(params): Query<KioskParams>) -> impl IntoResponse {
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("failed to load place list: {e}")).into_response(),
+    };
+    let recent = match RecentPlace::read(state_path("recent.toml")) {
+        Ok(r) => r,
+        Err(e) => return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("failed to load recent-place state: {e}")).into_response(),
+    };
+    let mut suggester = match build_suggester(places, recent) {
+        Ok(s) => s,
+        Err(e) => return (axum::http::StatusCode::BAD_GATEWAY, format!("failed to set up weather provider: {e}")).into_response(),
+    };
+    let now = suggester.places.area.now();
+    let mood = Mood::default();
+
+    let upcoming_parts: Vec<PartOfDay> = ALL_DAY.into_iter().filter(|&part| !suggester.places.area.part_has_passed(part, now)).collect();
+    suggester.prefetch(&upcoming_parts).await;
+
+    let mut lines = vec![now.format("%Y-%m-%d %a").to_string()];
+    for part in ALL_DAY {
+        if suggester.places.area.part_has_passed(part, now) {
+            continue;
+        }
+        let line = match suggester.suggest(part, &mood, false, false).await {
+            Ok(suggestion) => {
+                let condition = suggestion.forecast.condition();
+                let place = suggestion
+                    .place
+                    .map(|p| p.name)
+                    .or_else(|| suggestion.itinerary.map(|(itinerary, _)| itinerary.name));
+                format!(
+                    "{part:?}: {condition:?} {}% {}",
+                    suggestion.forecast.pop,
+                    place.as_deref().unwrap_or("no suggestion")
+                )
+            }
+            Err(_) => format!("{part:?}: forecast unavailable"),
+        };
+        lines.push(line);
+    }
+    let _ = suggester.save();
+
+    match sunnyday::kiosk::render(&lines, params.width, params.height) {
+        Ok(bytes) => ([(axum::http::header::CONTENT_TYPE, "image/png")], bytes).into_response(),
+        Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("failed to render kiosk image: {e}")).into_response(),
+    }
+}
+
+/// `GET /route/{place}.gpx`: `place`'s walking route as GPX, for loading
+/// into a watch or phone app -- see `sunnyday::gpx::route_for` and the
+/// console's `route` subcommand.
+async fn route_handler(Path(file): Path<String>) -> impl IntoResponse {
+    let name = file.strip_suffix(".gpx").unwrap_or(&file);
+    let places = match load_places() {
+        Ok(p) => p,
+        Err(e) => return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("failed to load place list: {e}")).into_response(),
+    };
+    let Some(place) = places.place_by_name(name) else {
+        return (axum::http::StatusCode::NOT_FOUND, format!("no place named {name:?}")).into_response();
+    };
+    match sunnyday::gpx::route_for(place) {
+        Ok(gpx) => ([(axum::http::header::CONTENT_TYPE, "application/gpx+xml")], gpx).into_response(),
+        Err(e) => (axum::http::StatusCode::BAD_REQUEST, format!("failed to build route: {e}")).into_response(),
+    }
+}
+
+/// Pixel geometry for one bar in a [`HistoryTemplate`] chart.
+#[derive(Serialize)]
+struct Bar {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Width of one day's bar, plus the gap before the next one.
+const HISTORY_BAR_STRIDE: u32 = 12;
+const HISTORY_BAR_WIDTH: u32 = 10;
+/// Pixel height of each chart -- bars grow upward from this baseline.
+const HISTORY_CHART_HEIGHT: u32 = 80;
+
+/// Scale `value` (out of `max`) to a bar height/y pair against
+/// [`HISTORY_CHART_HEIGHT`]'s baseline, so the tallest bar in the chart
+/// always reaches the top.
+fn history_bar(x: u32, value: f64, max: f64) -> Bar {
+    let height = if max <= 0.0 { 0 } else { ((value / max) * HISTORY_CHART_HEIGHT as f64).round() as u32 };
+    Bar { x, y: HISTORY_CHART_HEIGHT - height, width: HISTORY_BAR_WIDTH, height }
+}
+
+/// One day in `/history`'s charts.
+#[derive(Serialize)]
+struct HistoryDay {
+    date: String,
+    precipitation: f64,
+    visited: usize,
+    rain_bar: Bar,
+    visit_bar: Bar,
+}
+
+#[derive(Template, Serialize)]
+#[template(path = "history.html")]
+struct HistoryTemplate {
+    days: Vec<HistoryDay>,
+    chart_width: u32,
+    chart_height: u32,
+}
+
+/// `GET /history`: the visit log's precipitation and accepted-visit counts,
+/// one bar per day, as inline SVG rendered server-side by askama --
+/// doubles the web instance as a lightweight outing diary, for a browser
+/// rather than a spreadsheet (compare `sunnyday stats export`).
+async fn history_handler() -> impl IntoResponse {
+    let records = VisitLog::open(state_path("visits.jsonl")).read_all().unwrap_or_default();
+    let day_stats = sunnyday::stats::by_day(&records);
+    let max_precipitation = day_stats.iter().map(|d| d.precipitation).fold(0.0, f64::max);
+    let max_visited = day_stats.iter().map(|d| d.visited).max().unwrap_or(0) as f64;
+
+    let days = day_stats
+        .into_iter()
+        .enumerate()
+        .map(|(i, d)| {
+            let x = i as u32 * HISTORY_BAR_STRIDE;
+            HistoryDay {
+                date: d.date.to_string(),
+                precipitation: d.precipitation,
+                visited: d.visited,
+                rain_bar: history_bar(x, d.precipitation, max_precipitation),
+                visit_bar: history_bar(x, d.visited as f64, max_visited),
+            }
+        })
+        .collect::<Vec<_>>();
+    let chart_width = (days.len() as u32 * HISTORY_BAR_STRIDE).max(HISTORY_BAR_STRIDE);
+
+    let template = HistoryTemplate { days, chart_width, chart_height: HISTORY_CHART_HEIGHT };
+    Html(render("history.html", &template))
+}
+
+/// Web app manifest, for "Add to Home Screen" on a phone -- see
+/// [`service_worker_handler`] for what makes the installed app actually
+/// work offline.
+const MANIFEST: &str = r##"{
+  "name": "sunnyday",
+  "short_name": "sunnyday",
+  "start_url": "/",
+  "display": "standalone",
+  "background_color": "#ffffff",
+  "theme_color": "#ffffff"
+}"##;
+
+async fn manifest_handler() -> impl IntoResponse {
+    ([(axum::http::header::CONTENT_TYPE, "application/manifest+json")], MANIFEST)
+}
+
+/// A minimal service worker: cache every successful `GET` as it comes in,
+/// and fall back to the cached copy when the network fetch fails -- so
+/// the last suggestion shown still renders offline, without the app
+/// needing its own offline-storage logic.
+const SERVICE_WORKER: &str = r#"const CACHE = "sunnyday-v1";
+
+self.addEventListener("fetch", (event) => {
+  if (event.request.method !== "GET") return;
+  event.respondWith(
+    fetch(event.request)
+      .then((response) => {
+        const copy = response.clone();
+        caches.open(CACHE).then((cache) => cache.put(event.request, copy));
+        return response;
+      })
+      .catch(() => caches.match(event.request))
+  );
+});
+"#;
+
+async fn service_worker_handler() -> impl IntoResponse {
+    ([(axum::http::header::CONTENT_TYPE, "text/javascript")], SERVICE_WORKER)
+}
+
+/// Snapshot of the process-wide tile cache's hit/miss/eviction counters and
+/// current size (see [`jma::configure_cache`]), for dashboards and for
+/// sanity-checking a `[cache]` config change.
+async fn metrics_handler() -> impl IntoResponse {
+    Json(serde_json::json!(jma::cache_metrics().await))
+}
+
+/// Renders the `metrics` crate's counters/histograms (tile fetch/decode
+/// time, tile cache hits/misses/evictions, suggestion outcomes -- see
+/// `jma`/`suggester`) in Prometheus text format, for a Prometheus `scrape_config`
+/// to poll -- distinct from [`metrics_handler`]'s `/api/metrics`, which is
+/// the tile cache's own JSON snapshot, not a Prometheus exposition.
+#[cfg(feature = "metrics")]
+async fn prometheus_handler() -> impl IntoResponse {
+    PROMETHEUS_HANDLE.get().map(|handle| handle.render()).unwrap_or_default()
+}
+
+#[cfg(feature = "metrics")]
+static PROMETHEUS_HANDLE: std::sync::OnceLock<metrics_exporter_prometheus::PrometheusHandle> = std::sync::OnceLock::new();
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if cli.print_systemd_unit {
+        print!("{}", sunnyday::systemd::example_units());
+        return;
+    }
+    if let Some(dir) = &cli.state_dir {
+        // SAFETY: single-threaded at this point, before any server/tokio
+        // task that might read env vars concurrently has started.
+        unsafe { std::env::set_var(STATE_DIR_ENV, dir) };
+    }
+
+    tracing_subscriber::fmt().with_env_filter(tracing_subscriber::EnvFilter::from_default_env()).init();
+
+    if let Ok(places) = load_places() {
+        jma::configure_cache(places.cache);
+        sunnyday::http::configure(places.http);
+        // Tile prefetching is JMA-specific -- nothing to warm for a
+        // provider that doesn't fetch tiles at all (fixture, open-meteo,
+        // ...), and running it anyway would just spend network time on
+        // tiles no request will ever look at.
+        if places.weather.provider_name() == "jma" {
+            tokio::spawn(jma::run_tile_prefetch_daemon(places.area, TILE_PREFETCH_INTERVAL));
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    {
+        match metrics_exporter_prometheus::PrometheusBuilder::new().install_recorder() {
+            Ok(handle) => _ = PROMETHEUS_HANDLE.set(handle),
+            Err(e) => tracing::warn!(error = %e, "failed to install Prometheus recorder, /metrics will be empty"),
+        }
+    }
+
+    let app = Router::new()
+        .route("/", get(place_handler))
+        .route("/api/week", get(week_handler))
+        .route("/api/rank", get(rank_handler))
+        .route("/api/timeline", get(timeline_handler))
+        .route("/api/report", get(report_handler))
+        .route("/api/himawari", get(himawari_handler))
+        .route("/api/map.png", get(map_handler))
+        .route("/api/qr.png", get(qr_handler))
+        .route("/kiosk.png", get(kiosk_handler))
+        .route("/history", get(history_handler))
+        .route("/manifest.json", get(manifest_handler))
+        .route("/sw.js", get(service_worker_handler))
+        .route("/route/{file}", get(route_handler))
+        .route("/api/wbgt", get(wbgt_handler))
+        .route("/api/typhoon", get(typhoon_handler))
+        .route("/api/airquality", get(airquality_handler))
+        .route("/api/metrics", get(metrics_handler))
+        .route("/api/visit", post(visit_handler))
+        .route("/api/share", post(share_handler))
+        .route("/assistant", post(assistant_handler))
+        .route("/share/{token}", get(share_page_handler))
+        .route("/u/{user}/", get(user_place_handler))
+        .route("/u/{user}/api/visit", post(user_visit_handler))
+        .route("/u/{user}/api/share", post(user_share_handler));
+    #[cfg(feature = "metrics")]
+    let app = app.route("/metrics", get(prometheus_handler));
+
+    // Under systemd socket activation the listening socket is already
+    // open (and, with `Accept=no`, already bound) by the time this
+    // process starts -- take it over instead of binding our own. Falls
+    // back to a plain bind outside systemd, or without socket activation
+    // configured.
+    let listener = match sunnyday::systemd::listener_from_env() {
+        Some(Ok(std_listener)) => tokio::net::TcpListener::from_std(std_listener).unwrap(),
+        Some(Err(e)) => panic!("systemd passed a socket but it couldn't be taken over: {e}"),
+        None => {
+            let port = std::env::var(PORT_ENV).unwrap_or_else(|_| "3000".to_string());
+            tokio::net::TcpListener::bind(format!("0.0.0.0:{port}")).await.unwrap()
+        }
+    };
+
+    if let Some(interval) = sunnyday::systemd::watchdog_interval() {
+        tokio::spawn(sunnyday::systemd::run_watchdog_pings(interval));
+    }
+    sunnyday::systemd::notify_ready();
+
+    axum::serve(listener, app).await.unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_path_traversal_and_other_non_plain_usernames() {
+        assert!(!valid_user(""));
+        assert!(!valid_user("../../../root/crate/place"));
+        assert!(!valid_user("..%2f..%2fetc"));
+        assert!(!valid_user("a/b"));
+        assert!(!valid_user("a.b"));
+    }
+
+    #[test]
+    fn accepts_plain_alphanumeric_usernames() {
+        assert!(valid_user("alice"));
+        assert!(valid_user("bob_2"));
+        assert!(valid_user("carol-3"));
+    }
+}
@@ -0,0 +1,271 @@
+//! Calendar event creation for accepted suggestions -- CalDAV PUT or the
+//! Google Calendar REST API, picked by `[calendar]` in `place.toml` (see
+//! [`CalendarConfig`]). This is the first real backend behind the
+//! long-reserved `notify` feature (see its Cargo.toml comment); enabling
+//! it used to do nothing at all.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::jma::PartOfDay;
+
+/// Resolve a local wall-clock `naive` time to an instant in `tz`, without
+/// panicking on a DST gap or fold. Unlike `jma::PartOfDay::starts_at`/
+/// `has_passed`, which fall back to an already-valid instant (`now`) they
+/// have on hand, there's no such instant here to fall back to, so instead:
+/// an ambiguous time (a fold) resolves to the earlier of its two instants,
+/// and a time that doesn't exist at all (a gap) is nudged forward an hour,
+/// which resolves every real-world one-hour DST transition while staying
+/// close to what was asked for. A transition wider than an hour (e.g.
+/// `Pacific/Apia` skipping all of 2011-12-30) still can't be resolved by
+/// nudging, so as a last resort this treats the local time as UTC, which
+/// can be many hours off but at least never panics.
+fn resolve_local(tz: Tz, naive: chrono::NaiveDateTime) -> DateTime<Tz> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(naive + chrono::Duration::hours(1))).earliest())
+        .unwrap_or_else(|| tz.from_utc_datetime(&naive))
+}
+
+/// Start/end instants for `part` on `date`, in `tz` -- for a calendar
+/// event's start/end times, reusing [`PartOfDay`]'s own clock-hour window
+/// rather than inventing a separate notion of "when a part happens".
+pub fn event_window(part: PartOfDay, date: NaiveDate, tz: Tz) -> (DateTime<Tz>, DateTime<Tz>) {
+    let (start_hour, end_hour) = part.window();
+    let at = |hour| resolve_local(tz, date.and_hms_opt(hour, 0, 0).unwrap());
+    (at(start_hour), at(end_hour))
+}
+
+/// `[calendar]` in `place.toml`: where to create an event when a
+/// suggestion is accepted (see [`crate::VisitOutcome::Accepted`]). Absent
+/// entirely -- no calendar integration.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum CalendarConfig {
+    /// PUT a `.ics` `VEVENT` straight at a CalDAV collection URL, e.g. a
+    /// self-hosted Radicale/Nextcloud calendar, or Google Calendar's own
+    /// CalDAV endpoint.
+    Caldav {
+        /// Collection URL to PUT the event under, e.g.
+        /// `https://cal.example.com/dav/calendars/user/home/` (trailing
+        /// slash required -- the event's own filename is appended to it).
+        url: String,
+        username: String,
+        /// HTTP Basic auth password. Takes priority over `password_env`.
+        #[serde(default)]
+        password: Option<String>,
+        /// Name of an environment variable to read the password from, for
+        /// users who don't want secrets in `place.toml`.
+        #[serde(default)]
+        password_env: Option<String>,
+    },
+    /// Insert an event via the Google Calendar API
+    /// (`POST /calendars/{calendar_id}/events`), authenticated with a
+    /// bearer OAuth access token read from an environment variable --
+    /// always from the environment, unlike `Caldav`'s inline `password`,
+    /// since an access token is short-lived and refreshed by something
+    /// outside this crate, not a secret worth writing to `place.toml` at
+    /// all.
+    Google { calendar_id: String, token_env: String },
+}
+
+impl CalendarConfig {
+    /// Resolve credentials and build the backend this config selects.
+    pub fn build(&self) -> Result<Box<dyn CalendarBackend>> {
+        match self {
+            CalendarConfig::Caldav { url, username, password, password_env } => {
+                let password = password
+                    .clone()
+                    .or_else(|| password_env.as_ref().and_then(|var| std::env::var(var).ok()))
+                    .context("CalDAV calendar requires password or password_env in [calendar]")?;
+                Ok(Box::new(CaldavBackend { client: crate::http::client(), url: url.clone(), username: username.clone(), password }))
+            }
+            CalendarConfig::Google { calendar_id, token_env } => {
+                let token = std::env::var(token_env).with_context(|| format!("reading Google Calendar token from ${token_env}"))?;
+                Ok(Box::new(GoogleCalendarBackend { client: crate::http::client(), calendar_id: calendar_id.clone(), token }))
+            }
+        }
+    }
+}
+
+/// One calendar event, built from an accepted suggestion.
+pub struct Event {
+    pub summary: String,
+    /// Just the place's name -- this crate has no street-address field for
+    /// a [`crate::Place`], so that's the most useful thing `location` can
+    /// carry.
+    pub location: String,
+    pub start: DateTime<Tz>,
+    pub end: DateTime<Tz>,
+}
+
+/// A backend [`CalendarConfig::build`] can produce.
+#[async_trait]
+pub trait CalendarBackend: Send + Sync {
+    async fn create_event(&self, event: &Event) -> Result<()>;
+}
+
+/// Deterministic event ID from what the event is *for*, not a random one --
+/// re-accepting the same suggestion (e.g. a retried request) PUTs/updates
+/// the same event instead of creating a duplicate, the same "acting on the
+/// same thing twice is a no-op" idea as [`crate::suggester::Suggester::suggest`]'s
+/// own same-day idempotency.
+fn event_uid(event: &Event) -> String {
+    let raw = format!("{}-{}-{}", event.start.format("%Y%m%dT%H%M"), event.location, event.summary);
+    let safe: String = raw.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect();
+    format!("{safe}@sunnyday")
+}
+
+/// Escape a value for an iCalendar `TEXT` property (RFC 5545 section 3.3.11):
+/// backslash, comma, and semicolon are escaped, and a literal newline
+/// becomes `\n`.
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(',', "\\,").replace(';', "\\;").replace('\n', "\\n")
+}
+
+fn ics_datetime(dt: DateTime<Tz>) -> String {
+    dt.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn ics_event(uid: &str, event: &Event) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         PRODID:-//sunnyday//sunnyday//EN\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:{uid}\r\n\
+         DTSTAMP:{now}\r\n\
+         DTSTART:{start}\r\n\
+         DTEND:{end}\r\n\
+         SUMMARY:{summary}\r\n\
+         LOCATION:{location}\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        now = ics_datetime(Utc::now().with_timezone(&event.start.timezone())),
+        start = ics_datetime(event.start),
+        end = ics_datetime(event.end),
+        summary = ics_escape(&event.summary),
+        location = ics_escape(&event.location),
+    )
+}
+
+struct CaldavBackend {
+    client: reqwest::Client,
+    url: String,
+    username: String,
+    password: String,
+}
+
+#[async_trait]
+impl CalendarBackend for CaldavBackend {
+    async fn create_event(&self, event: &Event) -> Result<()> {
+        let uid = event_uid(event);
+        let event_url = format!("{}{uid}.ics", self.url);
+        self.client
+            .put(&event_url)
+            .basic_auth(&self.username, Some(&self.password))
+            .header("Content-Type", "text/calendar; charset=utf-8")
+            .body(ics_event(&uid, event))
+            .send()
+            .await
+            .context("PUTting CalDAV event")?
+            .error_for_status()
+            .context("CalDAV server rejected the event")?;
+        Ok(())
+    }
+}
+
+struct GoogleCalendarBackend {
+    client: reqwest::Client,
+    calendar_id: String,
+    token: String,
+}
+
+#[async_trait]
+impl CalendarBackend for GoogleCalendarBackend {
+    async fn create_event(&self, event: &Event) -> Result<()> {
+        let url = format!("https://www.googleapis.com/calendar/v3/calendars/{}/events", self.calendar_id);
+        self.client
+            .post(&url)
+            .bearer_auth(&self.token)
+            .json(&serde_json::json!({
+                "summary": event.summary,
+                "location": event.location,
+                "start": {"dateTime": event.start.to_rfc3339(), "timeZone": event.start.timezone().to_string()},
+                "end": {"dateTime": event.end.to_rfc3339(), "timeZone": event.end.timezone().to_string()},
+            }))
+            .send()
+            .await
+            .context("creating Google Calendar event")?
+            .error_for_status()
+            .context("Google Calendar API rejected the event")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(location: &str, summary: &str) -> Event {
+        let start = chrono_tz::Asia::Tokyo.with_ymd_and_hms(2026, 8, 9, 12, 0, 0).unwrap();
+        let end = chrono_tz::Asia::Tokyo.with_ymd_and_hms(2026, 8, 9, 18, 0, 0).unwrap();
+        Event { summary: summary.to_string(), location: location.to_string(), start, end }
+    }
+
+    #[test]
+    fn escapes_commas_semicolons_backslashes_and_newlines() {
+        assert_eq!(ics_escape("a, b; c\\d\ne"), "a\\, b\\; c\\\\d\\ne");
+    }
+
+    #[test]
+    fn event_window_does_not_panic_on_a_day_skipped_entirely_by_its_timezone() {
+        // Pacific/Apia skipped 2011-12-30 outright (Samoa moved from UTC-11
+        // to UTC+13), so every local wall-clock time on that date is a gap.
+        let date = NaiveDate::from_ymd_opt(2011, 12, 30).unwrap();
+        let (start, end) = event_window(PartOfDay::Afternoon, date, chrono_tz::Pacific::Apia);
+        assert!(start < end);
+    }
+
+    #[test]
+    fn resolve_local_nudges_an_hour_forward_across_a_one_hour_dst_gap() {
+        // America/New_York springs forward at 2023-03-12 02:00 local --
+        // 02:30 doesn't exist. The nudge should land on 03:30 EDT (one hour
+        // later, as asked), not jump by the zone's whole UTC offset.
+        let gap = NaiveDate::from_ymd_opt(2023, 3, 12).unwrap().and_hms_opt(2, 30, 0).unwrap();
+        let resolved = resolve_local(chrono_tz::America::New_York, gap);
+        assert_eq!(resolved, chrono_tz::America::New_York.with_ymd_and_hms(2023, 3, 12, 3, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn resolve_local_picks_the_earlier_instant_across_a_dst_fold() {
+        // America/New_York falls back at 2023-11-05 02:00 local -- 01:30
+        // occurs twice. The earlier (still-daylight-saving) instant is
+        // picked.
+        let fold = NaiveDate::from_ymd_opt(2023, 11, 5).unwrap().and_hms_opt(1, 30, 0).unwrap();
+        let resolved = resolve_local(chrono_tz::America::New_York, fold);
+        assert_eq!(resolved, chrono_tz::America::New_York.from_local_datetime(&fold).earliest().unwrap());
+    }
+
+    #[test]
+    fn uid_is_stable_for_the_same_event_and_differs_for_a_different_one() {
+        let a = event("Riverside Park", "Riverside Park");
+        let b = event("Riverside Park", "Riverside Park");
+        let c = event("Mountainside Trail", "Mountainside Trail");
+        assert_eq!(event_uid(&a), event_uid(&b));
+        assert_ne!(event_uid(&a), event_uid(&c));
+    }
+
+    #[test]
+    fn rendered_ics_carries_the_summary_location_and_utc_times() {
+        let e = event("Riverside Park, Block 2", "Riverside Park");
+        let ics = ics_event(&event_uid(&e), &e);
+        assert!(ics.contains("SUMMARY:Riverside Park\r\n"));
+        assert!(ics.contains("LOCATION:Riverside Park\\, Block 2\r\n"));
+        assert!(ics.contains("DTSTART:20260809T030000Z\r\n"));
+        assert!(ics.contains("DTEND:20260809T090000Z\r\n"));
+    }
+}
@@ -0,0 +1,107 @@
+//! Weather-backend selection, configured from `place.toml`.
+
+use std::env;
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+#[cfg(feature = "image")]
+use crate::jma::JmaProvider;
+use crate::provider::fixture::FixtureProvider;
+use crate::provider::met_no::MetNoProvider;
+use crate::provider::open_meteo::OpenMeteoProvider;
+use crate::provider::openweathermap::OpenWeatherMapProvider;
+use crate::provider::WeatherProvider;
+
+/// The `[weather]` table in `place.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WeatherConfig {
+    /// Which backend to use: `"jma"` (default), `"open-meteo"`,
+    /// `"openweathermap"`, or `"met.no"`.
+    pub provider: String,
+    /// API key for providers that need one, e.g. OpenWeatherMap. Takes
+    /// priority over `api_key_env`.
+    pub api_key: Option<String>,
+    /// Name of an environment variable to read the API key from, for users
+    /// who don't want secrets in `place.toml`.
+    pub api_key_env: Option<String>,
+    /// Directory of canned fixtures for `provider = "fixture"`.
+    pub fixture_dir: Option<String>,
+}
+
+/// Overrides `[weather] provider` without editing `place.toml`, e.g. to
+/// flip a deployment to the fixture provider for a test run.
+const PROVIDER_ENV: &str = "SUNNYDAY_PROVIDER";
+/// Overrides `[weather] fixture_dir`.
+const FIXTURE_DIR_ENV: &str = "SUNNYDAY_FIXTURE_DIR";
+
+/// Every provider name [`WeatherConfig::build_named`] understands, for
+/// `sunnyday forecast --compare` to try them all.
+pub const KNOWN_PROVIDERS: [&str; 5] = ["jma", "open-meteo", "met.no", "openweathermap", "fixture"];
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        WeatherConfig {
+            provider: "jma".to_string(),
+            api_key: None,
+            api_key_env: None,
+            fixture_dir: None,
+        }
+    }
+}
+
+impl WeatherConfig {
+    /// Resolve the configured API key, preferring an inline value over the
+    /// named environment variable.
+    pub fn api_key(&self) -> Option<String> {
+        self.api_key.clone().or_else(|| {
+            self.api_key_env.as_ref().and_then(|var| env::var(var).ok())
+        })
+    }
+
+    /// The provider name, with [`PROVIDER_ENV`] taking priority over
+    /// `place.toml` so deployments can be redirected without editing it.
+    pub fn provider_name(&self) -> String {
+        env::var(PROVIDER_ENV).unwrap_or_else(|_| self.provider.clone())
+    }
+
+    /// The fixture directory, with [`FIXTURE_DIR_ENV`] taking priority.
+    fn fixture_dir(&self) -> Option<String> {
+        env::var(FIXTURE_DIR_ENV).ok().or_else(|| self.fixture_dir.clone())
+    }
+
+    /// Build the [`WeatherProvider`] this config selects.
+    pub fn build(&self) -> Result<Box<dyn WeatherProvider>> {
+        self.build_named(&self.provider_name())
+    }
+
+    /// Build the [`WeatherProvider`] named `name`, using this config's
+    /// `api_key`/`fixture_dir` regardless of which provider `self.provider`
+    /// itself selects -- split out from [`Self::build`] so
+    /// `sunnyday forecast --compare` can build every provider it knows
+    /// about with the same config, not just the configured one.
+    pub fn build_named(&self, name: &str) -> Result<Box<dyn WeatherProvider>> {
+        match name {
+            #[cfg(feature = "image")]
+            "jma" => Ok(Box::new(JmaProvider)),
+            #[cfg(not(feature = "image"))]
+            "jma" => bail!("provider \"jma\" requires the \"image\" feature"),
+            "open-meteo" => Ok(Box::new(OpenMeteoProvider::new())),
+            "met.no" => Ok(Box::new(MetNoProvider::new())),
+            "openweathermap" => {
+                let Some(api_key) = self.api_key() else {
+                    bail!("provider \"openweathermap\" requires api_key or api_key_env in [weather]");
+                };
+                Ok(Box::new(OpenWeatherMapProvider::new(api_key)))
+            }
+            "fixture" => {
+                let Some(dir) = self.fixture_dir() else {
+                    bail!("provider \"fixture\" requires fixture_dir in [weather] (or SUNNYDAY_FIXTURE_DIR)");
+                };
+                Ok(Box::new(FixtureProvider::new(dir)?))
+            }
+            other => bail!("unknown weather provider \"{other}\""),
+        }
+    }
+}
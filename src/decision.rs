@@ -0,0 +1,48 @@
+//! Optional Rhai scripting hook for the walking decision (see
+//! [`crate::jma::AreaCode::decision_script`] and
+//! [`crate::suggester::Suggester::suggest`]), for rules the built-in
+//! precipitation/pop/wind thresholds can't express.
+
+use anyhow::{anyhow, Result};
+
+use crate::jma::{part_key, PartOfDay};
+
+/// Evaluate `script` -- a single Rhai expression -- against the forecast,
+/// returning whether it's OK to go out. The script sees four variables:
+/// `pop` (0-100), `precipitation` (mm/h), `wind` (m/s), and `part`
+/// (`"morning"`/`"afternoon"`/`"evening"`), e.g.
+/// `"pop < 50 && precipitation < 2.0"`.
+pub fn evaluate(script: &str, pop: u32, precipitation: f64, wind: f64, part: PartOfDay) -> Result<bool> {
+    let mut scope = rhai::Scope::new();
+    scope.push("pop", pop as i64);
+    scope.push("precipitation", precipitation);
+    scope.push("wind", wind);
+    scope.push("part", part_key(part).to_string());
+    let engine = rhai::Engine::new();
+    engine
+        .eval_with_scope::<bool>(&mut scope, script)
+        .map_err(|e| anyhow!("evaluating decision_script: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_a_simple_threshold_expression() {
+        assert!(evaluate("pop < 50 && precipitation < 2.0", 10, 0.0, 0.0, PartOfDay::Morning).unwrap());
+        assert!(!evaluate("pop < 50 && precipitation < 2.0", 90, 0.0, 0.0, PartOfDay::Morning).unwrap());
+    }
+
+    #[test]
+    fn can_branch_on_part() {
+        let script = r#"if part == "morning" { pop < 80 } else { pop < 30 }"#;
+        assert!(evaluate(script, 70, 0.0, 0.0, PartOfDay::Morning).unwrap());
+        assert!(!evaluate(script, 70, 0.0, 0.0, PartOfDay::Afternoon).unwrap());
+    }
+
+    #[test]
+    fn a_malformed_script_is_an_error_rather_than_a_panic() {
+        assert!(evaluate("pop <<< 50", 10, 0.0, 0.0, PartOfDay::Morning).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+//! Straight-line distance and travel-time estimates, used to filter
+//! suggestions down to ones reachable from home (see [`crate::Mood`]).
+
+/// Mean Earth radius in km, for the haversine distance below.
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Typical walking speed in km/h, used to turn a distance into a
+/// travel-time estimate for places without `cycling` set.
+pub const WALKING_KMH: f64 = 4.5;
+
+/// Typical cycling speed in km/h, used the same way for places with
+/// `cycling` set.
+pub const CYCLING_KMH: f64 = 15.0;
+
+/// Great-circle distance between two `(lat, lon)` points, in km.
+pub fn distance_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (a.0.to_radians(), a.1.to_radians());
+    let (lat2, lon2) = (b.0.to_radians(), b.1.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = lon2 - lon1;
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+/// Minutes to cover `distance_km` at a constant `speed_kmh`.
+pub fn minutes_at(distance_km: f64, speed_kmh: f64) -> f64 {
+    distance_km / speed_kmh * 60.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokyo_to_yokohama_is_roughly_thirty_km() {
+        let tokyo = (35.6895, 139.6917);
+        let yokohama = (35.4437, 139.6380);
+        let km = distance_km(tokyo, yokohama);
+        assert!((25.0..35.0).contains(&km), "expected ~30km, got {km}");
+    }
+}
@@ -0,0 +1,52 @@
+//! Format-agnostic (de)serialization for config/state files, picked by
+//! file extension so `place.toml`/`recent.toml` can also be written as
+//! yaml or json. Toml is the fallback for unknown or missing extensions,
+//! since that's the format every existing file already uses.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Parse `text` in the format `path`'s extension selects.
+pub fn parse<T: DeserializeOwned>(path: &Path, text: &str) -> Result<T> {
+    match extension(path) {
+        "yaml" | "yml" => serde_yaml::from_str(text).context("parsing yaml"),
+        "json" => serde_json::from_str(text).context("parsing json"),
+        _ => toml::from_str(text).context("parsing toml"),
+    }
+}
+
+/// Serialize `value` in the format `path`'s extension selects.
+pub fn to_string<T: Serialize>(path: &Path, value: &T) -> Result<String> {
+    match extension(path) {
+        "yaml" | "yml" => serde_yaml::to_string(value).context("serializing yaml"),
+        "json" => serde_json::to_string_pretty(value).context("serializing json"),
+        _ => toml::to_string(value).context("serializing toml"),
+    }
+}
+
+fn extension(path: &Path) -> &str {
+    path.extension().and_then(|e| e.to_str()).unwrap_or("toml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_each_format() {
+        #[derive(Debug, PartialEq, Serialize, serde::Deserialize)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+        let point = Point { x: 1, y: 2 };
+        for ext in ["toml", "yaml", "yml", "json", ""] {
+            let path = Path::new("x").with_extension(ext);
+            let text = to_string(&path, &point).unwrap();
+            assert_eq!(parse::<Point>(&path, &text).unwrap(), point, "format {ext:?}");
+        }
+    }
+}
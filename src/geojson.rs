@@ -0,0 +1,158 @@
+//! Export [`Places`] to GeoJSON, for plotting the place list on a map, and
+//! parse a GeoJSON export (e.g. Google Takeout "saved places") back into
+//! places for `sunnyday places import`.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use crate::import::ImportedPlace;
+use crate::place::Places;
+
+/// A GeoJSON `FeatureCollection` with one `Point` feature per place that
+/// has its own coordinates (see [`crate::Place::lat_lon`]). Places with no
+/// coordinates of their own can't be plotted and are left out.
+pub fn to_feature_collection(places: &Places) -> Value {
+    let features: Vec<Value> = places
+        .place
+        .iter()
+        .filter_map(|place| {
+            let (lat, lon) = place.lat_lon()?;
+            Some(json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "Point",
+                    "coordinates": [lon, lat],
+                },
+                "properties": {
+                    "name": place.name,
+                    "tags": place.tags,
+                },
+            }))
+        })
+        .collect();
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+/// Parse a GeoJSON `FeatureCollection` into the points it contains, for
+/// `sunnyday places import`. Pulls the name from `properties.name`/
+/// `properties.Title`/`properties.Location."Business Name"` (whichever is
+/// present -- Google Takeout's saved-places export uses the latter two)
+/// and the URL from `properties.url`/`properties."Google Maps URL"`.
+pub fn parse_feature_collection(text: &str) -> Result<Vec<ImportedPlace>> {
+    let geojson: Value = serde_json::from_str(text).context("parsing geojson")?;
+    let features = geojson["features"].as_array().cloned().unwrap_or_default();
+    Ok(features.iter().filter_map(feature_to_imported_place).collect())
+}
+
+/// Pull a single [`ImportedPlace`] out of a GeoJSON `Feature`, or `None` if
+/// it has no usable point geometry.
+fn feature_to_imported_place(feature: &Value) -> Option<ImportedPlace> {
+    let coordinates = feature["geometry"]["coordinates"].as_array()?;
+    let lon = coordinates.first()?.as_f64()?;
+    let lat = coordinates.get(1)?.as_f64()?;
+    let props = &feature["properties"];
+    let name = props["name"]
+        .as_str()
+        .or_else(|| props["Title"].as_str())
+        .or_else(|| props["Location"]["Business Name"].as_str())
+        .unwrap_or("Unnamed place")
+        .to_string();
+    let url = props["url"].as_str().or_else(|| props["Google Maps URL"].as_str()).map(str::to_string);
+    Some(ImportedPlace { name, lat, lon, url })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jma::AreaCode;
+    use crate::place::Place;
+
+    fn area() -> AreaCode {
+        AreaCode {
+            offices: "130000".to_string(),
+            class10s: "130010".to_string(),
+            lat: 35.0,
+            lon: 139.0,
+            precipitation: 1.0,
+            max_wind: 8.0,
+            respect_warnings: true,
+            timezone: chrono_tz::Asia::Tokyo,
+            sun_aware: false,
+            pop_limit: None,
+            precipitation_by_part: Default::default(),
+            precipitation_aggregation: Default::default(),
+            roi_window_px: 16,
+            roi_radius_m: None,
+            max_forecast_age_minutes: None,
+            wbgt_limit: None,
+            snow_limit: None,
+            typhoon_distance_km: None,
+            max_pm25: None,
+            units: Default::default(),
+            decision_script: None,
+        }
+    }
+
+    #[test]
+    fn skips_places_with_no_coordinates_and_keeps_tags() {
+        let places = Places {
+            area: area(),
+            weather: Default::default(),
+            cache: Default::default(),
+            http: Default::default(),
+            home: None,
+            #[cfg(feature = "notify")]
+            calendar: None,
+            rotation_days: crate::recent::DEFAULT_ROTATION_DAYS,
+            learning: Default::default(),
+            dedup_same_day: true,
+            place: vec![
+                Place {
+                    name: "With Coords".to_string(),
+                    lat: Some(35.6895),
+                    lon: Some(139.6917),
+                    tags: vec!["park".to_string()],
+                    ..Place::default()
+                },
+                Place {
+                    name: "No Coords".to_string(),
+                    ..Place::default()
+                },
+            ],
+            shop: Vec::new(),
+            itinerary: Vec::new(),
+            include: Vec::new(),
+        };
+
+        let geojson = to_feature_collection(&places);
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0]["properties"]["name"], "With Coords");
+        assert_eq!(features[0]["properties"]["tags"][0], "park");
+        assert_eq!(features[0]["geometry"]["coordinates"], json!([139.6917, 35.6895]));
+    }
+
+    #[test]
+    fn parses_google_takeout_style_properties() {
+        let text = r#"{
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [139.6917, 35.6895]},
+                "properties": {
+                    "Location": {"Business Name": "Tokyo Station"},
+                    "Google Maps URL": "https://maps.example.com/tokyo-station"
+                }
+            }]
+        }"#;
+        let imported = parse_feature_collection(text).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name, "Tokyo Station");
+        assert_eq!(imported[0].lat, 35.6895);
+        assert_eq!(imported[0].lon, 139.6917);
+        assert_eq!(imported[0].url.as_deref(), Some("https://maps.example.com/tokyo-station"));
+    }
+}
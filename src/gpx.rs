@@ -0,0 +1,68 @@
+//! GPX export for a place's walking route -- `sunnyday route`/
+//! `/route/{place}.gpx`, for loading a suggested walk straight into a
+//! watch or phone app.
+
+use anyhow::{Context, Result};
+
+use crate::place::Place;
+
+/// `place`'s route as GPX XML: its own [`Place::gpx`] file, read verbatim
+/// and trusted to already be valid GPX, or -- if that's unset -- a
+/// single waypoint synthesized from [`Place::lat_lon`]. Errors if neither
+/// is available.
+pub fn route_for(place: &Place) -> Result<String> {
+    if let Some(path) = &place.gpx {
+        return std::fs::read_to_string(path).with_context(|| format!("reading {path}"));
+    }
+    let (lat, lon) = place
+        .lat_lon()
+        .with_context(|| format!("{:?} has no `gpx` route file and no coordinates of its own", place.name))?;
+    Ok(waypoint_gpx(&place.name, lat, lon))
+}
+
+/// A minimal single-waypoint GPX document for `name` at `(lat, lon)`.
+fn waypoint_gpx(name: &str, lat: f64, lon: f64) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="sunnyday" xmlns="http://www.topografix.com/GPX/1/1">
+  <wpt lat="{lat}" lon="{lon}">
+    <name>{}</name>
+  </wpt>
+</gpx>
+"#,
+        escape_xml(name)
+    )
+}
+
+/// Escape the handful of characters that are special inside GPX text/
+/// attribute content -- place names are free-form config, not trusted XML.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::place::Place;
+
+    #[test]
+    fn falls_back_to_a_waypoint_when_no_gpx_file_is_configured() {
+        let place = Place { name: "Riverside Park".to_string(), lat: Some(35.0), lon: Some(139.0), ..Default::default() };
+        let gpx = route_for(&place).unwrap();
+        assert!(gpx.contains(r#"<wpt lat="35" lon="139">"#));
+        assert!(gpx.contains("<name>Riverside Park</name>"));
+    }
+
+    #[test]
+    fn errors_without_a_gpx_file_or_coordinates() {
+        let place = Place { name: "Nowhere".to_string(), ..Default::default() };
+        assert!(route_for(&place).is_err());
+    }
+
+    #[test]
+    fn escapes_special_characters_in_the_place_name() {
+        let place = Place { name: "Bob & Sons".to_string(), lat: Some(1.0), lon: Some(2.0), ..Default::default() };
+        let gpx = route_for(&place).unwrap();
+        assert!(gpx.contains("<name>Bob &amp; Sons</name>"));
+    }
+}
@@ -0,0 +1,16 @@
+//! Japanese holiday calendar, used to tell weekdays apart from days off
+//! (weekends and national holidays) for the `weekdays_only`/`holidays_only`
+//! place filters.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use jpholiday::jpholiday::JPHoliday;
+
+/// Whether `date` is a Japanese national holiday (not counting weekends).
+pub fn is_holiday(date: NaiveDate) -> bool {
+    JPHoliday::new().is_holiday(&date)
+}
+
+/// Whether `date` is a Saturday/Sunday or a Japanese national holiday.
+pub fn is_day_off(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun) || is_holiday(date)
+}
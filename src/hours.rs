@@ -0,0 +1,89 @@
+//! Opening-hours schedules for places and shops (see `src/place.rs`), kept
+//! in one place since both are filtered the same way.
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Deserializer};
+
+/// A single day's opening window, written in `place.toml` as `"09:00-18:00"`.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeRange {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+impl TimeRange {
+    fn overlaps(&self, start: NaiveTime, end: NaiveTime) -> bool {
+        self.open < end && start < self.close
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeRange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let (open, close) =
+            s.split_once('-').ok_or_else(|| serde::de::Error::custom(format!("expected \"HH:MM-HH:MM\", got {s:?}")))?;
+        let parse = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .map_err(|e| serde::de::Error::custom(format!("invalid time {t:?}: {e}")))
+        };
+        Ok(TimeRange {
+            open: parse(open)?,
+            close: parse(close)?,
+        })
+    }
+}
+
+/// A place or shop's opening hours: a window per weekday, with an optional
+/// override for national holidays (see [`crate::holiday`]). Missing days
+/// (and an `open` table omitted entirely) mean closed / always open
+/// respectively -- see [`OpeningHours::is_open_on`].
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OpeningHours {
+    #[serde(default)]
+    pub mon: Option<TimeRange>,
+    #[serde(default)]
+    pub tue: Option<TimeRange>,
+    #[serde(default)]
+    pub wed: Option<TimeRange>,
+    #[serde(default)]
+    pub thu: Option<TimeRange>,
+    #[serde(default)]
+    pub fri: Option<TimeRange>,
+    #[serde(default)]
+    pub sat: Option<TimeRange>,
+    #[serde(default)]
+    pub sun: Option<TimeRange>,
+    /// Overrides the weekday schedule on a national holiday, if set.
+    #[serde(default)]
+    pub holiday: Option<TimeRange>,
+}
+
+impl OpeningHours {
+    fn scheduled(&self, date: NaiveDate) -> Option<TimeRange> {
+        if crate::holiday::is_holiday(date) {
+            if let Some(range) = self.holiday {
+                return Some(range);
+            }
+        }
+        match date.weekday() {
+            Weekday::Mon => self.mon,
+            Weekday::Tue => self.tue,
+            Weekday::Wed => self.wed,
+            Weekday::Thu => self.thu,
+            Weekday::Fri => self.fri,
+            Weekday::Sat => self.sat,
+            Weekday::Sun => self.sun,
+        }
+    }
+
+    /// Whether this schedule has an opening window overlapping
+    /// `[start, end)` on `date`.
+    pub fn is_open_between(&self, date: NaiveDate, start: NaiveTime, end: NaiveTime) -> bool {
+        self.scheduled(date).is_some_and(|r| r.overlaps(start, end))
+    }
+
+    /// Whether this schedule has any opening window at all on `date`.
+    pub fn is_open_on(&self, date: NaiveDate) -> bool {
+        self.scheduled(date).is_some()
+    }
+}
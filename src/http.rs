@@ -0,0 +1,56 @@
+//! Shared HTTP client construction, so every outbound request (JMA tiles,
+//! AMeDAS, WBGT, typhoon track, air quality...) honors the same proxy
+//! configuration instead of each module reaching for a bare
+//! `reqwest::Client::new()`. TLS backend (native-tls vs rustls, for
+//! easier musl/ARM cross-compiles) is chosen at compile time via the
+//! `native-tls`/`rustls` cargo features -- see `Cargo.toml`.
+
+use std::sync::{Mutex, OnceLock};
+
+use serde::Deserialize;
+
+/// The `[http]` table in `place.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Proxy URL (e.g. `"http://proxy.example:8080"`) to route every
+    /// request through. `None` (the default) leaves `reqwest` to its own
+    /// defaults, which already honor `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY`
+    /// -- set this only to override those or when running somewhere that
+    /// doesn't set them (e.g. a service manager that scrubs the
+    /// environment).
+    pub proxy: Option<String>,
+}
+
+static HTTP_CONFIG: OnceLock<Mutex<HttpConfig>> = OnceLock::new();
+
+fn http_config() -> &'static Mutex<HttpConfig> {
+    HTTP_CONFIG.get_or_init(|| Mutex::new(HttpConfig::default()))
+}
+
+/// Replace the process-wide HTTP client configuration -- call this once at
+/// startup, before anything fetches, so every [`client`] call across the
+/// process sees the same proxy settings.
+pub fn configure(config: HttpConfig) {
+    *http_config().lock().unwrap() = config;
+}
+
+/// Build an HTTP client honoring the configured proxy (see [`configure`]),
+/// falling back to a plain default client -- with a warning -- if the
+/// configured proxy URL doesn't parse or the client otherwise fails to
+/// build, so a typo in `[http] proxy` degrades to "no proxy" instead of
+/// taking the whole process down.
+pub fn client() -> reqwest::Client {
+    let proxy = http_config().lock().unwrap().proxy.clone();
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = &proxy {
+        match reqwest::Proxy::all(proxy) {
+            Ok(p) => builder = builder.proxy(p),
+            Err(e) => tracing::warn!(proxy = %proxy, error = %e, "invalid [http] proxy, ignoring"),
+        }
+    }
+    builder.build().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "failed to build HTTP client with [http] settings, falling back to defaults");
+        reqwest::Client::new()
+    })
+}
@@ -0,0 +1,97 @@
+//! Importing places from someone else's export -- a Google Takeout "saved
+//! places" GeoJSON or KML file, or any other point-per-feature file in
+//! those formats -- into `place.toml` entries. See `sunnyday places
+//! import`.
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::place::Place;
+
+/// A place extracted from an imported file, before being filled in with
+/// the attributes (`walking`, `parking`, ...) only a human can answer --
+/// see [`Self::into_place`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedPlace {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub url: Option<String>,
+}
+
+impl ImportedPlace {
+    /// Turn this into a full [`Place`], filling in the attributes an
+    /// export has no way to know.
+    pub fn into_place(self, walking: bool, parking: bool) -> Place {
+        Place {
+            name: self.name,
+            walking,
+            parking,
+            lat: Some(self.lat),
+            lon: Some(self.lon),
+            url: self.url,
+            ..Place::default()
+        }
+    }
+}
+
+/// Parse `text` as GeoJSON or KML, picked by `path`'s extension (same
+/// convention as [`crate::format`]; anything other than `.kml` is tried as
+/// GeoJSON), into the places it contains.
+pub fn parse<P: AsRef<Path>>(path: P, text: &str) -> Result<Vec<ImportedPlace>> {
+    if path.as_ref().extension().and_then(|e| e.to_str()) == Some("kml") {
+        Ok(crate::kml::placemarks(text))
+    } else {
+        crate::geojson::parse_feature_collection(text)
+    }
+}
+
+/// A `[[place]]` TOML fragment for `place`, to review and paste into
+/// place.toml -- just the fields [`ImportedPlace::into_place`] fills in,
+/// not a full round-trip serialization of [`Place`].
+pub fn to_toml_fragment(place: &Place) -> String {
+    let mut fragment = format!("[[place]]\nname = {:?}\nwalking = {}\nparking = {}\n", place.name, place.walking, place.parking);
+    if let (Some(lat), Some(lon)) = (place.lat, place.lon) {
+        fragment.push_str(&format!("lat = {lat}\nlon = {lon}\n"));
+    }
+    if let Some(url) = &place.url {
+        fragment.push_str(&format!("url = {url:?}\n"));
+    }
+    fragment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_geojson_or_kml_by_extension() {
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "geometry": {"type": "Point", "coordinates": [139.0, 35.0]}, "properties": {"name": "A"}}
+        ]}"#;
+        let places = parse("export.geojson", geojson).unwrap();
+        assert_eq!(places.len(), 1);
+        assert_eq!(places[0].name, "A");
+
+        let kml = "<Placemark><name>B</name><coordinates>139.0,35.0,0</coordinates></Placemark>";
+        let places = parse("export.kml", kml).unwrap();
+        assert_eq!(places.len(), 1);
+        assert_eq!(places[0].name, "B");
+    }
+
+    #[test]
+    fn fills_in_prompted_attributes() {
+        let imported = ImportedPlace {
+            name: "Tokyo Station".to_string(),
+            lat: 35.6812,
+            lon: 139.7671,
+            url: Some("https://maps.example.com/tokyo-station".to_string()),
+        };
+        let place = imported.into_place(true, false);
+        assert_eq!(place.name, "Tokyo Station");
+        assert!(place.walking);
+        assert!(!place.parking);
+        assert_eq!(place.lat_lon(), Some((35.6812, 139.7671)));
+    }
+}
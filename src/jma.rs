@@ -0,0 +1,1829 @@
+//! Japan Meteorological Agency backend.
+//!
+//! Two independent signals are combined into a walking decision: the
+//! probability-of-precipitation (`pops`) published in the regular forecast,
+//! and rain tiles (`rasrf`, or the finer-grained `nowc` 5-minute nowcast
+//! for windows starting soon), which are decoded pixel-by-pixel against
+//! JMA's legend to estimate mm/h over the configured area.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration as StdDuration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex as AsyncMutex, OnceCell};
+
+use crate::provider::{Forecast, WeatherProvider};
+
+/// Root of the JMA "bosai" (disaster prevention) open API.
+pub const JMA_BASE: &str = "https://www.jma.go.jp/bosai";
+
+/// Part of the day a suggestion is being made for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PartOfDay {
+    Morning,
+    Afternoon,
+    /// After work, roughly 18:00-22:00.
+    Evening,
+}
+
+/// All parts of the day the tool currently knows how to evaluate.
+pub const ALL_DAY: [PartOfDay; 3] = [PartOfDay::Morning, PartOfDay::Afternoon, PartOfDay::Evening];
+
+impl PartOfDay {
+    /// JMA forecasts are published against fixed `timeDefines`; this is the
+    /// hour each part is read from. Other providers that work off hourly
+    /// data use the same hour as a stand-in for "this part of the day".
+    pub(crate) fn jma_hour(&self) -> u32 {
+        match self {
+            PartOfDay::Morning => 6,
+            PartOfDay::Afternoon => 12,
+            PartOfDay::Evening => 18,
+        }
+    }
+
+    /// When this part of the day starts, relative to `now`, in `now`'s own
+    /// timezone.
+    fn starts_at<Tz: TimeZone>(&self, now: chrono::DateTime<Tz>) -> chrono::DateTime<Tz> {
+        let today = now
+            .date_naive()
+            .and_hms_opt(self.jma_hour(), 0, 0)
+            .expect("jma_hour is always a valid hour");
+        now.timezone().from_local_datetime(&today).single().unwrap_or(now)
+    }
+
+    /// The hour this part's window ends, used by [`has_passed`].
+    fn end_hour(&self) -> u32 {
+        match self {
+            PartOfDay::Morning => 12,
+            PartOfDay::Afternoon => 18,
+            PartOfDay::Evening => 22,
+        }
+    }
+
+    /// The clock-hour window this part covers, e.g. `(6, 12)` for Morning.
+    /// Used to check a place's opening hours against the whole part, not
+    /// just a single instant.
+    pub(crate) fn window(&self) -> (u32, u32) {
+        (self.jma_hour(), self.end_hour())
+    }
+
+    /// [`Self::window`] as a pair of [`chrono::NaiveTime`]s, for opening-hours
+    /// checks that want clock times rather than bare hours.
+    pub(crate) fn naive_window(&self) -> (chrono::NaiveTime, chrono::NaiveTime) {
+        let (start, end) = self.window();
+        (chrono::NaiveTime::from_hms_opt(start, 0, 0).unwrap(), chrono::NaiveTime::from_hms_opt(end, 0, 0).unwrap())
+    }
+
+    /// Whether `now` is already past this part's window, so it no longer
+    /// makes sense to fetch or suggest for it today. `now` should be in the
+    /// area's configured timezone; see [`AreaCode::now`].
+    pub fn has_passed<Tz: TimeZone>(&self, now: chrono::DateTime<Tz>) -> bool {
+        let today = now
+            .date_naive()
+            .and_hms_opt(self.end_hour(), 0, 0)
+            .expect("end_hour is always a valid hour");
+        let end = now.timezone().from_local_datetime(&today).single().unwrap_or_else(|| now.clone());
+        now >= end
+    }
+}
+
+/// The area a user cares about, plus the thresholds that turn raw weather
+/// numbers into a walking decision.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AreaCode {
+    /// JMA "office" code, e.g. `"130000"` for Tokyo.
+    pub offices: String,
+    /// JMA "class10s" code, the finer-grained area used for tile lookups.
+    pub class10s: String,
+    /// Latitude of the point of interest, used for tile pixel lookups.
+    pub lat: f64,
+    /// Longitude of the point of interest.
+    pub lon: f64,
+    /// Precipitation (mm/h) at or above which walking is discouraged.
+    pub precipitation: f64,
+    /// Wind speed (m/s) at or above which outdoor activity, especially
+    /// cycling, is discouraged.
+    #[serde(default = "default_max_wind")]
+    pub max_wind: f64,
+    /// Whether an active JMA heavy-rain/storm/heat warning should force a
+    /// "stay in" suggestion regardless of the rain/wind thresholds above.
+    #[serde(default = "default_respect_warnings")]
+    pub respect_warnings: bool,
+    /// IANA timezone this area's part-of-day windows and "today" are
+    /// computed in. Defaults to Japan's, since JMA is the default
+    /// provider; set this explicitly for other areas so the tool gives
+    /// correct answers regardless of what timezone it happens to run in.
+    #[serde(default = "default_timezone")]
+    pub timezone: chrono_tz::Tz,
+    /// Clamp the Morning window to start at sunrise and the Evening window
+    /// to end at sunset, rather than always 06:00/22:00. Off by default.
+    #[serde(default)]
+    pub sun_aware: bool,
+    /// Probability of precipitation (%) at or above which walking is
+    /// discouraged, on top of `precipitation`'s tile-derived threshold --
+    /// see [`Self::is_rainy`]. `None` (the default) ignores `pop` entirely,
+    /// matching the old precipitation-only decision.
+    #[serde(default)]
+    pub pop_limit: Option<u32>,
+    /// Per-part overrides of `precipitation` -- e.g. a higher threshold for
+    /// a short morning walk than for an afternoon hike. Keyed by
+    /// `"morning"`/`"afternoon"`/`"evening"`; a part with no entry here
+    /// falls back to `precipitation`. See [`Self::precipitation_threshold`].
+    #[serde(default)]
+    pub precipitation_by_part: HashMap<String, f64>,
+    /// How [`precipitation_with_images`]'s per-frame estimates are combined
+    /// into one value. Defaults to `Max`, matching the old "a single rainy
+    /// frame vetoes the whole window" behavior.
+    #[serde(default)]
+    pub precipitation_aggregation: PrecipitationAggregation,
+    /// Side of the region-of-interest pixel box (see
+    /// [`count_precipitation_roi`]) each per-frame estimate is averaged
+    /// over, centered on the point of interest. Defaults to 16px, a
+    /// roughly 1.5km box at zoom 10 -- local enough to matter, wide enough
+    /// to not be thrown by a single noisy pixel.
+    #[serde(default = "default_roi_window_px")]
+    pub roi_window_px: u32,
+    /// Real-world radius (meters) `roi_window_px` should cover around the
+    /// point of interest. When set, the tile zoom level is picked
+    /// automatically (see [`tile_zoom_for_radius`]) instead of the fixed
+    /// zoom 10 tiles used when this is left unset.
+    #[serde(default)]
+    pub roi_radius_m: Option<f64>,
+    /// Warn rather than silently trust the data when the JMA forecast run
+    /// behind a [`WeatherReport`] is older than this many minutes. `None`
+    /// (the default) disables the staleness check.
+    #[serde(default)]
+    pub max_forecast_age_minutes: Option<u32>,
+    /// WBGT value (degrees Celsius-equivalent; see [`crate::wbgt`]) at or
+    /// above which heat-stroke risk vetoes walking the same way an active
+    /// JMA warning does, and indoor places are preferred. `None` (the
+    /// default) skips the WBGT check entirely.
+    #[serde(default)]
+    pub wbgt_limit: Option<f64>,
+    /// Forecast snowfall (cm; see [`snowfall`]) at or above which heavy
+    /// snow vetoes walking for that part of the day the same way an active
+    /// JMA warning does, and indoor places are preferred. Mainly useful in
+    /// Hokkaido/Tohoku, where JMA actually publishes snow amounts; `None`
+    /// (the default) skips the check entirely.
+    #[serde(default)]
+    pub snow_limit: Option<f64>,
+    /// Distance (km) within which an approaching typhoon's forecast track
+    /// (see [`crate::typhoon::nearest_approach`]) vetoes walking the same
+    /// way an active JMA warning does, and indoor places are preferred.
+    /// `None` (the default) skips the check entirely.
+    #[serde(default)]
+    pub typhoon_distance_km: Option<f64>,
+    /// PM2.5 (µg/m³; see [`crate::airquality::current_pm25`]) at or above
+    /// which poor air quality vetoes walking the same way an active JMA
+    /// warning does, and indoor places are preferred. For users who care
+    /// about more than rain -- asthma, allergies, and the like. `None`
+    /// (the default) skips the check entirely.
+    #[serde(default)]
+    pub max_pm25: Option<f64>,
+    /// Unit system precipitation is shown in -- console/web output only;
+    /// `precipitation`/`precipitation_by_part`/forecasts are always stored
+    /// and compared in mm/h regardless of this setting, so switching it
+    /// doesn't change any walking decision, only how the numbers read.
+    /// Defaults to [`Units::Metric`].
+    #[serde(default)]
+    pub units: Units,
+    /// A Rhai expression overriding the built-in rain/wind thresholds for
+    /// the walking decision (see [`crate::decision::evaluate`] and
+    /// [`crate::suggester::Suggester::suggest`]), e.g.
+    /// `"pop < 50 && precipitation < 2.0"`. Sees `pop`, `precipitation`,
+    /// `wind`, and `part` -- the request that prompted this also asked for
+    /// a `temperature` input, but nothing in this crate tracks forecast
+    /// temperature, so it's left out rather than faked. Requires the
+    /// `scripting` feature; with it disabled, or if the script fails to
+    /// parse or evaluate, the built-in thresholds are used instead. `None`
+    /// (the default) skips scripting entirely.
+    #[serde(default)]
+    pub decision_script: Option<String>,
+}
+
+/// Unit system for displaying precipitation (see [`AreaCode::units`]).
+/// Mainly for the non-JMA providers' users who think in inches rather than
+/// JMA's native mm/h.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+/// Millimeters per inch, for converting a mm/h precipitation value to
+/// [`Units::Imperial`]'s in/h.
+const MM_PER_INCH: f64 = 25.4;
+
+impl Units {
+    /// The suffix to show after a converted precipitation value, e.g.
+    /// `"1.2in/h"`.
+    fn precipitation_suffix(&self) -> &'static str {
+        match self {
+            Units::Metric => "mm/h",
+            Units::Imperial => "in/h",
+        }
+    }
+
+    /// Convert `mm_per_hour` (the unit every forecast/threshold is stored
+    /// and compared in) to this unit system, for display only.
+    fn convert_precipitation(&self, mm_per_hour: f64) -> f64 {
+        match self {
+            Units::Metric => mm_per_hour,
+            Units::Imperial => mm_per_hour / MM_PER_INCH,
+        }
+    }
+}
+
+fn default_roi_window_px() -> u32 {
+    16
+}
+
+/// The legacy fixed zoom level, used when [`AreaCode::roi_radius_m`] is unset.
+const DEFAULT_TILE_ZOOM: u8 = 10;
+
+/// The tile zoom level to fetch for `area`: auto-selected from
+/// [`AreaCode::roi_radius_m`] if set, else [`DEFAULT_TILE_ZOOM`].
+fn tile_zoom(area: &AreaCode) -> u8 {
+    match area.roi_radius_m {
+        Some(radius_m) => tile_zoom_for_radius(area.lat, radius_m, area.roi_window_px),
+        None => DEFAULT_TILE_ZOOM,
+    }
+}
+
+/// The highest zoom level (most detail, `1..=18`) at which `window_px`
+/// pixels, centered on `lat`, still span at least `radius_m` meters of
+/// diameter -- using the standard Web Mercator meters-per-pixel formula.
+/// Falls back to zoom 1 (the widest tiles) if even that can't cover
+/// `radius_m` in `window_px` pixels.
+fn tile_zoom_for_radius(lat: f64, radius_m: f64, window_px: u32) -> u8 {
+    const EARTH_CIRCUMFERENCE_M: f64 = 40_075_016.686;
+    let lat_rad = lat.to_radians();
+    for z in (1..=18u8).rev() {
+        let meters_per_pixel = EARTH_CIRCUMFERENCE_M * lat_rad.cos() / 2f64.powi(z as i32 + 8);
+        if window_px as f64 * meters_per_pixel >= 2.0 * radius_m {
+            return z;
+        }
+    }
+    1
+}
+
+/// How to combine a tile fetch's per-validtime precipitation estimates (see
+/// [`precipitation_with_images`]) into the single value checked against
+/// [`AreaCode::precipitation_threshold`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrecipitationAggregation {
+    /// The strongest frame vetoes the whole window, even a brief one.
+    #[default]
+    Max,
+    /// The average across all frames.
+    Mean,
+    /// The 75th percentile across all frames -- more forgiving of a single
+    /// brief spike than `Max`, less forgiving than `Mean`.
+    P75,
+    /// The fraction of frames (0.0-1.0) at or above
+    /// [`AreaCode::precipitation`], for areas that would rather judge "how
+    /// much of the window is rainy" than "how hard does it rain at worst".
+    /// Since this returns a fraction rather than mm/h, pair it with a
+    /// fractional [`AreaCode::precipitation_threshold`] (e.g. `0.5`), not a
+    /// mm/h one.
+    FractionAboveThreshold,
+}
+
+/// Combine `samples` (one per validtime frame) per `aggregation`.
+/// `FractionAboveThreshold` counts samples at or above `threshold`.
+fn aggregate_precipitation(samples: &[f64], threshold: f64, aggregation: PrecipitationAggregation) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    match aggregation {
+        PrecipitationAggregation::Max => samples.iter().cloned().fold(0.0, f64::max),
+        PrecipitationAggregation::Mean => samples.iter().sum::<f64>() / samples.len() as f64,
+        PrecipitationAggregation::P75 => percentile(samples, 75.0),
+        PrecipitationAggregation::FractionAboveThreshold => {
+            samples.iter().filter(|&&mmh| mmh >= threshold).count() as f64 / samples.len() as f64
+        }
+    }
+}
+
+/// Linear-interpolated percentile (0-100) of `samples`.
+fn percentile(samples: &[f64], pct: f64) -> f64 {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let rank = (pct / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    if lower == upper {
+        sorted[lower]
+    } else {
+        sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+    }
+}
+
+fn default_max_wind() -> f64 {
+    // Generous enough that areas which don't set this explicitly keep
+    // today's walking-only behavior.
+    f64::MAX
+}
+
+fn default_respect_warnings() -> bool {
+    true
+}
+
+fn default_timezone() -> chrono_tz::Tz {
+    chrono_tz::Asia::Tokyo
+}
+
+impl AreaCode {
+    /// The current time in this area's configured timezone.
+    pub fn now(&self) -> chrono::DateTime<chrono_tz::Tz> {
+        chrono::Utc::now().with_timezone(&self.timezone)
+    }
+
+    /// Whether it's too early to suggest going out for `part`. Only
+    /// relevant to `Morning`, and only when [`sun_aware`](Self::sun_aware)
+    /// is set; everything else always returns `false`.
+    pub fn part_not_yet_light(&self, part: PartOfDay, now: chrono::DateTime<chrono_tz::Tz>) -> bool {
+        if !self.sun_aware || part != PartOfDay::Morning {
+            return false;
+        }
+        match crate::sun::sunrise_sunset(self.lat, self.lon, now.date_naive(), self.timezone) {
+            Some((sunrise, _)) => now < sunrise,
+            None => false,
+        }
+    }
+
+    /// Whether `part`'s window has already ended. When
+    /// [`sun_aware`](Self::sun_aware) is set, `Evening` additionally ends
+    /// at sunset if that comes before its usual 22:00 cutoff.
+    pub fn part_has_passed(&self, part: PartOfDay, now: chrono::DateTime<chrono_tz::Tz>) -> bool {
+        if self.sun_aware && part == PartOfDay::Evening {
+            if let Some((_, sunset)) = crate::sun::sunrise_sunset(self.lat, self.lon, now.date_naive(), self.timezone)
+            {
+                if now >= sunset {
+                    return true;
+                }
+            }
+        }
+        part.has_passed(now)
+    }
+
+    /// The precipitation threshold (mm/h) that applies to `part`: its entry
+    /// in [`Self::precipitation_by_part`] if one is set, else
+    /// [`Self::precipitation`].
+    pub fn precipitation_threshold(&self, part: PartOfDay) -> f64 {
+        self.precipitation_by_part.get(part_key(part)).copied().unwrap_or(self.precipitation)
+    }
+
+    /// Whether `forecast` crosses either rain threshold for `part`:
+    /// tile-derived `precipitation` against
+    /// [`Self::precipitation_threshold`], or -- if [`Self::pop_limit`] is
+    /// set -- JMA's own probability-of-precipitation against it. Either one
+    /// being true is enough; `pop_limit` unset leaves this exactly the
+    /// precipitation-only check.
+    pub fn is_rainy(&self, part: PartOfDay, forecast: &Forecast) -> bool {
+        forecast.precipitation >= self.precipitation_threshold(part) || self.pop_limit.is_some_and(|limit| forecast.pop >= limit)
+    }
+
+    /// Whether `forecast`'s wind speed crosses [`Self::max_wind`] -- the
+    /// other half of [`crate::suggester::Suggester::suggest`]'s
+    /// `built_in_walkable` check, alongside [`Self::is_rainy`].
+    pub fn is_windy(&self, forecast: &Forecast) -> bool {
+        forecast.wind_speed >= self.max_wind
+    }
+
+    /// Convert a mm/h precipitation value to [`Self::units`] for display,
+    /// e.g. in `--explain` output or the web UI -- see [`Units::convert_precipitation`].
+    pub fn display_precipitation(&self, mm_per_hour: f64) -> f64 {
+        self.units.convert_precipitation(mm_per_hour)
+    }
+
+    /// The unit suffix matching [`Self::display_precipitation`]'s output,
+    /// e.g. `"mm/h"`/`"in/h"`.
+    pub fn precipitation_unit(&self) -> &'static str {
+        self.units.precipitation_suffix()
+    }
+}
+
+/// `"morning"`/`"afternoon"`/`"evening"`, for keying
+/// [`AreaCode::precipitation_by_part`], and for naming `part` in
+/// [`crate::decision::evaluate`]'s scope.
+pub(crate) fn part_key(part: PartOfDay) -> &'static str {
+    match part {
+        PartOfDay::Morning => "morning",
+        PartOfDay::Afternoon => "afternoon",
+        PartOfDay::Evening => "evening",
+    }
+}
+
+/// Which JMA rain-tile product to read. `Nowcast` is JMA's 5-minute,
+/// 250m-resolution product, available only for roughly the next hour;
+/// `Rasrf` is the coarser, longer-range "high-resolution precipitation
+/// nowcast" used for everything further out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileProduct {
+    Rasrf,
+    Nowcast,
+}
+
+impl TileProduct {
+    /// JMA's path segment for this product, used both in tile URLs and in
+    /// the `targetTimes.json` endpoint.
+    fn path(&self) -> &'static str {
+        match self {
+            TileProduct::Rasrf => "rasrf",
+            TileProduct::Nowcast => "nowc",
+        }
+    }
+
+    /// Prefer the high-resolution nowcast automatically when the window
+    /// we're evaluating starts within the next hour of `now`; fall back to
+    /// `rasrf` otherwise, since the nowcast product doesn't extend that
+    /// far out.
+    fn for_part<Tz: TimeZone>(part: PartOfDay, now: chrono::DateTime<Tz>) -> Self {
+        if part.starts_at(now.clone()) <= now + chrono::Duration::hours(1) {
+            TileProduct::Nowcast
+        } else {
+            TileProduct::Rasrf
+        }
+    }
+}
+
+/// A single rain-tile coordinate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tile {
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Tile {
+    /// The standard slippy-map tile containing `(lat, lon)` at zoom `z`.
+    pub fn from_lat_lon(lat: f64, lon: f64, z: u8) -> Self {
+        let n = 2f64.powi(z as i32);
+        let x = ((lon + 180.0) / 360.0 * n).floor() as u32;
+        let lat_rad = lat.to_radians();
+        let y = ((1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0
+            * n)
+            .floor() as u32;
+        Tile { z, x, y }
+    }
+
+    /// The pixel within [`Self::from_lat_lon`]'s 256x256 tile image that
+    /// `(lat, lon)` itself falls on, for [`count_precipitation_roi`].
+    pub fn pixel_for(lat: f64, lon: f64, z: u8) -> (u32, u32) {
+        let n = 2f64.powi(z as i32);
+        let x = (lon + 180.0) / 360.0 * n;
+        let lat_rad = lat.to_radians();
+        let y = (1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0 * n;
+        let px = ((x - x.floor()) * 256.0).floor() as u32;
+        let py = ((y - y.floor()) * 256.0).floor() as u32;
+        (px.min(255), py.min(255))
+    }
+
+    /// Every tile a `window`x`window` region-of-interest box centered on
+    /// `(lat, lon)`'s own pixel (see [`Self::pixel_for`]) touches: just the
+    /// one tile [`Self::from_lat_lon`] would return, unless the box crosses
+    /// a tile edge, in which case the touched neighbor (or, at a corner,
+    /// three neighbors) are included too. `x` wraps around the date line;
+    /// `y` clamps at the poles.
+    pub fn tiles_for_roi(lat: f64, lon: f64, z: u8, window: u32) -> Vec<Tile> {
+        let tile = Tile::from_lat_lon(lat, lon, z);
+        let (px, py) = Tile::pixel_for(lat, lon, z);
+        let half = window / 2;
+        let dx_range = if px < half {
+            -1..=0
+        } else if px + half > 255 {
+            0..=1
+        } else {
+            0..=0
+        };
+        let dy_range = if py < half {
+            -1..=0
+        } else if py + half > 255 {
+            0..=1
+        } else {
+            0..=0
+        };
+
+        let n = 2i64.pow(z as u32);
+        let mut tiles = Vec::new();
+        for dy in dy_range.clone() {
+            for dx in dx_range.clone() {
+                let x = (tile.x as i64 + dx).rem_euclid(n) as u32;
+                let y = (tile.y as i64 + dy).clamp(0, n - 1) as u32;
+                let candidate = Tile { z, x, y };
+                if !tiles.contains(&candidate) {
+                    tiles.push(candidate);
+                }
+            }
+        }
+        tiles
+    }
+
+    fn url(&self, product: TileProduct, basetime: &str, validtime: &str) -> String {
+        let p = product.path();
+        format!(
+            "{JMA_BASE}/jmatile/data/{p}/{basetime}/none/{validtime}/surf/{p}/{}/{}/{}.png",
+            self.z, self.x, self.y
+        )
+    }
+}
+
+/// Configuration for the process-wide tile cache (see [`configure_cache`]).
+/// Entry count, staleness, and memory footprint are all untunable by
+/// default (12 slots, no TTL, no memory cap) -- these knobs exist for
+/// deployments that fetch enough tiles to actually notice.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct TileCacheConfig {
+    /// Maximum number of tile entries to keep at once.
+    pub capacity: usize,
+    /// Drop an entry once it's been cached this many seconds, even if
+    /// there's still room for it. `None` (the default) never expires an
+    /// entry by age -- only [`Self::capacity`]/[`Self::max_memory_bytes`]
+    /// evict.
+    pub ttl_seconds: Option<u64>,
+    /// Evict entries (oldest first) once the cache's total byte size
+    /// would exceed this. `None` (the default) doesn't track memory at
+    /// all.
+    pub max_memory_bytes: Option<usize>,
+}
+
+impl Default for TileCacheConfig {
+    fn default() -> Self {
+        TileCacheConfig {
+            capacity: 12,
+            ttl_seconds: None,
+            max_memory_bytes: None,
+        }
+    }
+}
+
+static CACHE_CONFIG: OnceLock<Mutex<TileCacheConfig>> = OnceLock::new();
+
+fn cache_config() -> &'static Mutex<TileCacheConfig> {
+    CACHE_CONFIG.get_or_init(|| Mutex::new(TileCacheConfig::default()))
+}
+
+/// Replace the process-wide tile cache's configuration -- call this once
+/// at startup, before fetching anything, so every tile fetch across the
+/// process sees the same limits. Safe to call again later; it just
+/// changes what the next [`cache_push`] enforces.
+pub fn configure_cache(config: TileCacheConfig) {
+    *cache_config().lock().unwrap() = config;
+}
+
+/// One tile cache entry: the bytes plus when they were fetched, for
+/// [`TileCacheConfig::ttl_seconds`] to check against.
+struct CacheEntry {
+    bytes: Vec<u8>,
+    inserted_at: Instant,
+}
+
+/// A cache slot. Starts out empty and is filled in by whichever caller
+/// reaches it first -- [`OnceCell::get_or_try_init`] makes every other
+/// concurrent caller for the same key await that one fetch instead of
+/// starting a fetch of its own, so a burst of requests for a tile that
+/// isn't cached yet still only downloads it once.
+type Slot = Arc<OnceCell<CacheEntry>>;
+
+/// Process-wide tile cache, shared by every kind of JMA tile fetch. An
+/// async mutex, not [`std::sync::Mutex`], since it's only ever held
+/// across a map lookup/insert (never across the network fetch itself) and
+/// every caller is already in async code.
+static CACHE: OnceLock<AsyncMutex<HashMap<String, Slot>>> = OnceLock::new();
+
+fn cache() -> &'static AsyncMutex<HashMap<String, Slot>> {
+    CACHE.get_or_init(|| AsyncMutex::new(HashMap::new()))
+}
+
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+static CACHE_EVICTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Snapshot of the tile cache's hit/miss/eviction counters and current
+/// size, for the `/metrics` endpoint and anyone else who wants to know
+/// whether the cache is actually earning its keep.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub len: usize,
+}
+
+/// The tile cache's current counters; see [`CacheMetrics`].
+pub async fn cache_metrics() -> CacheMetrics {
+    CacheMetrics {
+        hits: CACHE_HITS.load(Ordering::Relaxed),
+        misses: CACHE_MISSES.load(Ordering::Relaxed),
+        evictions: CACHE_EVICTIONS.load(Ordering::Relaxed),
+        len: cache().lock().await.len(),
+    }
+}
+
+/// Whether `slot` holds a value that hasn't outlived `ttl_seconds`. An
+/// unfilled (still in-flight) slot is never considered expired.
+fn is_live(slot: &Slot, ttl_seconds: Option<u64>) -> bool {
+    match (slot.get(), ttl_seconds) {
+        (Some(entry), Some(ttl_seconds)) => entry.inserted_at.elapsed() < StdDuration::from_secs(ttl_seconds),
+        _ => true,
+    }
+}
+
+/// Get this key's cache slot, starting a fresh one if the existing entry
+/// (if any) has expired under `ttl_seconds`.
+async fn cache_slot(key: &str, ttl_seconds: Option<u64>) -> Slot {
+    let mut cache = cache().lock().await;
+    if !cache.get(key).is_some_and(|slot| is_live(slot, ttl_seconds)) && cache.remove(key).is_some() {
+        tracing::debug!(url = key, "tile cache entry expired, evicting");
+        CACHE_EVICTIONS.fetch_add(1, Ordering::Relaxed);
+        metrics::counter!("sunnyday_tile_cache_evictions_total", "reason" => "expired").increment(1);
+    }
+    cache.entry(key.to_string()).or_insert_with(|| Arc::new(OnceCell::new())).clone()
+}
+
+async fn cache_contains(key: &str) -> bool {
+    let ttl_seconds = cache_config().lock().unwrap().ttl_seconds;
+    cache()
+        .lock()
+        .await
+        .get(key)
+        .is_some_and(|slot| slot.get().is_some() && is_live(slot, ttl_seconds))
+}
+
+/// Evict arbitrary already-filled entries (in-flight slots are left
+/// alone) until the cache fits `config`'s capacity and memory limits --
+/// good enough for a best-effort cache.
+async fn enforce_limits(config: TileCacheConfig) {
+    let mut cache = cache().lock().await;
+    while cache.len() > config.capacity {
+        let Some(key) = cache.iter().find(|(_, slot)| slot.get().is_some()).map(|(k, _)| k.clone()) else { break };
+        cache.remove(&key);
+        tracing::debug!(url = key, capacity = config.capacity, "tile cache over capacity, evicting");
+        CACHE_EVICTIONS.fetch_add(1, Ordering::Relaxed);
+        metrics::counter!("sunnyday_tile_cache_evictions_total", "reason" => "capacity").increment(1);
+    }
+    if let Some(max_memory_bytes) = config.max_memory_bytes {
+        let mut total: usize = cache.values().filter_map(|slot| slot.get()).map(|entry| entry.bytes.len()).sum();
+        while total > max_memory_bytes {
+            let Some(key) = cache.iter().find(|(_, slot)| slot.get().is_some()).map(|(k, _)| k.clone()) else { break };
+            let Some(slot) = cache.remove(&key) else { break };
+            if let Some(entry) = slot.get() {
+                total -= entry.bytes.len();
+            }
+            tracing::debug!(url = key, max_memory_bytes, "tile cache over memory cap, evicting");
+            CACHE_EVICTIONS.fetch_add(1, Ordering::Relaxed);
+            metrics::counter!("sunnyday_tile_cache_evictions_total", "reason" => "memory_cap").increment(1);
+        }
+    }
+}
+
+/// Fetch `url` through the process-wide tile cache, shared by every kind
+/// of JMA tile (rain, nowcast, and [`himawari_tile_url`]'s satellite
+/// imagery) since the cache is keyed by URL, not by product -- also used
+/// by [`crate::staticmap`] for OpenStreetMap tiles, which are raster
+/// tiles too and benefit from the same cache. Concurrent fetches of the
+/// same not-yet-cached `url` share a single download (see [`Slot`]).
+#[tracing::instrument(skip(client), fields(cache_hit))]
+pub(crate) async fn fetch_bytes_cached(client: &reqwest::Client, url: String) -> Result<Vec<u8>> {
+    let config = *cache_config().lock().unwrap();
+    let slot = cache_slot(&url, config.ttl_seconds).await;
+    let already_filled = slot.initialized();
+
+    let entry = slot
+        .get_or_try_init(|| async {
+            let started = Instant::now();
+            let bytes = client.get(&url).send().await?.error_for_status()?.bytes().await?.to_vec();
+            let elapsed = started.elapsed();
+            tracing::debug!(url, elapsed_ms = elapsed.as_millis() as u64, bytes = bytes.len(), "fetched tile");
+            metrics::histogram!("sunnyday_tile_fetch_seconds").record(elapsed.as_secs_f64());
+            Ok::<CacheEntry, anyhow::Error>(CacheEntry { bytes, inserted_at: Instant::now() })
+        })
+        .await?;
+
+    tracing::Span::current().record("cache_hit", already_filled);
+    if already_filled {
+        CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+        metrics::counter!("sunnyday_tile_cache_requests_total", "result" => "hit").increment(1);
+    } else {
+        CACHE_MISSES.fetch_add(1, Ordering::Relaxed);
+        metrics::counter!("sunnyday_tile_cache_requests_total", "result" => "miss").increment(1);
+        enforce_limits(config).await;
+    }
+    Ok(entry.bytes.clone())
+}
+
+async fn fetch_tile(
+    client: &reqwest::Client,
+    tile: &Tile,
+    product: TileProduct,
+    basetime: &str,
+    validtime: &str,
+) -> Result<Vec<u8>> {
+    fetch_bytes_cached(client, tile.url(product, basetime, validtime)).await
+}
+
+/// JMA's `rasrf` legend: indexed color -> precipitation intensity (mm/h).
+/// Colors below are JMA's standard 10-step rain palette.
+const RAIN_LEGEND: &[([u8; 4], f64)] = &[
+    ([0, 65, 255, 255], 80.0),
+    ([0, 33, 255, 255], 50.0),
+    ([255, 40, 0, 255], 30.0),
+    ([255, 153, 0, 255], 20.0),
+    ([255, 245, 0, 255], 10.0),
+    ([0, 180, 255, 255], 5.0),
+    ([33, 140, 255, 255], 1.0),
+];
+
+/// `RAIN_LEGEND`'s precipitation for `color`, or `0.0` (no rain) if it
+/// doesn't match any legend entry.
+#[cfg(feature = "image")]
+fn legend_intensity(color: [u8; 4]) -> f64 {
+    RAIN_LEGEND.iter().find(|(c, _)| *c == color).map_or(0.0, |(_, mmh)| *mmh)
+}
+
+/// Decode a tile PNG into one precipitation intensity (mm/h) per pixel,
+/// plus its dimensions, for [`count_precipitation`]/
+/// [`count_precipitation_roi`]/[`count_precipitation_roi_stitched`] to read
+/// without re-deriving colors on every access.
+///
+/// JMA's `rasrf`/`nowc` tiles are palette-indexed PNGs with only a handful
+/// of distinct colors; when a tile decodes as indexed, its (small) palette
+/// is matched against [`RAIN_LEGEND`] once, and every pixel becomes a plain
+/// index lookup into the resulting table -- far cheaper than comparing all
+/// 65536 pixels' RGBA values against the legend directly, and adding a new
+/// legend color only touches this one match instead of three call sites.
+/// Falls back to decoding and matching RGBA pixels directly for any tile
+/// that isn't palette-indexed (e.g. the plain RGBA tiles synthesized by
+/// this module's tests).
+#[cfg(feature = "image")]
+fn tile_intensities(png_bytes: &[u8]) -> Result<(Vec<f64>, u32, u32)> {
+    let started = Instant::now();
+    let result = tile_intensities_uncounted(png_bytes);
+    metrics::histogram!("sunnyday_tile_decode_seconds").record(started.elapsed().as_secs_f64());
+    result
+}
+
+#[cfg(feature = "image")]
+fn tile_intensities_uncounted(png_bytes: &[u8]) -> Result<(Vec<f64>, u32, u32)> {
+    let decoder = png::Decoder::new(std::io::Cursor::new(png_bytes));
+    let mut reader = decoder.read_info().context("reading tile png header")?;
+    if reader.info().color_type == png::ColorType::Indexed {
+        let palette = reader.info().palette.clone().context("indexed tile has no palette")?;
+        let intensity_by_index: Vec<f64> =
+            palette.chunks_exact(3).map(|rgb| legend_intensity([rgb[0], rgb[1], rgb[2], 255])).collect();
+        let (width, height) = (reader.info().width, reader.info().height);
+        let mut buf = vec![0u8; reader.output_buffer_size().unwrap_or(0)];
+        let frame = reader.next_frame(&mut buf).context("decoding indexed tile")?;
+        let intensities =
+            buf[..frame.buffer_size()].iter().map(|&i| intensity_by_index.get(i as usize).copied().unwrap_or(0.0)).collect();
+        return Ok((intensities, width, height));
+    }
+
+    let img = image::load_from_memory(png_bytes).context("decoding rasrf tile")?;
+    let rgba = img.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let intensities = rgba.pixels().map(|p| legend_intensity(p.0)).collect();
+    Ok((intensities, width, height))
+}
+
+/// Decode a tile image and estimate precipitation (mm/h) by matching every
+/// pixel against [`RAIN_LEGEND`] and taking the strongest match found.
+///
+/// This is the naive "whole tile" implementation: every one of the tile's
+/// 256x256 pixels is checked, regardless of where in the tile the area of
+/// interest actually is.
+#[cfg(feature = "image")]
+pub fn count_precipitation(png_bytes: &[u8]) -> Result<f64> {
+    let (intensities, _, _) = tile_intensities(png_bytes)?;
+    Ok(intensities.into_iter().fold(0.0, f64::max))
+}
+
+/// Decode a tile image and estimate precipitation (mm/h) by averaging
+/// [`RAIN_LEGEND`] matches over a `window`x`window` pixel box centered on
+/// `center` (see [`Tile::pixel_for`]), clamped to the tile's bounds; a pixel
+/// with no legend match (no rain) counts as `0.0`.
+///
+/// Unlike [`count_precipitation`]'s whole-tile max, this reflects the rain
+/// right at the point of interest instead of being diluted or exaggerated
+/// by the rest of a tile that, at zoom 10, spans roughly 25km.
+#[cfg(feature = "image")]
+pub fn count_precipitation_roi(png_bytes: &[u8], center: (u32, u32), window: u32) -> Result<f64> {
+    let (intensities, width, height) = tile_intensities(png_bytes)?;
+    let half = window / 2;
+    let x_start = center.0.saturating_sub(half);
+    let y_start = center.1.saturating_sub(half);
+    let x_end = (center.0 + half).min(width.saturating_sub(1));
+    let y_end = (center.1 + half).min(height.saturating_sub(1));
+
+    let mut total = 0.0f64;
+    let mut count = 0u32;
+    for y in y_start..=y_end {
+        for x in x_start..=x_end {
+            total += intensities[(y * width + x) as usize];
+            count += 1;
+        }
+    }
+    Ok(if count == 0 { 0.0 } else { total / count as f64 })
+}
+
+/// Like [`count_precipitation_roi`], but the window may cross a tile edge
+/// (see [`Tile::tiles_for_roi`]) -- `tiles` supplies every tile the window
+/// touches, each paired with its decoded PNG bytes, so a pixel outside
+/// `center`'s own tile is still looked up in whichever neighbor covers it.
+/// A pixel in a tile that isn't in `tiles` (shouldn't happen if `tiles`
+/// came from `tiles_for_roi`) is skipped rather than failing outright.
+#[cfg(feature = "image")]
+pub fn count_precipitation_roi_stitched(
+    tiles: &[(Tile, Vec<u8>)],
+    center: Tile,
+    center_pixel: (u32, u32),
+    window: u32,
+) -> Result<f64> {
+    let decoded: Vec<(Tile, Vec<f64>)> =
+        tiles.iter().map(|(t, bytes)| Ok((*t, tile_intensities(bytes)?.0))).collect::<Result<_>>()?;
+
+    let global_x = center.x as i64 * 256 + center_pixel.0 as i64;
+    let global_y = center.y as i64 * 256 + center_pixel.1 as i64;
+    let half = window as i64 / 2;
+
+    let mut total = 0.0f64;
+    let mut count = 0u32;
+    for gy in (global_y - half)..=(global_y + half) {
+        for gx in (global_x - half)..=(global_x + half) {
+            let tile_x = gx.div_euclid(256) as u32;
+            let tile_y = gy.div_euclid(256) as u32;
+            let Some((_, intensities)) = decoded.iter().find(|(t, _)| t.x == tile_x && t.y == tile_y) else {
+                continue;
+            };
+            let local_x = gx.rem_euclid(256) as u32;
+            let local_y = gy.rem_euclid(256) as u32;
+            total += intensities[(local_y * 256 + local_x) as usize];
+            count += 1;
+        }
+    }
+    Ok(if count == 0 { 0.0 } else { total / count as f64 })
+}
+
+#[derive(Debug, Deserialize)]
+struct TargetTimes {
+    basetime: String,
+    validtime: Vec<String>,
+}
+
+async fn target_times_for_path(client: &reqwest::Client, path: &str) -> Result<TargetTimes> {
+    let url = format!("{JMA_BASE}/jmatile/data/{path}/targetTimes.json");
+    let times: Vec<TargetTimes> = client.get(&url).send().await?.error_for_status()?.json().await?;
+    times
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("targetTimes.json returned no entries"))
+}
+
+async fn target_times(client: &reqwest::Client, product: TileProduct) -> Result<TargetTimes> {
+    target_times_for_path(client, product.path()).await
+}
+
+/// One frame of [`precipitation_timeline`]: a single validtime's
+/// region-of-interest precipitation estimate, plus the tile it was decoded
+/// from.
+#[derive(Debug, Clone, Serialize)]
+#[cfg(feature = "image")]
+pub struct PrecipitationFrame {
+    /// The validtime this frame covers, as published by JMA (RFC 3339).
+    pub validtime: String,
+    /// Region-of-interest precipitation estimate (mm/h) for this frame --
+    /// see [`count_precipitation_roi_stitched`].
+    pub precipitation: f64,
+    /// URL of the tile this estimate was decoded from, centered on the
+    /// point of interest, so a caller can show or link to the actual frame.
+    pub tile_url: String,
+}
+
+/// Fetch the per-validtime region-of-interest precipitation series for
+/// `(lat, lon)` during `area`'s `part` of day, instead of
+/// [`precipitation_with_images`]'s single aggregated value -- e.g. for
+/// `--verbose` CLI output or `/api/timeline`, where seeing the forecast
+/// change frame by frame matters more than one collapsed number.
+#[cfg(feature = "image")]
+pub async fn precipitation_timeline(area: &AreaCode, part: PartOfDay, lat: f64, lon: f64) -> Result<Vec<PrecipitationFrame>> {
+    let client = crate::http::client();
+    let product = TileProduct::for_part(part, area.now());
+    let times = target_times(&client, product).await?;
+    let zoom = tile_zoom(area);
+    let tile = Tile::from_lat_lon(lat, lon, zoom);
+    let pixel = Tile::pixel_for(lat, lon, zoom);
+    let roi_tiles = Tile::tiles_for_roi(lat, lon, zoom, area.roi_window_px);
+
+    let mut frames = Vec::with_capacity(times.validtime.len());
+    for validtime in &times.validtime {
+        let mut fetched = Vec::with_capacity(roi_tiles.len());
+        for t in &roi_tiles {
+            fetched.push((*t, fetch_tile(&client, t, product, &times.basetime, validtime).await?));
+        }
+        let precipitation = count_precipitation_roi_stitched(&fetched, tile, pixel, area.roi_window_px)?;
+        frames.push(PrecipitationFrame {
+            validtime: validtime.clone(),
+            precipitation,
+            tile_url: tile.url(product, &times.basetime, validtime),
+        });
+    }
+    Ok(frames)
+}
+
+/// Fetch every validtime tile covering `(lat, lon)` for `area`'s part of
+/// day -- and, transparently, whichever neighboring tiles the
+/// region-of-interest window also touches (see [`Tile::tiles_for_roi`]) --
+/// and return the region-of-interest precipitation estimate that actually
+/// drives the walking decision, the whole-tile estimate for comparison
+/// (see [`count_precipitation_roi_stitched`] and [`count_precipitation`]),
+/// and the raw PNG bytes of every tile fetched (so callers can show the
+/// frames, e.g. in verbose CLI output). Each set of per-frame estimates is
+/// combined via `area`'s [`PrecipitationAggregation`] (`Max` by default).
+///
+/// The zoom level is [`AreaCode::roi_radius_m`]-derived if set, else the
+/// legacy fixed zoom 10. The `nowc` (5-minute) product is used
+/// automatically when `part` starts within the next hour, falling back to
+/// `rasrf` otherwise; see [`TileProduct::for_part`].
+#[cfg(feature = "image")]
+pub async fn precipitation_with_images(
+    area: &AreaCode,
+    part: PartOfDay,
+    lat: f64,
+    lon: f64,
+) -> Result<(f64, f64, Vec<Vec<u8>>)> {
+    let client = crate::http::client();
+    let product = TileProduct::for_part(part, area.now());
+    let times = target_times(&client, product).await?;
+    let zoom = tile_zoom(area);
+    let tile = Tile::from_lat_lon(lat, lon, zoom);
+    let pixel = Tile::pixel_for(lat, lon, zoom);
+    let roi_tiles = Tile::tiles_for_roi(lat, lon, zoom, area.roi_window_px);
+
+    let mut roi_samples = Vec::with_capacity(times.validtime.len());
+    let mut whole_tile_samples = Vec::with_capacity(times.validtime.len());
+    let mut images = Vec::new();
+    for validtime in &times.validtime {
+        let mut fetched = Vec::with_capacity(roi_tiles.len());
+        for t in &roi_tiles {
+            fetched.push((*t, fetch_tile(&client, t, product, &times.basetime, validtime).await?));
+        }
+        let center_bytes = &fetched.iter().find(|(t, _)| *t == tile).expect("tiles_for_roi always includes the center tile").1;
+        whole_tile_samples.push(count_precipitation(center_bytes)?);
+        roi_samples.push(count_precipitation_roi_stitched(&fetched, tile, pixel, area.roi_window_px)?);
+        images.extend(fetched.into_iter().map(|(_, bytes)| bytes));
+    }
+    let precipitation = aggregate_precipitation(&roi_samples, area.precipitation, area.precipitation_aggregation);
+    let whole_tile = aggregate_precipitation(&whole_tile_samples, area.precipitation, area.precipitation_aggregation);
+    Ok((precipitation, whole_tile, images))
+}
+
+/// Fetch and cache every tile `parts` would need for `area`'s own point --
+/// same fetches [`precipitation_with_images`] makes, just discarding the
+/// decoded result. Meant to run ahead of an actual request so it lands on
+/// an already-warm [`fetch_tile`] cache; see [`run_tile_prefetch_daemon`].
+/// Each part is tried independently and its own error (if any) reported
+/// alongside it, so one missing/failed part doesn't stop the others.
+#[cfg(feature = "image")]
+pub async fn prefetch_tiles(area: &AreaCode, parts: &[PartOfDay]) -> Vec<(PartOfDay, Result<()>)> {
+    let mut results = Vec::with_capacity(parts.len());
+    for &part in parts {
+        let result = precipitation_with_images(area, part, area.lat, area.lon).await.map(|_| ());
+        results.push((part, result));
+    }
+    results
+}
+
+/// Runs [`prefetch_tiles`] for every part of `area`'s day that hasn't
+/// passed yet, once every `interval`, forever -- so whichever basetime JMA
+/// is currently publishing is already cached by the time an interactive
+/// request needs it, instead of that request paying for the fetch itself.
+/// There's no push notification for a new basetime; this just polls
+/// `targetTimes.json` on a timer and relies on [`fetch_tile`]'s cache being
+/// keyed by URL (which embeds the basetime) to make an unchanged basetime's
+/// re-check cheap -- a cache hit, not a re-download. A part's fetch failure
+/// is logged and otherwise ignored, same best-effort spirit as
+/// [`weather_report`]'s callers. Meant to be spawned once per process (see
+/// `home.rs`'s startup and `console.rs`'s `daemon` command) -- the cache it
+/// warms is process-wide but in-memory, so it only helps requests served by
+/// this same process.
+#[cfg(feature = "image")]
+pub async fn run_tile_prefetch_daemon(area: AreaCode, interval: std::time::Duration) {
+    loop {
+        let now = area.now();
+        let parts: Vec<PartOfDay> = ALL_DAY.into_iter().filter(|&part| !area.part_has_passed(part, now)).collect();
+        for (part, result) in prefetch_tiles(&area, &parts).await {
+            if let Err(e) = result {
+                tracing::warn!("tile prefetch for {part:?} failed: {e:#}");
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ForecastTimeSeries {
+    #[serde(rename = "timeDefines")]
+    time_defines: Vec<String>,
+    areas: Vec<ForecastArea>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForecastArea {
+    area: ForecastAreaName,
+    pops: Option<Vec<String>>,
+    snows: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForecastAreaName {
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForecastDoc {
+    #[serde(rename = "timeSeries")]
+    time_series: Vec<ForecastTimeSeries>,
+}
+
+/// All of JMA's currently published pops slots for `area`'s `class10s`
+/// code, keyed by (date, part of day), rather than just today's `part` the
+/// way [`pops`] looks it up. The short-range series this reads from
+/// usually carries today's remaining slots plus however much of tomorrow
+/// JMA has rolled onto it by now, so evening and next-day lookups both
+/// come from here. A slot whose hour doesn't land on one of
+/// [`PartOfDay::jma_hour`]'s hours has no part of the day to file it under
+/// and is left out.
+pub async fn pops_windows(area: &AreaCode) -> Result<HashMap<(chrono::NaiveDate, PartOfDay), u32>> {
+    let url = format!("{JMA_BASE}/forecast/data/forecast/{}.json", area.offices);
+    let docs: Vec<ForecastDoc> = reqwest::get(&url).await?.error_for_status()?.json().await?;
+    extract_pops_windows(&docs, &area.class10s, area.timezone)
+}
+
+/// The parsing half of [`pops_windows`], split out so it can be exercised
+/// against a hand-built `docs` value without a network call.
+fn extract_pops_windows(
+    docs: &[ForecastDoc],
+    class10s: &str,
+    timezone: chrono_tz::Tz,
+) -> Result<HashMap<(chrono::NaiveDate, PartOfDay), u32>> {
+    let mut windows = HashMap::new();
+    for doc in docs {
+        for series in &doc.time_series {
+            let Some(forecast_area) = series.areas.iter().find(|a| a.area.code == class10s) else {
+                continue;
+            };
+            let Some(pops) = &forecast_area.pops else { continue };
+            for (time_define, pop) in series.time_defines.iter().zip(pops) {
+                let local = chrono::DateTime::parse_from_rfc3339(time_define)
+                    .context("parsing pops timeDefine")?
+                    .with_timezone(&timezone);
+                let Some(part) = ALL_DAY.into_iter().find(|p| p.jma_hour() == local.hour()) else {
+                    continue;
+                };
+                windows.insert((local.date_naive(), part), pop.parse::<u32>().context("parsing pops value")?);
+            }
+        }
+    }
+    Ok(windows)
+}
+
+/// Fetch the probability of precipitation (0-100) for `area`'s `class10s`
+/// code during `part` of today, from JMA's regular forecast.
+pub async fn pops(area: &AreaCode, part: PartOfDay) -> Result<u32> {
+    let today = area.now().date_naive();
+    pops_windows(area)
+        .await?
+        .get(&(today, part))
+        .copied()
+        .ok_or_else(|| anyhow!("no pops found for {} at {:?}", area.class10s, part))
+}
+
+/// Pull the largest number (cm) out of a JMA snowfall string, e.g. `"5~10"`
+/// for a range or `"8"` for a single figure. JMA publishes snow amounts as
+/// a range more often than a single value, and `None`/`"--"`/non-numeric
+/// entries mean "no snow expected"; taking the upper bound errs toward
+/// over- rather than under-estimating the risk a [`AreaCode::snow_limit`]
+/// check is meant to catch.
+fn parse_snow_cm(raw: &str) -> Option<f64> {
+    raw.split(|c: char| !c.is_ascii_digit() && c != '.')
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse::<f64>().ok())
+        .fold(None, |max, n| Some(max.map_or(n, |m: f64| m.max(n))))
+}
+
+/// All of JMA's currently published snowfall slots for `area`'s `class10s`
+/// code, keyed by (date, part of day) -- same shape and caveats as
+/// [`pops_windows`]. Most offices never publish `snows` at all (it's only
+/// included for regions where snow is expected), so an empty map here is
+/// normal, not an error.
+pub async fn snowfall_windows(area: &AreaCode) -> Result<HashMap<(chrono::NaiveDate, PartOfDay), f64>> {
+    let url = format!("{JMA_BASE}/forecast/data/forecast/{}.json", area.offices);
+    let docs: Vec<ForecastDoc> = reqwest::get(&url).await?.error_for_status()?.json().await?;
+    extract_snowfall_windows(&docs, &area.class10s, area.timezone)
+}
+
+/// The parsing half of [`snowfall_windows`], split out so it can be
+/// exercised against a hand-built `docs` value without a network call.
+fn extract_snowfall_windows(
+    docs: &[ForecastDoc],
+    class10s: &str,
+    timezone: chrono_tz::Tz,
+) -> Result<HashMap<(chrono::NaiveDate, PartOfDay), f64>> {
+    let mut windows = HashMap::new();
+    for doc in docs {
+        for series in &doc.time_series {
+            let Some(forecast_area) = series.areas.iter().find(|a| a.area.code == class10s) else {
+                continue;
+            };
+            let Some(snows) = &forecast_area.snows else { continue };
+            for (time_define, snow) in series.time_defines.iter().zip(snows) {
+                let Some(cm) = parse_snow_cm(snow) else { continue };
+                let local = chrono::DateTime::parse_from_rfc3339(time_define)
+                    .context("parsing snows timeDefine")?
+                    .with_timezone(&timezone);
+                let Some(part) = ALL_DAY.into_iter().find(|p| p.jma_hour() == local.hour()) else {
+                    continue;
+                };
+                windows.insert((local.date_naive(), part), cm);
+            }
+        }
+    }
+    Ok(windows)
+}
+
+/// Forecast snowfall (cm) for `area`'s `class10s` code during `part` of
+/// today, from JMA's regular forecast -- `0.0` if JMA publishes no `snows`
+/// entry for this area/time at all, since that's the common case outside
+/// snow country, not a fetch failure. See [`AreaCode::snow_limit`].
+pub async fn snowfall(area: &AreaCode, part: PartOfDay) -> Result<f64> {
+    let today = area.now().date_naive();
+    Ok(snowfall_windows(area).await?.get(&(today, part)).copied().unwrap_or(0.0))
+}
+
+/// Probability of precipitation (%) at or above which a day in the weekly
+/// forecast is marked "stay in" rather than walkable. The weekly forecast
+/// only has `pops`, not a tile-derived precipitation estimate, so there's
+/// no finer-grained threshold to lean on here.
+pub const WEEKLY_WALKABLE_POP: u32 = 50;
+
+/// Fetch the multi-day probability-of-precipitation forecast for `area`'s
+/// `class10s` code, one entry per day.
+///
+/// JMA publishes this in the same endpoint [`pops`] reads; the weekly
+/// series is simply the one, among all of a document's `timeSeries`
+/// entries for this area, with the most `timeDefines`.
+pub async fn weekly_pops(area: &AreaCode) -> Result<Vec<(chrono::NaiveDate, u32)>> {
+    let url = format!("{JMA_BASE}/forecast/data/forecast/{}.json", area.offices);
+    let docs: Vec<ForecastDoc> = reqwest::get(&url).await?.error_for_status()?.json().await?;
+
+    let mut best: Option<&ForecastTimeSeries> = None;
+    for doc in &docs {
+        for series in &doc.time_series {
+            if !series.areas.iter().any(|a| a.area.code == area.class10s) {
+                continue;
+            }
+            if best.map(|b| series.time_defines.len() > b.time_defines.len()).unwrap_or(true) {
+                best = Some(series);
+            }
+        }
+    }
+    let series = best.ok_or_else(|| anyhow!("no weekly forecast series found for {}", area.class10s))?;
+    let forecast_area = series
+        .areas
+        .iter()
+        .find(|a| a.area.code == area.class10s)
+        .ok_or_else(|| anyhow!("no weekly forecast area found for {}", area.class10s))?;
+    let pops = forecast_area
+        .pops
+        .as_ref()
+        .ok_or_else(|| anyhow!("weekly forecast series has no pops for {}", area.class10s))?;
+
+    let mut out = Vec::new();
+    for (time_define, pop) in series.time_defines.iter().zip(pops) {
+        let date = chrono::DateTime::parse_from_rfc3339(time_define)
+            .context("parsing weekly timeDefine")?
+            .with_timezone(&area.timezone)
+            .date_naive();
+        out.push((date, pop.parse::<u32>().context("parsing weekly pops value")?));
+    }
+    Ok(out)
+}
+
+/// JIS X 0401 prefecture number (1-47) and an approximate centroid,
+/// ordered by number -- used by [`guess_area_codes`] to turn a bare point
+/// into a prefecture, the first step of guessing `offices`/`class10s`.
+const PREFECTURE_CENTERS: &[(u8, f64, f64)] = &[
+    (1, 43.42, 142.86),  // Hokkaido
+    (2, 40.73, 140.96),  // Aomori
+    (3, 39.42, 141.15),  // Iwate
+    (4, 38.41, 140.87),  // Miyagi
+    (5, 39.27, 140.22),  // Akita
+    (6, 38.30, 140.22),  // Yamagata
+    (7, 37.47, 140.30),  // Fukushima
+    (8, 36.51, 140.23),  // Ibaraki
+    (9, 36.63, 139.84),  // Tochigi
+    (10, 36.56, 139.03), // Gunma
+    (11, 35.93, 139.57), // Saitama
+    (12, 35.40, 140.11), // Chiba
+    (13, 35.69, 139.69), // Tokyo
+    (14, 35.39, 139.39), // Kanagawa
+    (15, 37.80, 139.04), // Niigata
+    (16, 36.67, 137.16), // Toyama
+    (17, 36.65, 136.63), // Ishikawa
+    (18, 36.02, 136.13), // Fukui
+    (19, 35.68, 138.52), // Yamanashi
+    (20, 36.23, 138.15), // Nagano
+    (21, 35.64, 136.92), // Gifu
+    (22, 34.88, 138.40), // Shizuoka
+    (23, 35.04, 136.95), // Aichi
+    (24, 34.73, 136.42), // Mie
+    (25, 35.08, 135.99), // Shiga
+    (26, 35.05, 135.72), // Kyoto
+    (27, 34.69, 135.52), // Osaka
+    (28, 34.64, 135.13), // Hyogo
+    (29, 34.69, 135.76), // Nara
+    (30, 34.17, 135.29), // Wakayama
+    (31, 35.50, 134.24), // Tottori
+    (32, 35.47, 133.05), // Shimane
+    (33, 34.65, 133.92), // Okayama
+    (34, 34.40, 132.46), // Hiroshima
+    (35, 34.06, 131.47), // Yamaguchi
+    (36, 34.07, 134.56), // Tokushima
+    (37, 34.34, 133.96), // Kagawa
+    (38, 33.84, 132.77), // Ehime
+    (39, 33.56, 133.53), // Kochi
+    (40, 33.61, 130.42), // Fukuoka
+    (41, 33.25, 130.30), // Saga
+    (42, 32.75, 129.87), // Nagasaki
+    (43, 32.79, 130.74), // Kumamoto
+    (44, 33.24, 131.61), // Oita
+    (45, 31.91, 131.42), // Miyazaki
+    (46, 31.56, 130.56), // Kagoshima
+    (47, 26.21, 127.68), // Okinawa
+];
+
+/// Guess a prefecture's JMA office/class10s code pair from `(lat, lon)`,
+/// by finding its nearest entry in [`PREFECTURE_CENTERS`] and following
+/// the `"{pref:02}0000"`/`"{pref:02}010"` numbering most prefectures use --
+/// matching Tokyo's `"130000"`/`"130010"` in the example place.toml.
+///
+/// This is an approximation, not a real point-in-area lookup: a handful of
+/// prefectures (Hokkaido chief among them) are split into several JMA
+/// offices/class10s areas that don't follow this single-office pattern,
+/// and this can't tell which one a point actually falls in. See
+/// [`area_codes_for`], which checks the guess against JMA's own area list
+/// rather than returning it blindly.
+fn guess_area_codes(lat: f64, lon: f64) -> (String, String) {
+    let (pref, _, _) = PREFECTURE_CENTERS
+        .iter()
+        .min_by(|(_, a_lat, a_lon), (_, b_lat, b_lon)| {
+            let da = (a_lat - lat).powi(2) + (a_lon - lon).powi(2);
+            let db = (b_lat - lat).powi(2) + (b_lon - lon).powi(2);
+            da.partial_cmp(&db).unwrap()
+        })
+        .copied()
+        .unwrap_or((13, 35.69, 139.69));
+    (format!("{pref:02}0000"), format!("{pref:02}0010"))
+}
+
+/// The parts of JMA's `area.json` this module cares about: which office
+/// and class10s codes actually exist, to confirm a guess against.
+#[derive(Debug, Deserialize)]
+struct AreaTable {
+    offices: HashMap<String, serde_json::Value>,
+    class10s: HashMap<String, serde_json::Value>,
+}
+
+/// Load JMA's area code list from `cache_path` if it's already there,
+/// else fetch it and write it there for next time -- it changes rarely
+/// enough that refetching on every call would be wasteful.
+async fn area_table(client: &reqwest::Client, cache_path: &std::path::Path) -> Result<AreaTable> {
+    if let Ok(cached) = std::fs::read_to_string(cache_path) {
+        if let Ok(table) = serde_json::from_str(&cached) {
+            return Ok(table);
+        }
+    }
+    let url = format!("{JMA_BASE}/common/const/area.json");
+    let text = client.get(&url).send().await?.error_for_status()?.text().await?;
+    let _ = std::fs::write(cache_path, &text);
+    serde_json::from_str(&text).context("parsing area.json")
+}
+
+/// Guess `(offices, class10s)` for `(lat, lon)` (see [`guess_area_codes`]),
+/// then confirm both codes are real entries in JMA's own area list --
+/// downloaded once and cached at `cache_path` (see [`area_table`]) -- so a
+/// prefecture whose numbering doesn't follow the common single-office
+/// pattern fails loudly instead of silently returning a wrong area. Set
+/// `offices`/`class10s` explicitly in place.toml for those rather than
+/// relying on this.
+pub async fn area_codes_for(lat: f64, lon: f64, cache_path: &std::path::Path) -> Result<(String, String)> {
+    let (offices, class10s) = guess_area_codes(lat, lon);
+    let client = crate::http::client();
+    let table = area_table(&client, cache_path).await?;
+    if !table.offices.contains_key(&offices) {
+        return Err(anyhow!("guessed office code {offices} isn't a known JMA office -- set [area] offices/class10s explicitly"));
+    }
+    if !table.class10s.contains_key(&class10s) {
+        return Err(anyhow!("guessed class10s code {class10s} isn't a known JMA class10s area -- set [area] offices/class10s explicitly"));
+    }
+    Ok((offices, class10s))
+}
+
+/// The built-in [`WeatherProvider`] backed by JMA.
+#[cfg(feature = "image")]
+pub struct JmaProvider;
+
+#[async_trait]
+#[cfg(feature = "image")]
+impl WeatherProvider for JmaProvider {
+    fn name(&self) -> &'static str {
+        "jma"
+    }
+
+    async fn forecast(&self, area: &AreaCode, part: PartOfDay, lat_lon: Option<(f64, f64)>) -> Result<Forecast> {
+        let (lat, lon) = lat_lon.unwrap_or((area.lat, area.lon));
+        let pop = pops(area, part).await?;
+        let (precipitation, _whole_tile, _images) = precipitation_with_images(area, part, lat, lon).await?;
+        Ok(Forecast {
+            pop,
+            precipitation,
+            // JMA's regular forecast only publishes wind as descriptive
+            // text ("light wind", "near gales"), not a numeric speed; until
+            // that's parsed, `cycling`'s wind guard has no effect here.
+            wind_speed: 0.0,
+        })
+    }
+}
+
+/// Parse JMA's `basetime`/`validtime` numeric format (`yyyyMMddHHmmss`,
+/// UTC) into `tz`'s local time. `None` if `raw` doesn't parse.
+fn parse_jma_timestamp(raw: &str, tz: chrono_tz::Tz) -> Option<chrono::DateTime<chrono_tz::Tz>> {
+    let naive = chrono::NaiveDateTime::parse_from_str(raw, "%Y%m%d%H%M%S").ok()?;
+    Some(chrono::Utc.from_utc_datetime(&naive).with_timezone(&tz))
+}
+
+/// A [`Forecast`] plus where it came from and how fresh it is: the JMA run
+/// it was computed from (`basetime`), when this process fetched it, and
+/// whether the underlying tile data came from [`fetch_tile`]'s
+/// process-wide cache rather than a fresh request. See [`weather_report`].
+#[derive(Debug, Clone, Serialize)]
+pub struct WeatherReport {
+    pub forecast: Forecast,
+    /// Matches [`WeatherProvider::name`]; always `"jma"` here.
+    pub provider: &'static str,
+    /// When JMA computed the forecast run this report is built from.
+    /// `None` if `basetime` didn't parse.
+    pub basetime: Option<chrono::DateTime<chrono_tz::Tz>>,
+    /// When this process fetched the data.
+    pub fetched_at: chrono::DateTime<chrono_tz::Tz>,
+    /// Whether the tile data behind `forecast` was already in
+    /// [`fetch_tile`]'s process-wide cache rather than freshly requested.
+    pub from_cache: bool,
+}
+
+impl WeatherReport {
+    /// Whether `basetime` is more than `max_age_minutes` behind
+    /// `fetched_at` -- `false` if there's no `basetime` to compare against.
+    pub fn is_stale(&self, max_age_minutes: u32) -> bool {
+        self.basetime
+            .map(|basetime| self.fetched_at - basetime > chrono::Duration::minutes(max_age_minutes as i64))
+            .unwrap_or(false)
+    }
+}
+
+/// Fetch `area`'s forecast for `part` of day at `(lat, lon)`, the same way
+/// [`JmaProvider::forecast`] does, wrapped with data provenance --
+/// basetime and tile-cache status -- that only makes sense for JMA's own
+/// API and so isn't part of the [`WeatherProvider`] trait. Bypasses the
+/// trait the same way [`precipitation_timeline`] does, for `--verbose`
+/// CLI output and `/api/report`.
+#[cfg(feature = "image")]
+pub async fn weather_report(area: &AreaCode, part: PartOfDay, lat: f64, lon: f64) -> Result<WeatherReport> {
+    let client = crate::http::client();
+    let product = TileProduct::for_part(part, area.now());
+    let times = target_times(&client, product).await?;
+    let zoom = tile_zoom(area);
+    let tile = Tile::from_lat_lon(lat, lon, zoom);
+    let from_cache = match times.validtime.first() {
+        Some(validtime) => cache_contains(&tile.url(product, &times.basetime, validtime)).await,
+        None => false,
+    };
+
+    let pop = pops(area, part).await?;
+    let (precipitation, _whole_tile, _images) = precipitation_with_images(area, part, lat, lon).await?;
+
+    Ok(WeatherReport {
+        forecast: Forecast { pop, precipitation, wind_speed: 0.0 },
+        provider: "jma",
+        basetime: parse_jma_timestamp(&times.basetime, area.timezone),
+        fetched_at: area.now(),
+        from_cache,
+    })
+}
+
+/// JMA path segment for the Himawari true-color satellite tile product --
+/// unlike the rain/nowcast products above, these tiles are `.jpg`, not
+/// `.png`.
+const HIMAWARI_PRODUCT: &str = "satimg/TrueColor360";
+
+/// Build the Himawari satellite tile URL covering `tile`, for the given
+/// `basetime`/`validtime` (see [`himawari_target_times`]). Mirrors
+/// [`Tile::url`]'s layout, just under [`HIMAWARI_PRODUCT`] and `.jpg`.
+fn himawari_url(tile: Tile, basetime: &str, validtime: &str) -> String {
+    format!(
+        "{JMA_BASE}/jmatile/data/{HIMAWARI_PRODUCT}/{basetime}/none/{validtime}/surf/{HIMAWARI_PRODUCT}/{}/{}/{}.jpg",
+        tile.z, tile.x, tile.y
+    )
+}
+
+async fn himawari_target_times(client: &reqwest::Client) -> Result<TargetTimes> {
+    target_times_for_path(client, HIMAWARI_PRODUCT).await
+}
+
+/// Fetch the latest Himawari true-color satellite tile covering `(lat,
+/// lon)`, through [`fetch_bytes_cached`]'s same process-wide cache the
+/// rain tiles use, and return its URL -- the web UI's optional cloud-
+/// imagery panel and `/api/himawari` just hand this back rather than
+/// embedding image bytes, the same approach [`precipitation_timeline`]
+/// takes for rain frames.
+pub async fn himawari_tile_url(area: &AreaCode, lat: f64, lon: f64) -> Result<String> {
+    let client = crate::http::client();
+    let times = himawari_target_times(&client).await?;
+    let validtime = times.validtime.last().ok_or_else(|| anyhow!("himawari targetTimes.json returned no validtimes"))?;
+    let zoom = tile_zoom(area);
+    let tile = Tile::from_lat_lon(lat, lon, zoom);
+    let url = himawari_url(tile, &times.basetime, validtime);
+    fetch_bytes_cached(&client, url.clone()).await?;
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(precipitation: f64, pop_limit: Option<u32>) -> AreaCode {
+        AreaCode {
+            offices: "130000".to_string(),
+            class10s: "130010".to_string(),
+            lat: 35.0,
+            lon: 139.0,
+            precipitation,
+            max_wind: f64::MAX,
+            respect_warnings: true,
+            timezone: chrono_tz::Asia::Tokyo,
+            sun_aware: false,
+            pop_limit,
+            precipitation_by_part: HashMap::new(),
+            precipitation_aggregation: PrecipitationAggregation::default(),
+            roi_window_px: 16,
+            roi_radius_m: None,
+            max_forecast_age_minutes: None,
+            wbgt_limit: None,
+            snow_limit: None,
+            typhoon_distance_km: None,
+            max_pm25: None,
+            units: Units::default(),
+            decision_script: None,
+        }
+    }
+
+    fn forecast(pop: u32, precipitation: f64) -> Forecast {
+        Forecast { pop, precipitation, wind_speed: 0.0 }
+    }
+
+    #[test]
+    fn pop_limit_unset_only_looks_at_precipitation() {
+        let area = area(1.0, None);
+        assert!(!area.is_rainy(PartOfDay::Morning, &forecast(90, 0.0)));
+        assert!(area.is_rainy(PartOfDay::Morning, &forecast(0, 1.0)));
+    }
+
+    #[test]
+    fn pop_limit_set_rains_if_either_threshold_is_crossed() {
+        let area = area(1.0, Some(80));
+        assert!(!area.is_rainy(PartOfDay::Morning, &forecast(50, 0.0)));
+        assert!(area.is_rainy(PartOfDay::Morning, &forecast(90, 0.0)));
+        assert!(area.is_rainy(PartOfDay::Morning, &forecast(0, 1.0)));
+    }
+
+    #[test]
+    fn precipitation_threshold_falls_back_to_the_area_default() {
+        let mut area = area(1.0, None);
+        area.precipitation_by_part.insert("morning".to_string(), 3.0);
+        assert_eq!(area.precipitation_threshold(PartOfDay::Morning), 3.0);
+        assert_eq!(area.precipitation_threshold(PartOfDay::Afternoon), 1.0);
+    }
+
+    #[test]
+    fn is_rainy_uses_the_part_specific_threshold() {
+        let mut area = area(1.0, None);
+        area.precipitation_by_part.insert("morning".to_string(), 3.0);
+        assert!(!area.is_rainy(PartOfDay::Morning, &forecast(0, 2.0)));
+        assert!(area.is_rainy(PartOfDay::Afternoon, &forecast(0, 2.0)));
+    }
+
+    #[test]
+    fn is_windy_crosses_at_max_wind() {
+        let mut area = area(1.0, None);
+        area.max_wind = 8.0;
+        let mut calm = forecast(0, 0.0);
+        calm.wind_speed = 7.9;
+        assert!(!area.is_windy(&calm));
+        let mut gusty = forecast(0, 0.0);
+        gusty.wind_speed = 8.0;
+        assert!(area.is_windy(&gusty));
+    }
+
+    #[test]
+    fn aggregate_precipitation_max_is_vetoed_by_one_spike() {
+        let samples = [0.0, 0.0, 20.0, 0.0];
+        assert_eq!(aggregate_precipitation(&samples, 1.0, PrecipitationAggregation::Max), 20.0);
+    }
+
+    #[test]
+    fn aggregate_precipitation_mean_and_p75_smooth_out_a_spike() {
+        let samples = [0.0, 0.0, 20.0, 0.0];
+        assert_eq!(aggregate_precipitation(&samples, 1.0, PrecipitationAggregation::Mean), 5.0);
+        assert_eq!(aggregate_precipitation(&samples, 1.0, PrecipitationAggregation::P75), 5.0);
+    }
+
+    #[test]
+    fn aggregate_precipitation_fraction_above_threshold_counts_rainy_frames() {
+        let samples = [0.0, 2.0, 2.0, 0.0];
+        assert_eq!(
+            aggregate_precipitation(&samples, 1.0, PrecipitationAggregation::FractionAboveThreshold),
+            0.5
+        );
+    }
+
+    #[test]
+    fn aggregate_precipitation_of_no_samples_is_zero() {
+        assert_eq!(aggregate_precipitation(&[], 1.0, PrecipitationAggregation::Max), 0.0);
+    }
+
+    #[test]
+    fn pixel_for_stays_within_the_tile_its_own_tile_covers() {
+        let (lat, lon) = (35.6895, 139.6917);
+        let tile = Tile::from_lat_lon(lat, lon, 10);
+        let (px, py) = Tile::pixel_for(lat, lon, 10);
+        assert!(px < 256 && py < 256);
+        // A point a whole tile width east lands in a different tile but at
+        // roughly the same pixel row.
+        let east_tile = Tile::from_lat_lon(lat, lon + 360.0 / 2f64.powi(10), 10);
+        assert_eq!(east_tile.x, tile.x + 1);
+    }
+
+    /// A 2x2 palette-indexed PNG -- like the real `rasrf`/`nowc` tiles --
+    /// with index 0 clear and index 1 a 30mm/h legend color, arranged as
+    /// `[clear, rain, rain, clear]`.
+    #[cfg(feature = "image")]
+    fn indexed_tile() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, 2, 2);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(vec![0, 0, 0, 255, 40, 0]);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[0, 1, 1, 0]).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn tile_intensities_decodes_an_indexed_palette_tile_via_its_palette() {
+        let (intensities, width, height) = tile_intensities(&indexed_tile()).unwrap();
+        assert_eq!((width, height), (2, 2));
+        assert_eq!(intensities, vec![0.0, 30.0, 30.0, 0.0]);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn count_precipitation_reads_an_indexed_tile_the_same_as_an_rgba_one() {
+        assert_eq!(count_precipitation(&indexed_tile()).unwrap(), 30.0);
+    }
+
+    /// A 4x4 PNG: top-left quadrant heavy rain (80mm/h), everything else
+    /// clear, so a region-of-interest box can be aimed at either.
+    #[cfg(feature = "image")]
+    fn two_tone_tile() -> Vec<u8> {
+        let mut img = image::RgbaImage::new(4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                let color = if x < 2 && y < 2 { [0, 65, 255, 255] } else { [0, 0, 0, 0] };
+                img.put_pixel(x, y, image::Rgba(color));
+            }
+        }
+        let mut bytes = Vec::new();
+        image::DynamicImage::ImageRgba8(img).write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png).unwrap();
+        bytes
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn count_precipitation_roi_averages_only_the_window_around_center() {
+        let png = two_tone_tile();
+        // Centered on the rainy quadrant: all 4 pixels in a 2x2 box match.
+        assert_eq!(count_precipitation_roi(&png, (0, 0), 2).unwrap(), 80.0);
+        // Centered on the clear quadrant: no rain at all.
+        assert_eq!(count_precipitation_roi(&png, (3, 3), 2).unwrap(), 0.0);
+        // A window wide enough to straddle both quadrants averages them.
+        assert_eq!(count_precipitation_roi(&png, (1, 1), 4).unwrap(), 20.0);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn count_precipitation_roi_clamps_its_window_to_the_tile_bounds() {
+        let png = two_tone_tile();
+        // A window centered at the edge shouldn't panic by reaching past it.
+        assert_eq!(count_precipitation_roi(&png, (0, 0), 16).unwrap(), 20.0);
+    }
+
+    #[test]
+    fn tiles_for_roi_is_just_the_one_tile_away_from_its_edges() {
+        let tiles = Tile::tiles_for_roi(35.0, 139.0, 10, 16);
+        assert_eq!(tiles, vec![Tile::from_lat_lon(35.0, 139.0, 10)]);
+    }
+
+    #[test]
+    fn tiles_for_roi_includes_the_west_neighbor_near_a_tile_edge() {
+        let z = 10;
+        let lat = 35.0;
+        let tile_width_deg = 360.0 / 2f64.powi(z as i32);
+        let base = Tile::from_lat_lon(lat, 139.0, z);
+        // Just east of the tile's own west edge, so a wide window's left
+        // side spills into the tile to the west.
+        let lon = base.x as f64 * tile_width_deg - 180.0 + 0.0001;
+        let (px, _) = Tile::pixel_for(lat, lon, z);
+        assert!(px < 8);
+
+        let tiles = Tile::tiles_for_roi(lat, lon, z, 32);
+        assert_eq!(tiles.len(), 2);
+        assert!(tiles.contains(&base));
+        assert!(tiles.contains(&Tile { z, x: base.x - 1, y: base.y }));
+    }
+
+    #[test]
+    fn tile_zoom_for_radius_picks_a_closer_zoom_for_a_smaller_radius() {
+        let wide_area = tile_zoom_for_radius(35.0, 5_000.0, 16);
+        let narrow_area = tile_zoom_for_radius(35.0, 100.0, 16);
+        assert!(narrow_area > wide_area);
+    }
+
+    #[test]
+    fn tile_zoom_falls_back_to_the_legacy_zoom_when_radius_is_unset() {
+        let area = area(1.0, None);
+        assert_eq!(tile_zoom(&area), DEFAULT_TILE_ZOOM);
+    }
+
+    #[test]
+    fn tile_zoom_uses_the_radius_derived_value_when_set() {
+        let mut area = area(1.0, None);
+        area.roi_radius_m = Some(100.0);
+        assert_eq!(tile_zoom(&area), tile_zoom_for_radius(area.lat, 100.0, area.roi_window_px));
+    }
+
+    #[test]
+    fn guess_area_codes_picks_tokyo_for_a_point_near_it() {
+        assert_eq!(guess_area_codes(35.6895, 139.6917), ("130000".to_string(), "130010".to_string()));
+    }
+
+    #[test]
+    fn guess_area_codes_picks_the_nearest_prefecture_not_just_the_first() {
+        let (offices, class10s) = guess_area_codes(26.21, 127.68);
+        assert_eq!((offices.as_str(), class10s.as_str()), ("470000", "470010"));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn count_precipitation_roi_stitched_reads_across_a_tile_boundary() {
+        let z = 10;
+        let west = Tile { z, x: 500, y: 400 };
+        let east = Tile { z, x: 501, y: 400 };
+        // `west` is entirely clear; `east`'s left edge (column 0) is a
+        // 30mm/h match -- a window straddling the boundary should pick
+        // that up, not just whichever tile contains the center.
+        let west_img = image::RgbaImage::new(256, 256);
+        let mut east_img = image::RgbaImage::new(256, 256);
+        for y in 0..256 {
+            east_img.put_pixel(0, y, image::Rgba([255, 40, 0, 255]));
+        }
+        let encode = |img: image::RgbaImage| {
+            let mut bytes = Vec::new();
+            image::DynamicImage::ImageRgba8(img).write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png).unwrap();
+            bytes
+        };
+        let tiles = vec![(west, encode(west_img)), (east, encode(east_img))];
+
+        // Centered on `west`'s last column, with a window wide enough to
+        // spill one column into `east`.
+        let precipitation = count_precipitation_roi_stitched(&tiles, west, (255, 1), 2).unwrap();
+        assert_eq!(precipitation, 10.0);
+    }
+
+    #[test]
+    fn extract_pops_windows_covers_evening_and_tomorrow() {
+        // A short-range series the way JMA publishes it after noon: today's
+        // morning slot has already rolled off, but evening and tomorrow's
+        // slots are still there.
+        let docs: Vec<ForecastDoc> = serde_json::from_str(
+            r#"[{
+                "timeSeries": [{
+                    "timeDefines": [
+                        "2026-08-09T18:00:00+09:00",
+                        "2026-08-10T06:00:00+09:00",
+                        "2026-08-10T12:00:00+09:00"
+                    ],
+                    "areas": [{
+                        "area": {"code": "130010"},
+                        "pops": ["40", "20", "60"]
+                    }]
+                }]
+            }]"#,
+        )
+        .unwrap();
+
+        let windows = extract_pops_windows(&docs, "130010", chrono_tz::Asia::Tokyo).unwrap();
+
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 8, 9).unwrap();
+        let tomorrow = chrono::NaiveDate::from_ymd_opt(2026, 8, 10).unwrap();
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[&(today, PartOfDay::Evening)], 40);
+        assert_eq!(windows[&(tomorrow, PartOfDay::Morning)], 20);
+        assert_eq!(windows[&(tomorrow, PartOfDay::Afternoon)], 60);
+    }
+
+    #[test]
+    fn parse_snow_cm_takes_the_upper_bound_of_a_range() {
+        assert_eq!(parse_snow_cm("5~10"), Some(10.0));
+        assert_eq!(parse_snow_cm("8"), Some(8.0));
+        assert_eq!(parse_snow_cm("--"), None);
+    }
+
+    #[test]
+    fn extract_snowfall_windows_ignores_areas_with_no_snows_entry() {
+        let docs: Vec<ForecastDoc> = serde_json::from_str(
+            r#"[{
+                "timeSeries": [{
+                    "timeDefines": ["2026-01-09T06:00:00+09:00", "2026-01-09T12:00:00+09:00"],
+                    "areas": [
+                        {"area": {"code": "016010"}, "pops": ["40", "60"], "snows": ["5~10", "--"]},
+                        {"area": {"code": "130010"}, "pops": ["40", "60"]}
+                    ]
+                }]
+            }]"#,
+        )
+        .unwrap();
+
+        let windows = extract_snowfall_windows(&docs, "016010", chrono_tz::Asia::Tokyo).unwrap();
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 1, 9).unwrap();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[&(today, PartOfDay::Morning)], 10.0);
+
+        let empty = extract_snowfall_windows(&docs, "130010", chrono_tz::Asia::Tokyo).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn parse_jma_timestamp_reads_the_basetime_format() {
+        let parsed = parse_jma_timestamp("20260809060000", chrono_tz::Asia::Tokyo).unwrap();
+        assert_eq!(parsed.with_timezone(&chrono::Utc).to_string(), "2026-08-09 06:00:00 UTC");
+    }
+
+    #[test]
+    fn himawari_url_uses_a_jpg_extension_under_the_satimg_path() {
+        let tile = Tile { z: 5, x: 10, y: 12 };
+        let url = himawari_url(tile, "20260809060000", "20260809060000");
+        assert_eq!(
+            url,
+            format!("{JMA_BASE}/jmatile/data/satimg/TrueColor360/20260809060000/none/20260809060000/surf/satimg/TrueColor360/5/10/12.jpg")
+        );
+    }
+
+    #[test]
+    fn is_stale_compares_fetched_at_against_basetime() {
+        let basetime = chrono::Utc.with_ymd_and_hms(2026, 8, 9, 6, 0, 0).unwrap().with_timezone(&chrono_tz::Asia::Tokyo);
+        let fresh = WeatherReport {
+            forecast: Forecast { pop: 0, precipitation: 0.0, wind_speed: 0.0 },
+            provider: "jma",
+            basetime: Some(basetime),
+            fetched_at: basetime + chrono::Duration::minutes(10),
+            from_cache: false,
+        };
+        assert!(!fresh.is_stale(30));
+
+        let stale = WeatherReport { fetched_at: basetime + chrono::Duration::minutes(45), ..fresh };
+        assert!(stale.is_stale(30));
+    }
+}
@@ -0,0 +1,104 @@
+//! Raw, append-only record of every weather fetch [`crate::suggester::Suggester::suggest`]
+//! makes and the walking decision it reached -- unlike [`crate::visit::VisitLog`],
+//! which only logs suggestions a user actually answered, this captures
+//! every lookup, rain or not, answered or not. Backing data for
+//! `sunnyday stats`/`backtest` and the web UI's history chart to check the
+//! tool's own thresholds against what was actually forecast over time.
+
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::jma::PartOfDay;
+
+/// One line of [`ForecastJournal`]: a weather fetch and the decision it
+/// produced. Doesn't carry the JMA tile run's own `basetime` --
+/// [`crate::suggester::Suggester::suggest`] reaches the forecast through
+/// the generic [`crate::provider::WeatherProvider`] trait, which doesn't
+/// expose it, and only the JMA provider's own tile machinery tracks it
+/// (see [`crate::jma::WeatherReport`]) -- so `fetched_at` is the only
+/// timestamp recorded here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastRecord {
+    pub fetched_at: DateTime<Utc>,
+    pub part: PartOfDay,
+    pub pop: u32,
+    pub precipitation: f64,
+    pub walkable: bool,
+}
+
+/// Append-only JSON-lines log at `path`, same shape as [`crate::visit::VisitLog`]
+/// -- each [`Self::append`] call writes its line immediately, and a missing
+/// file reads as empty.
+pub struct ForecastJournal {
+    path: PathBuf,
+}
+
+impl ForecastJournal {
+    pub fn open<P: AsRef<Path>>(path: P) -> Self {
+        ForecastJournal { path: path.as_ref().to_path_buf() }
+    }
+
+    /// Append `record` as one JSON line, creating the file if it doesn't
+    /// exist yet.
+    pub fn append(&self, record: &ForecastRecord) -> Result<()> {
+        let line = serde_json::to_string(record).context("serializing forecast record")?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("opening {}", self.path.display()))?;
+        writeln!(file, "{line}").with_context(|| format!("writing to {}", self.path.display()))
+    }
+
+    /// Read every record logged so far, oldest first. A missing file reads
+    /// as empty, same convention as [`crate::visit::VisitLog::read_all`].
+    pub fn read_all(&self) -> Result<Vec<ForecastRecord>> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("opening {}", self.path.display())),
+        };
+        BufReader::new(file)
+            .lines()
+            .map(|line| {
+                let line = line.with_context(|| format!("reading {}", self.path.display()))?;
+                serde_json::from_str(&line).with_context(|| format!("parsing {}", self.path.display()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(pop: u32, walkable: bool) -> ForecastRecord {
+        ForecastRecord { fetched_at: Utc::now(), part: PartOfDay::Morning, pop, precipitation: 0.0, walkable }
+    }
+
+    #[test]
+    fn appended_records_read_back_in_order() {
+        let path = std::env::temp_dir().join("sunnyday-test-forecast-journal.jsonl");
+        let _ = std::fs::remove_file(&path);
+        let journal = ForecastJournal::open(&path);
+
+        journal.append(&record(10, true)).unwrap();
+        journal.append(&record(90, false)).unwrap();
+
+        let records = journal.read_all().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].pop, 10);
+        assert!(!records[1].walkable);
+    }
+
+    #[test]
+    fn reading_a_missing_journal_is_empty_not_an_error() {
+        let journal = ForecastJournal::open(std::env::temp_dir().join("sunnyday-test-forecast-journal-missing.jsonl"));
+        assert!(journal.read_all().unwrap().is_empty());
+    }
+}
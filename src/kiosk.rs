@@ -0,0 +1,54 @@
+//! Monochrome PNG rendering for e-paper/kiosk displays (`GET /kiosk.png`)
+//! -- an ESP32 e-paper frame can only fetch and blit a bitmap, it can't
+//! run a browser, so the day's suggestions and weather get rendered to a
+//! bitmap server-side instead of HTML.
+
+use anyhow::Result;
+use image::{GrayImage, Luma};
+
+mod font;
+
+/// Default resolution, matching a common low-cost e-paper panel (e.g.
+/// Waveshare's 7.5" 800x480).
+pub const DEFAULT_WIDTH: u32 = 800;
+pub const DEFAULT_HEIGHT: u32 = 480;
+
+/// Margin around the text block, in pixels.
+const MARGIN: u32 = 8;
+
+/// Render `lines` of text, one per row, as a `width`x`height` monochrome
+/// PNG: white background, black text, no anti-aliasing or grayscale --
+/// e-paper panels don't have any to spend on it. Lines are uppercased
+/// (see [`font`]'s glyph set) and truncated to fit `width`; lines past
+/// `height` are dropped rather than overflowing the image.
+pub fn render(lines: &[String], width: u32, height: u32) -> Result<Vec<u8>> {
+    let mut image = GrayImage::from_pixel(width, height, Luma([255]));
+    let chars_per_line = width.saturating_sub(2 * MARGIN) / font::GLYPH_WIDTH;
+    let rows_available = height.saturating_sub(2 * MARGIN) / font::GLYPH_HEIGHT;
+    for (row, line) in lines.iter().take(rows_available as usize).enumerate() {
+        let upper = line.to_uppercase();
+        let truncated: String = upper.chars().take(chars_per_line as usize).collect();
+        font::draw_line(&mut image, MARGIN, MARGIN + row as u32 * font::GLYPH_HEIGHT, &truncated);
+    }
+    let mut out = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageLuma8(image).write_to(&mut out, image::ImageFormat::Png)?;
+    Ok(out.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_a_valid_png_at_the_requested_resolution() {
+        let png = render(&["Morning: Sunny 10% Riverside Park".to_string()], 200, 100).unwrap();
+        let image = image::load_from_memory(&png).unwrap();
+        assert_eq!((image.width(), image.height()), (200, 100));
+    }
+
+    #[test]
+    fn does_not_panic_on_lines_or_characters_past_the_edge_of_the_image() {
+        let lines: Vec<String> = (0..50).map(|i| format!("line {i} {}", "x".repeat(200))).collect();
+        render(&lines, 64, 32).unwrap();
+    }
+}
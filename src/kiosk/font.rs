@@ -0,0 +1,83 @@
+//! A tiny embedded 5x7 dot-matrix font -- e-paper kiosk frames have no
+//! browser to hand text rendering to, and pulling in a font-rasterizing
+//! crate for a few lines of uppercase status text would be a lot of
+//! dependency for very little. Covers uppercase letters, digits, and the
+//! handful of punctuation marks [`super::render`]'s callers actually use;
+//! anything else renders as a blank cell.
+
+/// Glyph cell width/height, in pixels, including the column/row of
+/// spacing baked into each glyph's own blank border.
+pub const GLYPH_WIDTH: u32 = 6;
+pub const GLYPH_HEIGHT: u32 = 8;
+
+/// One row per pixel row, top to bottom; `#` lit, `.` blank. 5 columns wide,
+/// 7 rows tall -- [`GLYPH_WIDTH`]/[`GLYPH_HEIGHT`] add one blank column/row
+/// of spacing around it.
+fn glyph(c: char) -> [&'static str; 7] {
+    match c {
+        'A' => [".###.", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"],
+        'B' => ["####.", "#...#", "#...#", "####.", "#...#", "#...#", "####."],
+        'C' => [".###.", "#...#", "#....", "#....", "#....", "#...#", ".###."],
+        'D' => ["####.", "#...#", "#...#", "#...#", "#...#", "#...#", "####."],
+        'E' => ["#####", "#....", "#....", "####.", "#....", "#....", "#####"],
+        'F' => ["#####", "#....", "#....", "####.", "#....", "#....", "#...."],
+        'G' => [".###.", "#...#", "#....", "#.###", "#...#", "#...#", ".###."],
+        'H' => ["#...#", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"],
+        'I' => ["#####", "..#..", "..#..", "..#..", "..#..", "..#..", "#####"],
+        'J' => ["..###", "...#.", "...#.", "...#.", "...#.", "#..#.", ".##.."],
+        'K' => ["#...#", "#..#.", "#.#..", "##...", "#.#..", "#..#.", "#...#"],
+        'L' => ["#....", "#....", "#....", "#....", "#....", "#....", "#####"],
+        'M' => ["#...#", "##.##", "#.#.#", "#.#.#", "#...#", "#...#", "#...#"],
+        'N' => ["#...#", "##..#", "#.#.#", "#..##", "#...#", "#...#", "#...#"],
+        'O' => [".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."],
+        'P' => ["####.", "#...#", "#...#", "####.", "#....", "#....", "#...."],
+        'Q' => [".###.", "#...#", "#...#", "#...#", "#.#.#", "#..#.", ".##.#"],
+        'R' => ["####.", "#...#", "#...#", "####.", "#.#..", "#..#.", "#...#"],
+        'S' => [".###.", "#...#", "#....", ".###.", "....#", "#...#", ".###."],
+        'T' => ["#####", "..#..", "..#..", "..#..", "..#..", "..#..", "..#.."],
+        'U' => ["#...#", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."],
+        'V' => ["#...#", "#...#", "#...#", "#...#", "#...#", ".#.#.", "..#.."],
+        'W' => ["#...#", "#...#", "#...#", "#.#.#", "#.#.#", "##.##", "#...#"],
+        'X' => ["#...#", "#...#", ".#.#.", "..#..", ".#.#.", "#...#", "#...#"],
+        'Y' => ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#..", "..#.."],
+        'Z' => ["#####", "....#", "...#.", "..#..", ".#...", "#....", "#####"],
+        '0' => [".###.", "#...#", "#..##", "#.#.#", "##..#", "#...#", ".###."],
+        '1' => ["..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###."],
+        '2' => [".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####"],
+        '3' => ["####.", "....#", "...#.", "..##.", "....#", "#...#", ".###."],
+        '4' => ["#...#", "#...#", "#...#", "#####", "....#", "....#", "....#"],
+        '5' => ["#####", "#....", "#....", "####.", "....#", "#...#", ".###."],
+        '6' => [".###.", "#....", "#....", "####.", "#...#", "#...#", ".###."],
+        '7' => ["#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#..."],
+        '8' => [".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###."],
+        '9' => [".###.", "#...#", "#...#", ".####", "....#", "....#", ".###."],
+        ':' => [".....", "..#..", ".....", ".....", "..#..", ".....", "....."],
+        '.' => [".....", ".....", ".....", ".....", ".....", "..#..", "....."],
+        ',' => [".....", ".....", ".....", ".....", "..#..", ".#...", "....."],
+        '-' => [".....", ".....", ".....", "#####", ".....", ".....", "....."],
+        '%' => ["#...#", "....#", "...#.", "..#..", ".#...", "#....", "#...#"],
+        '/' => ["....#", "...#.", "...#.", "..#..", ".#...", ".#...", "#...."],
+        '!' => ["..#..", "..#..", "..#..", "..#..", "..#..", ".....", "..#.."],
+        '?' => [".###.", "#...#", "....#", "...#.", "..#..", ".....", "..#.."],
+        '\'' => ["..#..", "..#..", ".....", ".....", ".....", ".....", "....."],
+        _ => [".....", ".....", ".....", ".....", ".....", ".....", "....."],
+    }
+}
+
+/// Paint `text` (whatever [`glyph`] doesn't recognize renders blank) with
+/// its top-left corner at `(x, y)`, one [`GLYPH_WIDTH`]-wide cell per
+/// character.
+pub fn draw_line(image: &mut image::GrayImage, x: u32, y: u32, text: &str) {
+    let (width, height) = (image.width(), image.height());
+    for (i, c) in text.chars().enumerate() {
+        let ox = x + i as u32 * GLYPH_WIDTH;
+        for (row, pattern) in glyph(c).iter().enumerate() {
+            for (col, pixel) in pattern.chars().enumerate() {
+                let (px, py) = (ox + col as u32, y + row as u32);
+                if pixel == '#' && px < width && py < height {
+                    image.put_pixel(px, py, image::Luma([0]));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Minimal KML reader: just enough to pull `<Placemark>` points out of a
+//! Google Takeout "saved places" KML export for `sunnyday places import`,
+//! not general-purpose XML/KML support.
+
+use crate::import::ImportedPlace;
+
+/// Every `<Placemark>` in `kml` with a `<name>` and `<coordinates>`,
+/// treating `<description>` (if present) as the place's URL, since that's
+/// what Takeout puts there.
+pub fn placemarks(kml: &str) -> Vec<ImportedPlace> {
+    kml.split("<Placemark>")
+        .skip(1)
+        .filter_map(|block| {
+            let block = block.split("</Placemark>").next().unwrap_or(block);
+            let name = tag_text(block, "name")?;
+            let coordinates = tag_text(block, "coordinates")?;
+            let mut parts = coordinates.split(',');
+            let lon = parts.next()?.trim().parse().ok()?;
+            let lat = parts.next()?.trim().parse().ok()?;
+            let url = tag_text(block, "description").filter(|s| !s.is_empty());
+            Some(ImportedPlace { name, lat, lon, url })
+        })
+        .collect()
+}
+
+/// The text content of the first `<tag>...</tag>` in `xml`, or `None` if
+/// it's not present.
+fn tag_text(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&close)? + start;
+    Some(xml[start..end].trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_placemarks_with_description_as_url() {
+        let kml = r#"
+<kml>
+<Document>
+<Placemark>
+  <name>Tokyo Station</name>
+  <description>https://maps.example.com/tokyo-station</description>
+  <Point><coordinates>139.7671,35.6812,0</coordinates></Point>
+</Placemark>
+<Placemark>
+  <name>Shibuya Crossing</name>
+  <Point><coordinates>139.7006,35.6595,0</coordinates></Point>
+</Placemark>
+</Document>
+</kml>
+"#;
+        let places = placemarks(kml);
+        assert_eq!(places.len(), 2);
+        assert_eq!(places[0].name, "Tokyo Station");
+        assert_eq!(places[0].lat, 35.6812);
+        assert_eq!(places[0].lon, 139.7671);
+        assert_eq!(places[0].url.as_deref(), Some("https://maps.example.com/tokyo-station"));
+        assert_eq!(places[1].name, "Shibuya Crossing");
+        assert_eq!(places[1].url, None);
+    }
+}
@@ -0,0 +1,58 @@
+//! Core library for sunnyday: decide whether it is a good time to go out,
+//! and suggest a place, based on JMA (and other) weather data.
+
+pub mod airquality;
+pub mod amedas;
+pub mod bandit;
+#[cfg(feature = "notify")]
+pub mod calendar;
+pub mod config;
+#[cfg(feature = "scripting")]
+pub mod decision;
+pub mod distance;
+pub(crate) mod format;
+pub mod geojson;
+pub mod gpx;
+pub mod holiday;
+pub mod hours;
+pub mod http;
+pub mod import;
+pub mod jma;
+pub mod journal;
+#[cfg(feature = "image")]
+pub mod kiosk;
+pub(crate) mod kml;
+pub mod place;
+pub mod provider;
+pub mod qr;
+pub mod recent;
+#[cfg(feature = "share")]
+pub mod share;
+pub mod staticmap;
+pub mod stats;
+pub mod sun;
+pub mod suggester;
+// Socket activation and sd_notify talk directly to unix sockets/raw fds,
+// neither of which exist on wasm32 -- see the `wasm` feature.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod systemd;
+pub mod typhoon;
+pub mod visit;
+pub mod warning;
+pub mod wbgt;
+
+pub use bandit::LearningConfig;
+pub use config::WeatherConfig;
+pub use import::ImportedPlace;
+pub use jma::{AreaCode, PartOfDay, ALL_DAY};
+pub use journal::{ForecastJournal, ForecastRecord};
+pub use place::{
+    Duration, Energy, Exclusion, Home, Itinerary, Mood, Place, Places, ScoredPlace, Shop, TravelMode,
+    UnknownItineraryPlaceWarning, UnknownShopWarning,
+};
+pub use provider::WeatherProvider;
+pub use recent::RecentPlace;
+pub use stats::Stats;
+pub use suggester::{Reasoning, Suggester, Suggestion};
+pub use visit::{VisitLog, VisitOutcome, VisitRecord};
+pub use warning::Warning;
@@ -0,0 +1,1764 @@
+//! Place list: the things we might suggest going out to, and the mood-based
+//! filtering that narrows them down.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveTime};
+use clap::ValueEnum;
+use rand::seq::{IndexedRandom, SliceRandom};
+use serde::Deserialize;
+
+use crate::bandit::LearningConfig;
+use crate::config::WeatherConfig;
+use crate::hours::OpeningHours;
+use crate::jma::{AreaCode, PartOfDay};
+use crate::provider::{Forecast, WeatherProvider};
+use crate::recent::RecentPlace;
+
+/// Score multiplier applied by [`Places::rank`] to a place already in
+/// `recent`, rather than excluding it outright the way [`Places::candidates`]
+/// does.
+const RECENCY_PENALTY: f64 = 0.2;
+
+/// [`Places::dedup_same_day`]'s serde default.
+fn default_dedup_same_day() -> bool {
+    true
+}
+
+/// How a place can be reached, for the `--by`/`access` filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TravelMode {
+    Walk,
+    Bike,
+    Car,
+    Train,
+}
+
+/// How long a visit typically takes, for the `--duration` mood filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Duration {
+    Short,
+    HalfDay,
+}
+
+/// How much energy a visit takes, for the `--energy` mood filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Energy {
+    Lazy,
+    Active,
+}
+
+/// A [`Place`]'s `shop` entry with no matching `[[shop]]` table entry; see
+/// [`Places::unknown_shops`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownShopWarning {
+    pub place: String,
+    pub shop: String,
+}
+
+impl fmt::Display for UnknownShopWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "place {:?} references unknown shop {:?} (add it to [[shop]], or fix the typo)",
+            self.place, self.shop
+        )
+    }
+}
+
+/// An [`Itinerary`]'s `places` entry with no matching `[[place]]` table
+/// entry; see [`Places::unknown_itinerary_places`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownItineraryPlaceWarning {
+    pub itinerary: String,
+    pub place: String,
+}
+
+impl fmt::Display for UnknownItineraryPlaceWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "itinerary {:?} references unknown place {:?} (add it to [[place]], or fix the typo)",
+            self.itinerary, self.place
+        )
+    }
+}
+
+/// A shop (cafe, restaurant, ...) that can live inside a [`Place`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct Shop {
+    pub name: String,
+    /// Whether this shop serves food (as opposed to e.g. a vending machine).
+    #[serde(default)]
+    pub food: bool,
+    /// Opening hours; omit for a shop that's always open.
+    #[serde(default)]
+    pub open: Option<OpeningHours>,
+    /// Price level, 1 (cheap) to 4 (expensive), for the `--budget` mood
+    /// filter. Omit if it doesn't apply (e.g. a free vending machine).
+    #[serde(default)]
+    pub price: Option<u8>,
+    /// Dietary options catered to, e.g. `["vegetarian", "halal"]`, for the
+    /// `--diet` mood filter.
+    #[serde(default)]
+    pub diet: Vec<String>,
+}
+
+/// One entry in `place.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Place {
+    pub name: String,
+    /// Suitable for walking/outdoor activity.
+    #[serde(default)]
+    pub walking: bool,
+    /// Has food available nearby, via one of `shop`.
+    #[serde(default)]
+    pub food: bool,
+    /// Has parking.
+    #[serde(default)]
+    pub parking: bool,
+    /// Suitable for cycling. Separate from `walking` since a windy riverside
+    /// path can be fine on foot but unpleasant on a bike.
+    #[serde(default)]
+    pub cycling: bool,
+    /// Under a roof -- unaffected by rain, and exempt from the
+    /// precipitation check in [`Places::pickup_checked`]. Separate from
+    /// `walking` so rainy-day suggestions can require this explicitly
+    /// instead of overloading that flag.
+    #[serde(default)]
+    pub indoor: bool,
+    /// Names of shops (looked up in the top-level `shop` table) available
+    /// at this place.
+    #[serde(default)]
+    pub shop: Vec<String>,
+    /// Only suggest this place on a weekday (not a weekend or national
+    /// holiday) -- e.g. a café that's closed on days off.
+    #[serde(default)]
+    pub weekdays_only: bool,
+    /// Only suggest this place on a day off (weekend or national holiday)
+    /// -- e.g. a bigger outing that needs a whole free day.
+    #[serde(default)]
+    pub holidays_only: bool,
+    /// Free-form labels (e.g. "park", "indoor", "kids") for filtering finer
+    /// than the fixed boolean attributes above.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Opening hours; omit for a place that's always open (e.g. a park).
+    #[serde(default)]
+    pub open: Option<OpeningHours>,
+    /// This place's own coordinates, for a localized precipitation check
+    /// instead of the area-wide one -- e.g. a mountainside park that gets
+    /// rain the downtown area doesn't. Both must be set to take effect.
+    #[serde(default)]
+    pub lat: Option<f64>,
+    #[serde(default)]
+    pub lon: Option<f64>,
+    /// Travel modes this place is reachable by, e.g. `["walk", "car"]`.
+    /// Empty means unrestricted -- reachable however you're getting around.
+    #[serde(default)]
+    pub access: Vec<TravelMode>,
+    /// Relative likelihood of being picked over other matching places;
+    /// defaults to 1.0 when omitted. A favorite place with `weight = 3.0`
+    /// comes up about 3x as often as an equally-matching place left at the
+    /// default, while still leaving room for everything else to rotate in.
+    #[serde(default)]
+    pub weight: Option<f64>,
+    /// Free-form note (opening quirks, a recommendation, ...) shown
+    /// alongside a suggestion.
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Link to the place's own site (menu, hours, ...).
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Link to a map of the place, for directions.
+    #[serde(default)]
+    pub map_url: Option<String>,
+    /// Price level, 1 (cheap) to 4 (expensive), for the `--budget` mood
+    /// filter. Omit for a place with no cost of its own (e.g. a park) --
+    /// it's left in regardless of budget, same as a missing `lat`/`lon`
+    /// leaves a place out of the distance filter.
+    #[serde(default)]
+    pub price: Option<u8>,
+    /// Suitable for bringing children along.
+    #[serde(default)]
+    pub kids: bool,
+    /// Suitable for bringing a pet along.
+    #[serde(default)]
+    pub pets: bool,
+    /// Neighborhood/area label (e.g. a station name), for avoiding not just
+    /// the exact same place on consecutive suggestions but anything nearby
+    /// -- see [`RecentPlace::last_cluster`]. Places with no cluster of their
+    /// own are never excluded by this.
+    #[serde(default)]
+    pub cluster: Option<String>,
+    /// Overrides [`Places::rotation_days`] for this place specifically --
+    /// e.g. a special far-away park that should only come up monthly, or a
+    /// corner cafe fine to repeat every few days.
+    #[serde(default)]
+    pub cooldown_days: Option<u32>,
+    /// How long a visit here typically takes, for the `--duration` mood
+    /// filter. Omit for a place with no typical duration of its own (e.g.
+    /// a flexible park) -- it's left in regardless, same as a missing
+    /// `price` leaves a place out of the `--budget` filter.
+    #[serde(default)]
+    pub duration: Option<Duration>,
+    /// How much energy a visit here takes, for the `--energy` mood filter.
+    /// Same "left in if unset" fallback as `duration`.
+    #[serde(default)]
+    pub energy: Option<Energy>,
+    /// Path to a GPX file with this place's own walking route (e.g. a
+    /// riverside loop), resolved relative to the current directory, for
+    /// `sunnyday route`/`/route/{place}.gpx` (see [`crate::gpx::route_for`])
+    /// to hand back verbatim. Falls back to a single waypoint at
+    /// `lat`/`lon` when unset.
+    #[serde(default)]
+    pub gpx: Option<String>,
+}
+
+impl Place {
+    /// This place's own coordinates, if both `lat` and `lon` are set.
+    pub fn lat_lon(&self) -> Option<(f64, f64)> {
+        self.lat.zip(self.lon)
+    }
+
+    /// How many calendar days this place stays excluded after being
+    /// suggested: its own [`Self::cooldown_days`] if set, otherwise
+    /// `rotation_days` (see [`Places::rotation_days`]).
+    fn rotation_days(&self, rotation_days: u32) -> u32 {
+        self.cooldown_days.unwrap_or(rotation_days)
+    }
+
+    /// This place's sampling weight, defaulting to 1.0 when unset.
+    fn weight(&self) -> f64 {
+        self.weight.unwrap_or(1.0)
+    }
+}
+
+/// Desired attributes for a suggestion. `None`/empty means "don't care".
+#[derive(Debug, Clone, Default)]
+pub struct Mood {
+    pub walking: Option<bool>,
+    pub food: Option<bool>,
+    pub parking: Option<bool>,
+    pub cycling: Option<bool>,
+    pub indoor: Option<bool>,
+    /// Place must have all of these tags.
+    pub tags: Vec<String>,
+    /// Place must have none of these tags.
+    pub not_tags: Vec<String>,
+    /// Place must be within this many km of [`Places::home`] (straight-line
+    /// distance). Places with no coordinates of their own, or no `home`
+    /// configured, can't be judged and are left in.
+    pub max_distance_km: Option<f64>,
+    /// Place must be within this many minutes of [`Places::home`] at a
+    /// walking or cycling pace (see [`crate::distance`]), picked by the
+    /// place's own `cycling` flag. Same fallback as `max_distance_km`.
+    pub max_minutes: Option<f64>,
+    /// How you're getting around today. If set, places with a non-empty
+    /// `access` list must include this mode; places with no `access` list
+    /// are unrestricted and match regardless.
+    pub by: Option<TravelMode>,
+    /// Place's `price` must be at or below this. A place with no `price`
+    /// of its own can't be judged and is left in, same as `max_distance_km`
+    /// treats a place with no coordinates.
+    pub max_budget: Option<u8>,
+    /// Must be suitable for bringing children along.
+    pub kids: Option<bool>,
+    /// Must be suitable for bringing a pet along.
+    pub pets: Option<bool>,
+    /// Place must have an open, food-serving shop catering to all of these
+    /// (e.g. `["vegetarian"]`), checked in [`Places::candidates`] since it
+    /// needs the shop table rather than anything on [`Place`] itself.
+    pub diet: Vec<String>,
+    /// Place's `duration` must match, if both are set. Left in if either
+    /// side is unset, same as `max_budget` treats a place with no `price`.
+    pub duration: Option<Duration>,
+    /// Place's `energy` must match, if both are set. Same fallback as
+    /// `duration`.
+    pub energy: Option<Energy>,
+    /// Place or itinerary names to veto outright, regardless of how well
+    /// they'd otherwise match -- e.g. the web UI's "another one" button
+    /// re-suggesting with the place it just showed added here. Unlike
+    /// `not_tags` this is a hard filter even in [`Places::partial_matches`]'
+    /// fallback, since the whole point is "never this one, not even as a
+    /// last resort".
+    pub exclude: Vec<String>,
+}
+
+impl Mood {
+    fn matches(&self, place: &Place, has_food: bool, home: Option<(f64, f64)>) -> bool {
+        self.unmatched(place, has_food, home) == 0
+    }
+
+    /// Number of dimensions `place` fails to satisfy -- 0 means
+    /// [`Self::matches`] would return `true`. Used by [`Places::pickup`]'s
+    /// soft fallback to rank partial matches when nothing satisfies every
+    /// dimension; see [`Places::partial_matches`].
+    fn unmatched(&self, place: &Place, has_food: bool, home: Option<(f64, f64)>) -> u32 {
+        let mut unmatched = 0;
+        if self.walking.is_some_and(|walking| place.walking != walking) {
+            unmatched += 1;
+        }
+        if self.food.is_some_and(|food| has_food != food) {
+            unmatched += 1;
+        }
+        if self.parking.is_some_and(|parking| place.parking != parking) {
+            unmatched += 1;
+        }
+        if self.cycling.is_some_and(|cycling| place.cycling != cycling) {
+            unmatched += 1;
+        }
+        if self.indoor.is_some_and(|indoor| place.indoor != indoor) {
+            unmatched += 1;
+        }
+        if !self.tags.iter().all(|t| place.tags.contains(t)) {
+            unmatched += 1;
+        }
+        if self.not_tags.iter().any(|t| place.tags.contains(t)) {
+            unmatched += 1;
+        }
+        if let Some(by) = self.by {
+            if !place.access.is_empty() && !place.access.contains(&by) {
+                unmatched += 1;
+            }
+        }
+        if (self.max_distance_km.is_some() || self.max_minutes.is_some()) && !self.within_range(place, home) {
+            unmatched += 1;
+        }
+        if self.max_budget.is_some_and(|max_budget| place.price.is_some_and(|price| price > max_budget)) {
+            unmatched += 1;
+        }
+        if self.kids.is_some_and(|kids| place.kids != kids) {
+            unmatched += 1;
+        }
+        if self.pets.is_some_and(|pets| place.pets != pets) {
+            unmatched += 1;
+        }
+        if let (Some(duration), Some(place_duration)) = (self.duration, place.duration) {
+            if place_duration != duration {
+                unmatched += 1;
+            }
+        }
+        if let (Some(energy), Some(place_energy)) = (self.energy, place.energy) {
+            if place_energy != energy {
+                unmatched += 1;
+            }
+        }
+        unmatched
+    }
+
+    /// Whether `place` is close enough to `home`, per `max_distance_km`/
+    /// `max_minutes`. Returns `true` (i.e. doesn't filter it out) if either
+    /// `home` or `place`'s own coordinates are missing, since there's
+    /// nothing to judge against.
+    fn within_range(&self, place: &Place, home: Option<(f64, f64)>) -> bool {
+        let (Some(home), Some(place_lat_lon)) = (home, place.lat_lon()) else {
+            return true;
+        };
+        let distance = crate::distance::distance_km(home, place_lat_lon);
+        if self.max_distance_km.is_some_and(|max| distance > max) {
+            return false;
+        }
+        if let Some(max_minutes) = self.max_minutes {
+            let speed = if place.cycling { crate::distance::CYCLING_KMH } else { crate::distance::WALKING_KMH };
+            if crate::distance::minutes_at(distance, speed) > max_minutes {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Error parsing a compact mood string; see [`Mood`]'s `FromStr` impl.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoodParseError(String);
+
+impl fmt::Display for MoodParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for MoodParseError {}
+
+/// Sub-delimiter for a list-valued key (`tags`/`not_tags`/`diet`) in a
+/// compact mood string, since `,` already separates key=value pairs.
+const MOOD_LIST_SEP: char = '+';
+/// Value meaning "don't care" (`None`/unset) for an optional key in a
+/// compact mood string.
+const MOOD_UNSET: &str = "-";
+
+fn mood_parse_bool(key: &str, value: &str) -> Result<Option<bool>, MoodParseError> {
+    match value {
+        MOOD_UNSET | "" => Ok(None),
+        "yes" | "true" | "1" => Ok(Some(true)),
+        "no" | "false" | "0" => Ok(Some(false)),
+        other => Err(MoodParseError(format!("{key}={other:?} isn't yes/no/-"))),
+    }
+}
+
+fn mood_parse_list(value: &str) -> Vec<String> {
+    if value == MOOD_UNSET || value.is_empty() {
+        return Vec::new();
+    }
+    value.split(MOOD_LIST_SEP).map(str::to_string).filter(|s| !s.is_empty()).collect()
+}
+
+fn mood_parse_f64(key: &str, value: &str) -> Result<Option<f64>, MoodParseError> {
+    match value {
+        MOOD_UNSET | "" => Ok(None),
+        other => other.parse().map(Some).map_err(|_| MoodParseError(format!("{key}={other:?} isn't a number"))),
+    }
+}
+
+fn mood_parse_u8(key: &str, value: &str) -> Result<Option<u8>, MoodParseError> {
+    match value {
+        MOOD_UNSET | "" => Ok(None),
+        other => other.parse().map(Some).map_err(|_| MoodParseError(format!("{key}={other:?} isn't a number"))),
+    }
+}
+
+fn mood_parse_value_enum<T: clap::ValueEnum>(key: &str, value: &str) -> Result<Option<T>, MoodParseError> {
+    match value {
+        MOOD_UNSET | "" => Ok(None),
+        other => T::from_str(other, true).map(Some).map_err(|_| MoodParseError(format!("{key}={other:?} isn't valid"))),
+    }
+}
+
+impl std::str::FromStr for Mood {
+    type Err = MoodParseError;
+
+    /// Parse a compact mood string, e.g. `"food=yes,walking=no,parking=-"`:
+    /// comma-separated `key=value` pairs, one per [`Mood`] field, keyed by
+    /// the field's own name. A boolean field takes `yes`/`no`/`-` (unset);
+    /// a list field (`tags`/`not_tags`/`diet`) takes values joined with
+    /// `+`, or `-`/empty for none; `by`/`duration`/`energy` take their
+    /// usual CLI value names, or `-` for unset. Unset/omitted keys keep
+    /// [`Mood::default`]'s value, so a partial string only overrides what
+    /// it mentions.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mood = Mood::default();
+        for pair in s.split(',') {
+            let pair = pair.trim();
+            if pair.is_empty() {
+                continue;
+            }
+            let (key, value) = pair.split_once('=').ok_or_else(|| MoodParseError(format!("{pair:?} is missing '='")))?;
+            match key {
+                "walking" => mood.walking = mood_parse_bool(key, value)?,
+                "food" => mood.food = mood_parse_bool(key, value)?,
+                "parking" => mood.parking = mood_parse_bool(key, value)?,
+                "cycling" => mood.cycling = mood_parse_bool(key, value)?,
+                "indoor" => mood.indoor = mood_parse_bool(key, value)?,
+                "kids" => mood.kids = mood_parse_bool(key, value)?,
+                "pets" => mood.pets = mood_parse_bool(key, value)?,
+                "tags" => mood.tags = mood_parse_list(value),
+                "not_tags" => mood.not_tags = mood_parse_list(value),
+                "diet" => mood.diet = mood_parse_list(value),
+                "max_distance_km" => mood.max_distance_km = mood_parse_f64(key, value)?,
+                "max_minutes" => mood.max_minutes = mood_parse_f64(key, value)?,
+                "max_budget" => mood.max_budget = mood_parse_u8(key, value)?,
+                "by" => mood.by = mood_parse_value_enum(key, value)?,
+                "duration" => mood.duration = mood_parse_value_enum(key, value)?,
+                "energy" => mood.energy = mood_parse_value_enum(key, value)?,
+                other => return Err(MoodParseError(format!("unknown mood key {other:?}"))),
+            }
+        }
+        Ok(mood)
+    }
+}
+
+impl fmt::Display for Mood {
+    /// Inverse of [`Mood::from_str`]: only the fields that aren't at their
+    /// [`Mood::default`] value are written out, so round-tripping a mood
+    /// through `to_string`/`parse` is lossless but a fresh default mood
+    /// prints as an empty string.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(v) = self.walking {
+            parts.push(format!("walking={}", if v { "yes" } else { "no" }));
+        }
+        if let Some(v) = self.food {
+            parts.push(format!("food={}", if v { "yes" } else { "no" }));
+        }
+        if let Some(v) = self.parking {
+            parts.push(format!("parking={}", if v { "yes" } else { "no" }));
+        }
+        if let Some(v) = self.cycling {
+            parts.push(format!("cycling={}", if v { "yes" } else { "no" }));
+        }
+        if let Some(v) = self.indoor {
+            parts.push(format!("indoor={}", if v { "yes" } else { "no" }));
+        }
+        if let Some(v) = self.kids {
+            parts.push(format!("kids={}", if v { "yes" } else { "no" }));
+        }
+        if let Some(v) = self.pets {
+            parts.push(format!("pets={}", if v { "yes" } else { "no" }));
+        }
+        if !self.tags.is_empty() {
+            parts.push(format!("tags={}", self.tags.join(&MOOD_LIST_SEP.to_string())));
+        }
+        if !self.not_tags.is_empty() {
+            parts.push(format!("not_tags={}", self.not_tags.join(&MOOD_LIST_SEP.to_string())));
+        }
+        if !self.diet.is_empty() {
+            parts.push(format!("diet={}", self.diet.join(&MOOD_LIST_SEP.to_string())));
+        }
+        if let Some(v) = self.max_distance_km {
+            parts.push(format!("max_distance_km={v}"));
+        }
+        if let Some(v) = self.max_minutes {
+            parts.push(format!("max_minutes={v}"));
+        }
+        if let Some(v) = self.max_budget {
+            parts.push(format!("max_budget={v}"));
+        }
+        if let Some(v) = self.by {
+            parts.push(format!("by={}", v.to_possible_value().expect("no skipped values").get_name()));
+        }
+        if let Some(v) = self.duration {
+            parts.push(format!("duration={}", v.to_possible_value().expect("no skipped values").get_name()));
+        }
+        if let Some(v) = self.energy {
+            parts.push(format!("energy={}", v.to_possible_value().expect("no skipped values").get_name()));
+        }
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+impl serde::Serialize for Mood {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Mood {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Where the user is starting from, for the `max_distance_km`/`max_minutes`
+/// mood filters. Omit the whole `[home]` table to skip that filtering.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Home {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// A named combo of places suggested together for one part of the day,
+/// e.g. "riverside walk + bakery" -- `places` lists [`Place`] names (looked
+/// up in the top-level `place` table), visited in the order given. See
+/// [`Places::pickup_itinerary`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct Itinerary {
+    pub name: String,
+    pub places: Vec<String>,
+}
+
+/// The full place list loaded from `place.toml`: the area to check weather
+/// for, places, and the shop table they reference.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Places {
+    pub area: AreaCode,
+    #[serde(default)]
+    pub weather: WeatherConfig,
+    /// Tuning for the process-wide tile cache (see
+    /// [`crate::jma::configure_cache`]); untunable (12 slots, no TTL, no
+    /// memory cap) if omitted.
+    #[serde(default)]
+    pub cache: crate::jma::TileCacheConfig,
+    /// Proxy settings for every outbound request (see
+    /// [`crate::http::configure`]); no proxy override if omitted.
+    #[serde(default)]
+    pub http: crate::http::HttpConfig,
+    #[serde(default)]
+    pub home: Option<Home>,
+    /// Where to create a calendar event for an accepted suggestion (see
+    /// [`crate::calendar`]); absent means no calendar integration, the
+    /// same as every other optional nested config above. Requires the
+    /// `notify` feature to actually do anything.
+    #[cfg(feature = "notify")]
+    #[serde(default)]
+    pub calendar: Option<crate::calendar::CalendarConfig>,
+    /// How many calendar days a suggested place stays excluded from
+    /// further suggestions (see [`RecentPlace::check`]).
+    #[serde(default = "crate::recent::default_rotation_days")]
+    pub rotation_days: u32,
+    /// Epsilon-greedy bias toward places actually accepted when suggested
+    /// (see [`crate::visit`]), on top of the plain [`Place::weight`]
+    /// everything above already uses. Off by default.
+    #[serde(default)]
+    pub learning: LearningConfig,
+    /// Never suggest the same place for two parts of the same day, even if
+    /// its own `cooldown_days` (or a `rotation_days` of 0) would otherwise
+    /// allow it -- see [`RecentPlace::suggested_today`]. On by default.
+    #[serde(default = "default_dedup_same_day")]
+    pub dedup_same_day: bool,
+    pub place: Vec<Place>,
+    #[serde(default)]
+    pub shop: Vec<Shop>,
+    /// Named combos of places (see [`Itinerary`]) that [`Self::pickup_itinerary`]
+    /// can suggest as a whole.
+    #[serde(default)]
+    pub itinerary: Vec<Itinerary>,
+    /// Other place files to merge in, resolved relative to this file's own
+    /// directory -- e.g. `["parks.toml", "cafes.toml"]`, so a large list
+    /// doesn't have to live in one file. Each one only needs `place`/`shop`
+    /// tables of its own; `area`/`weather`/`home` stay in the main file.
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+/// A [`Place`] together with the score [`Places::rank`] gave it -- higher
+/// is better.
+#[derive(Debug, Clone)]
+pub struct ScoredPlace {
+    pub place: Place,
+    pub score: f64,
+}
+
+/// Why one place didn't make [`Places::rank`]'s output -- see
+/// [`Places::explain`].
+#[derive(Debug, Clone)]
+pub struct Exclusion {
+    pub place: String,
+    pub reason: String,
+}
+
+/// An `include`d file: just more places and shops, merged into the main
+/// [`Places`] list by [`Places::read`].
+#[derive(Debug, Deserialize)]
+struct PlaceFile {
+    #[serde(default)]
+    place: Vec<Place>,
+    #[serde(default)]
+    shop: Vec<Shop>,
+}
+
+impl Places {
+    /// Load a place list from a toml, yaml, or json file (picked by
+    /// extension; see [`crate::format`]), merging in anything listed in its
+    /// `include`. Passing a directory reads `place.toml` within it, so
+    /// `include` can do the rest of the splitting up.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Places> {
+        let mut path = path.as_ref().to_path_buf();
+        if path.is_dir() {
+            path.push("place.toml");
+        }
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let mut places: Places = crate::format::parse(&path, &text).context("parsing place list")?;
+
+        let base = path.parent().unwrap_or_else(|| Path::new("."));
+        for include in places.include.drain(..).collect::<Vec<_>>() {
+            let include_path = base.join(&include);
+            let text = std::fs::read_to_string(&include_path)
+                .with_context(|| format!("reading {}", include_path.display()))?;
+            let fragment: PlaceFile = crate::format::parse(&include_path, &text)
+                .with_context(|| format!("parsing {}", include_path.display()))?;
+            places.place.extend(fragment.place);
+            places.shop.extend(fragment.shop);
+        }
+
+        places.validate()?;
+        Ok(places)
+    }
+
+    /// Load a place list from literal TOML text instead of a file --
+    /// e.g. `sunnyday-web`'s `SUNNYDAY_PLACES_TOML`, for running with no
+    /// file on disk at all. `include` isn't supported, since there's no
+    /// directory to resolve it relative to; a list needing one should use
+    /// [`Self::read`] instead.
+    pub fn parse_toml(text: &str) -> Result<Places> {
+        let places: Places = toml::from_str(text).context("parsing place list")?;
+        if !places.include.is_empty() {
+            anyhow::bail!("`include` isn't supported when loading a place list from literal TOML");
+        }
+        places.validate()?;
+        Ok(places)
+    }
+
+    /// Check the place list for configuration mistakes serious enough to
+    /// refuse to run with -- duplicate place/shop names, malformed area
+    /// codes, and out-of-range coordinates -- reporting every problem found
+    /// at once rather than stopping at the first. Runs automatically at the
+    /// end of [`Self::read`]; also exposed for the console `validate`
+    /// subcommand to call explicitly.
+    ///
+    /// A `shop` reference with no matching `[[shop]]` entry is *not*
+    /// included here, since it's recoverable (treated as "no food" by
+    /// [`Self::has_food`]); see [`Self::unknown_shops`] for that instead.
+    /// Same goes for an `itinerary` leg with no matching `[[place]]` entry
+    /// -- see [`Self::unknown_itinerary_places`].
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        push_duplicates(&mut problems, "place", self.place.iter().map(|p| p.name.as_str()));
+        push_duplicates(&mut problems, "shop", self.shop.iter().map(|s| s.name.as_str()));
+        push_duplicates(&mut problems, "itinerary", self.itinerary.iter().map(|i| i.name.as_str()));
+
+        if !is_six_digit_code(&self.area.offices) {
+            problems.push(format!(
+                "[area] offices {:?} should be a 6-digit JMA office code, e.g. \"130000\"",
+                self.area.offices
+            ));
+        }
+        if !is_six_digit_code(&self.area.class10s) {
+            problems.push(format!(
+                "[area] class10s {:?} should be a 6-digit JMA class10s code, e.g. \"130010\"",
+                self.area.class10s
+            ));
+        } else if is_six_digit_code(&self.area.offices) && self.area.offices[..2] != self.area.class10s[..2] {
+            problems.push(format!(
+                "[area] class10s {:?} doesn't look like it belongs to offices {:?} (JMA codes share their first 2 digits); double check against JMA's area list",
+                self.area.class10s, self.area.offices
+            ));
+        }
+
+        push_lat_lon(&mut problems, "[area]".to_string(), self.area.lat, self.area.lon);
+        if let Some(home) = self.home {
+            push_lat_lon(&mut problems, "[home]".to_string(), home.lat, home.lon);
+        }
+        for place in &self.place {
+            if let Some((lat, lon)) = place.lat_lon() {
+                push_lat_lon(&mut problems, format!("place {:?}", place.name), lat, lon);
+            }
+            if let Some(price) = place.price {
+                push_price(&mut problems, format!("place {:?}", place.name), price);
+            }
+        }
+        for shop in &self.shop {
+            if let Some(price) = shop.price {
+                push_price(&mut problems, format!("shop {:?}", shop.name), price);
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("place list failed validation:\n- {}", problems.join("\n- "));
+        }
+    }
+
+    fn shop_table(&self) -> HashMap<&str, &Shop> {
+        self.shop.iter().map(|s| (s.name.as_str(), s)).collect()
+    }
+
+    /// Look up a configured place by its exact `name`, for `sunnyday
+    /// route`/`/route/{place}.gpx` (see [`crate::gpx::route_for`]).
+    pub fn place_by_name(&self, name: &str) -> Option<&Place> {
+        self.place.iter().find(|p| p.name == name)
+    }
+
+    /// Every `shop` name referenced by a [`Place`] with no matching
+    /// `[[shop]]` entry. [`Self::has_food`] treats these as "no food"
+    /// rather than failing, since a typo there shouldn't stop the tool
+    /// from running -- but it's still worth surfacing so the typo gets
+    /// fixed, which callers can do with these.
+    pub fn unknown_shops(&self) -> Vec<UnknownShopWarning> {
+        let shop_names = self.shop_table();
+        self.place
+            .iter()
+            .flat_map(|place| {
+                place
+                    .shop
+                    .iter()
+                    .filter(|shop| !shop_names.contains_key(shop.as_str()))
+                    .map(|shop| UnknownShopWarning {
+                        place: place.name.clone(),
+                        shop: shop.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Every `itinerary` leg with no matching `[[place]]` entry; see
+    /// [`Self::unknown_shops`] for why this is a warning rather than a hard
+    /// validation failure.
+    pub fn unknown_itinerary_places(&self) -> Vec<UnknownItineraryPlaceWarning> {
+        let place_names: HashSet<&str> = self.place.iter().map(|p| p.name.as_str()).collect();
+        self.itinerary
+            .iter()
+            .flat_map(|itinerary| {
+                itinerary
+                    .places
+                    .iter()
+                    .filter(|name| !place_names.contains(name.as_str()))
+                    .map(|name| UnknownItineraryPlaceWarning {
+                        itinerary: itinerary.name.clone(),
+                        place: name.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Whether `open` covers `date`, restricted to `window` (a part-of-day's
+    /// clock-hour range) if given, or any time at all on `date` if not (the
+    /// week-ahead planner doesn't suggest a specific time of day). `None`
+    /// means always open.
+    fn is_open(open: &Option<OpeningHours>, date: NaiveDate, window: Option<(NaiveTime, NaiveTime)>) -> bool {
+        match (open, window) {
+            (None, _) => true,
+            (Some(h), Some((start, end))) => h.is_open_between(date, start, end),
+            (Some(h), None) => h.is_open_on(date),
+        }
+    }
+
+    /// Whether `place` has any shop serving food that's open, per the `shop`
+    /// table. Shop names with no matching entry are silently treated as "no
+    /// food".
+    fn has_food(
+        &self,
+        place: &Place,
+        shops: &HashMap<&str, &Shop>,
+        date: NaiveDate,
+        window: Option<(NaiveTime, NaiveTime)>,
+    ) -> bool {
+        place
+            .shop
+            .iter()
+            .filter_map(|name| shops.get(name.as_str()))
+            .any(|s| s.food && Self::is_open(&s.open, date, window))
+    }
+
+    /// Whether `place` has any open, food-serving shop catering to every
+    /// dietary option in `diet` (e.g. `["vegetarian"]`). An empty `diet`
+    /// always passes.
+    fn satisfies_diet(
+        &self,
+        place: &Place,
+        shops: &HashMap<&str, &Shop>,
+        date: NaiveDate,
+        window: Option<(NaiveTime, NaiveTime)>,
+        diet: &[String],
+    ) -> bool {
+        if diet.is_empty() {
+            return true;
+        }
+        place.shop.iter().filter_map(|name| shops.get(name.as_str())).any(|s| {
+            s.food && Self::is_open(&s.open, date, window) && diet.iter().all(|d| s.diet.contains(d))
+        })
+    }
+
+    /// Whether `place` is open, matches `mood` (including `diet`), and is
+    /// allowed on `date` per its `weekdays_only`/`holidays_only` flags --
+    /// everything [`Self::leg_matches`] checks except recency, which
+    /// [`Self::rank`] turns into a score penalty instead of a hard filter.
+    #[allow(clippy::too_many_arguments)]
+    fn open_and_desired(
+        &self,
+        place: &Place,
+        mood: &Mood,
+        date: NaiveDate,
+        window: Option<(NaiveTime, NaiveTime)>,
+        day_off: bool,
+        home: Option<(f64, f64)>,
+        shops: &HashMap<&str, &Shop>,
+    ) -> bool {
+        Self::is_open(&place.open, date, window)
+            && mood.matches(place, place.food || self.has_food(place, shops, date, window), home)
+            && self.satisfies_diet(place, shops, date, window, &mood.diet)
+            && (!place.weekdays_only || !day_off)
+            && (!place.holidays_only || day_off)
+    }
+
+    /// Like [`Self::open_and_desired`], but doesn't treat `mood` as a hard
+    /// filter: any open, diet-satisfying, weekday-allowed place passes,
+    /// paired with [`Mood::unmatched`]'s count of how many of `mood`'s own
+    /// dimensions it fails. `None` means the non-mood checks failed, same
+    /// as [`Self::open_and_desired`] returning `false`. Feeds
+    /// [`Self::partial_matches`].
+    #[allow(clippy::too_many_arguments)]
+    fn open_regardless_of_mood(
+        &self,
+        place: &Place,
+        mood: &Mood,
+        date: NaiveDate,
+        window: Option<(NaiveTime, NaiveTime)>,
+        day_off: bool,
+        home: Option<(f64, f64)>,
+        shops: &HashMap<&str, &Shop>,
+    ) -> Option<u32> {
+        if Self::is_open(&place.open, date, window)
+            && self.satisfies_diet(place, shops, date, window, &mood.diet)
+            && (!place.weekdays_only || !day_off)
+            && (!place.holidays_only || day_off)
+        {
+            Some(mood.unmatched(place, place.food || self.has_food(place, shops, date, window), home))
+        } else {
+            None
+        }
+    }
+
+    /// Whether `place` is a valid pick right now: [`Self::open_and_desired`]
+    /// and not in `recent` (by name or by [`Place::cluster`]). Shared
+    /// between [`Self::candidates`] (over the whole place list) and
+    /// [`Self::pickup_itinerary`] (over each leg of a specific
+    /// [`Itinerary`]).
+    #[allow(clippy::too_many_arguments)]
+    fn leg_matches(
+        &self,
+        place: &Place,
+        mood: &Mood,
+        recent: &RecentPlace,
+        date: NaiveDate,
+        part: Option<PartOfDay>,
+        window: Option<(NaiveTime, NaiveTime)>,
+        day_off: bool,
+        home: Option<(f64, f64)>,
+        shops: &HashMap<&str, &Shop>,
+    ) -> bool {
+        !mood.exclude.iter().any(|name| name == &place.name)
+            && self.open_and_desired(place, mood, date, window, day_off, home, shops)
+            && self.not_recently_used(place, recent, date, part)
+    }
+
+    /// Whether `place` is allowed past recency/cluster/blacklist/same-day-
+    /// dedup -- the part of [`Self::leg_matches`] that doesn't depend on
+    /// `mood`, shared with [`Self::partial_matches`] which skips `mood` as
+    /// a hard filter but still needs these.
+    fn not_recently_used(&self, place: &Place, recent: &RecentPlace, date: NaiveDate, part: Option<PartOfDay>) -> bool {
+        !(recent.check(&place.name, date, place.rotation_days(self.rotation_days))
+            || place.cluster.as_deref().is_some_and(|c| Some(c) == recent.last_cluster())
+            || recent.excluded(&place.name, date)
+            || (self.dedup_same_day && recent.suggested_for_another_part_today(&place.name, date, part)))
+    }
+
+    /// [`Place::weight`], biased by `acceptance` if [`Self::learning`] is
+    /// enabled (see [`LearningConfig::weigh`]); otherwise `place.weight()`
+    /// unchanged. `acceptance` is keyed by place name, e.g.
+    /// [`crate::stats::acceptance_rates`]'s return value.
+    fn weighted(&self, place: &Place, acceptance: Option<&HashMap<String, f64>>) -> f64 {
+        if !self.learning.enabled {
+            return place.weight();
+        }
+        self.learning.weigh(place.weight(), acceptance.and_then(|a| a.get(&place.name).copied()))
+    }
+
+    /// Every place matching `mood`, excluding anything in `recent`, ordered
+    /// by weighted-random draw (see [`Self::weighted`]) so favorites surface
+    /// more often without ever fully crowding out the rest. `date` is used
+    /// for the `weekdays_only`/`holidays_only` filters and opening-hours
+    /// checks; `part` additionally restricts the opening-hours check to
+    /// that part's window, or pass `None` (as the week-ahead planner does)
+    /// to just check whether the place opens at all that day. `acceptance`
+    /// is only consulted when [`Self::learning`] is enabled; pass `None`
+    /// if the caller has no visit log to offer (e.g. [`Self::rank`]'s debug
+    /// paths).
+    ///
+    /// Callers that care about a place's own precipitation (see
+    /// [`Place::lat_lon`]) should walk this list and check each candidate
+    /// themselves, rather than assuming the first one is suitable; plain
+    /// [`Self::pickup`] does not do this.
+    pub fn candidates(
+        &self,
+        mood: &Mood,
+        recent: &RecentPlace,
+        date: NaiveDate,
+        part: Option<PartOfDay>,
+        acceptance: Option<&HashMap<String, f64>>,
+    ) -> Vec<Place> {
+        let shops = self.shop_table();
+        let day_off = crate::holiday::is_day_off(date);
+        let window = part.map(|p| p.naive_window());
+        let home = self.home.map(|h| (h.lat, h.lon));
+
+        let mut candidates: Vec<&Place> = self
+            .place
+            .iter()
+            .filter(|p| self.leg_matches(p, mood, recent, date, part, window, day_off, home, &shops))
+            .collect();
+
+        let mut rng = rand::rng();
+        let n = candidates.len();
+        match candidates.as_slice().sample_weighted(&mut rng, n, |p| self.weighted(p, acceptance)) {
+            Ok(sampled) => sampled.map(|p| (*p).clone()).collect(),
+            // All-zero (or otherwise unusable) weights: fall back to a
+            // plain shuffle rather than returning nothing.
+            Err(_) => {
+                candidates.shuffle(&mut rng);
+                candidates.into_iter().cloned().collect()
+            }
+        }
+    }
+
+    /// Pick a random place matching `mood`, excluding anything in `recent`.
+    /// See [`Self::candidates`] for the meaning of `date`/`part`/`acceptance`.
+    /// If nothing satisfies every dimension of `mood`, falls back to the
+    /// closest partial match (see [`Self::partial_matches`]) rather than
+    /// giving up; only returns `None` if even that finds nothing open.
+    pub fn pickup(
+        &self,
+        mood: &Mood,
+        recent: &RecentPlace,
+        date: NaiveDate,
+        part: Option<PartOfDay>,
+        acceptance: Option<&HashMap<String, f64>>,
+    ) -> Option<Place> {
+        if let Some(place) = self.candidates(mood, recent, date, part, acceptance).into_iter().next() {
+            return Some(place);
+        }
+        self.partial_matches(mood, recent, date, part, acceptance).into_iter().next().map(|scored| scored.place)
+    }
+
+    /// Every place open on `date`/`part` and allowed past recency/cluster/
+    /// blacklist/same-day-dedup (the same hard filters [`Self::candidates`]
+    /// applies), ranked by how many of `mood`'s own dimensions they satisfy
+    /// rather than requiring all of them -- [`Self::weighted`] as a
+    /// tiebreaker among equally-close matches. Used by [`Self::pickup`] so
+    /// "nothing matches today's mood exactly" still surfaces the closest
+    /// thing instead of nothing.
+    pub fn partial_matches(
+        &self,
+        mood: &Mood,
+        recent: &RecentPlace,
+        date: NaiveDate,
+        part: Option<PartOfDay>,
+        acceptance: Option<&HashMap<String, f64>>,
+    ) -> Vec<ScoredPlace> {
+        let shops = self.shop_table();
+        let day_off = crate::holiday::is_day_off(date);
+        let window = part.map(|p| p.naive_window());
+        let home = self.home.map(|h| (h.lat, h.lon));
+
+        let mut scored: Vec<(u32, ScoredPlace)> = self
+            .place
+            .iter()
+            .filter_map(|place| {
+                if mood.exclude.iter().any(|name| name == &place.name) {
+                    return None;
+                }
+                let unmatched = self.open_regardless_of_mood(place, mood, date, window, day_off, home, &shops)?;
+                self.not_recently_used(place, recent, date, part).then_some((place, unmatched))
+            })
+            .map(|(place, unmatched)| {
+                let score = self.weighted(place, acceptance) / (1.0 + unmatched as f64);
+                (unmatched, ScoredPlace { place: place.clone(), score })
+            })
+            .collect();
+
+        // Fewer unmatched dimensions wins outright; `score` (which already
+        // folds weight into the same ordering) only breaks ties within a
+        // given unmatched count, so a heavily-weighted favorite never
+        // outranks a closer match just by being popular.
+        scored.sort_by(|(a_unmatched, a), (b_unmatched, b)| {
+            a_unmatched.cmp(b_unmatched).then_with(|| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal))
+        });
+        scored.into_iter().map(|(_, scored)| scored).collect()
+    }
+
+    /// Score every place matching `mood` and open on `date`/`part` (same
+    /// hard filters as [`Self::candidates`] minus the recency exclusion --
+    /// a recently-suggested place still shows up here, just penalized),
+    /// highest score first. The score is [`Self::weighted`] (so it picks up
+    /// any learned acceptance bias from `acceptance`), multiplied by a
+    /// penalty if the place is in `recent` and, when both the place and
+    /// [`Self::home`] have coordinates, a bonus for being closer to home.
+    /// Unlike [`Self::candidates`]' weighted random draw this order is
+    /// deterministic, for consumers (e.g. both bundled binaries, or a
+    /// caller embedding this library) that want to show *why* one place
+    /// beat another rather than just receive a single pick.
+    pub fn rank(
+        &self,
+        mood: &Mood,
+        recent: &RecentPlace,
+        date: NaiveDate,
+        part: Option<PartOfDay>,
+        acceptance: Option<&HashMap<String, f64>>,
+    ) -> Vec<ScoredPlace> {
+        let shops = self.shop_table();
+        let day_off = crate::holiday::is_day_off(date);
+        let window = part.map(|p| p.naive_window());
+        let home = self.home.map(|h| (h.lat, h.lon));
+
+        let mut scored: Vec<ScoredPlace> = self
+            .place
+            .iter()
+            .filter(|p| !mood.exclude.iter().any(|name| name == &p.name))
+            .filter(|p| self.open_and_desired(p, mood, date, window, day_off, home, &shops))
+            .filter(|p| p.cluster.as_deref().is_none_or(|c| Some(c) != recent.last_cluster()))
+            .filter(|p| !recent.excluded(&p.name, date))
+            .filter(|p| !(self.dedup_same_day && recent.suggested_for_another_part_today(&p.name, date, part)))
+            .map(|place| {
+                let mut score = self.weighted(place, acceptance);
+                if recent.check(&place.name, date, place.rotation_days(self.rotation_days)) {
+                    score *= RECENCY_PENALTY;
+                }
+                if let (Some(home), Some(place_lat_lon)) = (home, place.lat_lon()) {
+                    score *= 1.0 / (1.0 + crate::distance::distance_km(home, place_lat_lon));
+                }
+                ScoredPlace { place: place.clone(), score }
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        scored
+    }
+
+    /// Why each place missing from [`Self::rank`]'s output didn't make it --
+    /// [`crate::suggester::Suggester::suggest`] folds this into
+    /// [`crate::suggester::Reasoning`] for `--explain`/the web UI's
+    /// collapsible detail section, since `rank` itself only says a place is
+    /// missing, not why.
+    pub fn explain(&self, mood: &Mood, recent: &RecentPlace, date: NaiveDate, part: Option<PartOfDay>) -> Vec<Exclusion> {
+        let shops = self.shop_table();
+        let day_off = crate::holiday::is_day_off(date);
+        let window = part.map(|p| p.naive_window());
+        let home = self.home.map(|h| (h.lat, h.lon));
+
+        self.place
+            .iter()
+            .filter_map(|place| {
+                let mut reasons = Vec::new();
+                if mood.exclude.iter().any(|name| name == &place.name) {
+                    reasons.push("excluded for this request".to_string());
+                }
+                if !Self::is_open(&place.open, date, window) {
+                    reasons.push("closed".to_string());
+                }
+                let has_food = place.food || self.has_food(place, &shops, date, window);
+                let unmatched = mood.unmatched(place, has_food, home);
+                if unmatched > 0 {
+                    reasons.push(format!("doesn't match {unmatched} mood constraint(s)"));
+                }
+                if !self.satisfies_diet(place, &shops, date, window, &mood.diet) {
+                    reasons.push("no shop covers the diet requirement".to_string());
+                }
+                if place.weekdays_only && day_off {
+                    reasons.push("weekdays only".to_string());
+                }
+                if place.holidays_only && !day_off {
+                    reasons.push("holidays only".to_string());
+                }
+                if place.cluster.as_deref().is_some_and(|c| Some(c) == recent.last_cluster()) {
+                    reasons.push("same cluster as the last pick".to_string());
+                }
+                if recent.excluded(&place.name, date) {
+                    reasons.push("blacklisted or snoozed".to_string());
+                }
+                if self.dedup_same_day && recent.suggested_for_another_part_today(&place.name, date, part) {
+                    reasons.push("already suggested for another part today".to_string());
+                }
+                if reasons.is_empty() {
+                    None
+                } else {
+                    Some(Exclusion { place: place.name.clone(), reason: reasons.join(", ") })
+                }
+            })
+            .collect()
+    }
+
+    /// Pick a concrete open, food-serving [`Shop`] at `place`, excluding
+    /// anything in `recent` (tracked separately from places, see
+    /// [`RecentPlace::contains_shop`]), so picking the same café inside the
+    /// same park every week doesn't feel like the tool isn't trying. `date`
+    /// and `part` mean the same as in [`Self::candidates`]. Returns `None`
+    /// if `place` has no matching shop.
+    pub fn pickup_shop(
+        &self,
+        place: &Place,
+        recent: &RecentPlace,
+        date: NaiveDate,
+        part: Option<PartOfDay>,
+    ) -> Option<Shop> {
+        let window = part.map(|p| p.naive_window());
+        let shops = self.shop_table();
+        let mut candidates: Vec<&Shop> = place
+            .shop
+            .iter()
+            .filter_map(|name| shops.get(name.as_str()).copied())
+            .filter(|s| s.food && Self::is_open(&s.open, date, window))
+            .filter(|s| !recent.contains_shop(&s.name))
+            .collect();
+        let mut rng = rand::rng();
+        candidates.shuffle(&mut rng);
+        candidates.into_iter().next().cloned()
+    }
+
+    /// Like [`Self::pickup`], but rejects a candidate with its own
+    /// coordinates (see [`Place::lat_lon`]) if a fresh [`WeatherProvider`]
+    /// lookup at that point shows too much rain there, even though
+    /// `area_forecast` (the already-fetched area-wide forecast for `part`)
+    /// looked fine. Places with no coordinates of their own are judged by
+    /// `area_forecast` alone, same as before.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn pickup_checked(
+        &self,
+        mood: &Mood,
+        recent: &RecentPlace,
+        date: NaiveDate,
+        part: PartOfDay,
+        provider: &dyn WeatherProvider,
+        area_forecast: &Forecast,
+        acceptance: Option<&HashMap<String, f64>>,
+    ) -> Option<Place> {
+        for place in self.candidates(mood, recent, date, Some(part), acceptance) {
+            if self.clears_precipitation(&place, part, provider, area_forecast).await {
+                return Some(place);
+            }
+        }
+        None
+    }
+
+    /// Whether `place` clears the precipitation check for `part`: an
+    /// `indoor` place always does; an outdoor one is checked at its own
+    /// coordinates (see [`Place::lat_lon`]) via a fresh [`WeatherProvider`]
+    /// lookup if it has them, falling back to the already-fetched
+    /// `area_forecast` otherwise or if that lookup fails.
+    async fn clears_precipitation(&self, place: &Place, part: PartOfDay, provider: &dyn WeatherProvider, area_forecast: &Forecast) -> bool {
+        if place.indoor {
+            return true;
+        }
+        let precipitation = match place.lat_lon() {
+            Some(lat_lon) => match provider.forecast(&self.area, part, Some(lat_lon)).await {
+                Ok(forecast) => forecast.precipitation,
+                Err(_) => area_forecast.precipitation,
+            },
+            None => area_forecast.precipitation,
+        };
+        precipitation < self.area.precipitation_threshold(part)
+    }
+
+    /// Like [`Self::pickup_checked`], but for a whole [`Itinerary`]: returns
+    /// the first configured itinerary whose every leg is open, matches
+    /// `mood`, isn't in `recent`, and clears the precipitation check for
+    /// `part` (see [`Self::clears_precipitation`]), along with the resolved
+    /// [`Place`]s in order. An itinerary naming a place that doesn't exist
+    /// (see [`Self::unknown_itinerary_places`]) is skipped, same as one
+    /// with a leg that doesn't currently match. Returns `None` if no
+    /// itinerary is fully available.
+    pub async fn pickup_itinerary(
+        &self,
+        mood: &Mood,
+        recent: &RecentPlace,
+        date: NaiveDate,
+        part: PartOfDay,
+        provider: &dyn WeatherProvider,
+        area_forecast: &Forecast,
+    ) -> Option<(&Itinerary, Vec<Place>)> {
+        let shops = self.shop_table();
+        let day_off = crate::holiday::is_day_off(date);
+        let window = Some(part.naive_window());
+        let home = self.home.map(|h| (h.lat, h.lon));
+
+        'itineraries: for itinerary in &self.itinerary {
+            if mood.exclude.iter().any(|name| name == &itinerary.name) {
+                continue;
+            }
+            let mut legs = Vec::with_capacity(itinerary.places.len());
+            for name in &itinerary.places {
+                let Some(place) = self.place.iter().find(|p| &p.name == name) else {
+                    continue 'itineraries;
+                };
+                if !self.leg_matches(place, mood, recent, date, Some(part), window, day_off, home, &shops) {
+                    continue 'itineraries;
+                }
+                if !self.clears_precipitation(place, part, provider, area_forecast).await {
+                    continue 'itineraries;
+                }
+                legs.push(place.clone());
+            }
+            return Some((itinerary, legs));
+        }
+        None
+    }
+}
+
+/// Append a problem for each name in `names` seen more than once.
+fn push_duplicates<'a>(problems: &mut Vec<String>, kind: &str, names: impl Iterator<Item = &'a str>) {
+    let mut seen = HashSet::new();
+    for name in names {
+        if !seen.insert(name) {
+            problems.push(format!("duplicate {kind} name {name:?} (check include files for overlap)"));
+        }
+    }
+}
+
+/// Append a problem for `lat`/`lon` if either is out of its valid range.
+fn push_lat_lon(problems: &mut Vec<String>, at: String, lat: f64, lon: f64) {
+    if !(-90.0..=90.0).contains(&lat) {
+        problems.push(format!("{at} lat {lat} is out of range (-90..=90)"));
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        problems.push(format!("{at} lon {lon} is out of range (-180..=180)"));
+    }
+}
+
+/// Append a problem if `price` is outside the 1-4 scale.
+fn push_price(problems: &mut Vec<String>, at: String, price: u8) {
+    if !(1..=4).contains(&price) {
+        problems.push(format!("{at} price {price} is out of range (1..=4)"));
+    }
+}
+
+/// Whether `code` looks like a JMA area code: exactly 6 ASCII digits.
+fn is_six_digit_code(code: &str) -> bool {
+    code.len() == 6 && code.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes `name` (under a fresh temp directory) with `contents` and
+    /// returns the directory, so `Places::read` can resolve `include`
+    /// against it.
+    fn write_fixture(dir_name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sunnyday-test-{dir_name}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in files {
+            std::fs::write(dir.join(name), contents).unwrap();
+        }
+        dir
+    }
+
+    const AREA: &str = r#"
+[area]
+offices = "130000"
+class10s = "130010"
+lat = 35.0
+lon = 139.0
+precipitation = 1.0
+max_wind = 8.0
+"#;
+
+    #[test]
+    fn merges_included_files() {
+        let dir = write_fixture(
+            "merge",
+            &[
+                (
+                    "place.toml",
+                    &format!(
+                        "include = [\"extra.toml\"]\n{AREA}\n[[place]]\nname = \"Main Park\"\n"
+                    ),
+                ),
+                ("extra.toml", "[[place]]\nname = \"Extra Cafe\"\n"),
+            ],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let names: Vec<_> = places.place.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Main Park", "Extra Cafe"]);
+    }
+
+    #[test]
+    fn rejects_duplicate_place_names_across_includes() {
+        let dir = write_fixture(
+            "dup",
+            &[
+                (
+                    "place.toml",
+                    &format!(
+                        "include = [\"extra.toml\"]\n{AREA}\n[[place]]\nname = \"Same Name\"\n"
+                    ),
+                ),
+                ("extra.toml", "[[place]]\nname = \"Same Name\"\n"),
+            ],
+        );
+        let err = Places::read(dir.join("place.toml")).unwrap_err();
+        assert!(err.to_string().contains("duplicate place name"));
+    }
+
+    #[test]
+    fn warns_about_unknown_shop_reference_instead_of_failing() {
+        let dir = write_fixture(
+            "bad-shop",
+            &[(
+                "place.toml",
+                &format!("{AREA}\n[[place]]\nname = \"Riverside Park\"\nshop = [\"typo'd cafe\"]\n"),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let warnings = places.unknown_shops();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].place, "Riverside Park");
+        assert_eq!(warnings[0].shop, "typo'd cafe");
+    }
+
+    #[test]
+    fn pickup_shop_skips_recent_and_non_food_shops() {
+        let dir = write_fixture(
+            "shops",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [[shop]]\nname = \"vending machine\"\n\
+                     [[shop]]\nname = \"river cafe\"\nfood = true\n\
+                     [[shop]]\nname = \"lake diner\"\nfood = true\n\
+                     [[place]]\nname = \"Riverside Park\"\nshop = [\"vending machine\", \"river cafe\", \"lake diner\"]\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let place = &places.place[0];
+        let mut recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        recent.push_shop("lake diner");
+
+        let shop = places.pickup_shop(place, &recent, NaiveDate::from_ymd_opt(2026, 8, 8).unwrap(), None).unwrap();
+        assert_eq!(shop.name, "river cafe");
+    }
+
+    #[tokio::test]
+    async fn pickup_itinerary_resolves_legs_in_order() {
+        let dir = write_fixture(
+            "itinerary",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [[place]]\nname = \"Riverside Park\"\nwalking = true\n\
+                     [[place]]\nname = \"Shopping Arcade\"\nindoor = true\n\
+                     [[itinerary]]\nname = \"riverside walk + shopping\"\nplaces = [\"Riverside Park\", \"Shopping Arcade\"]\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let provider = crate::provider::fixture::FixtureProvider::new(concat!(env!("CARGO_MANIFEST_DIR"), "/share")).unwrap();
+        let area_forecast = Forecast {
+            pop: 0,
+            precipitation: 0.0,
+            wind_speed: 0.0,
+        };
+
+        let (itinerary, legs) = places
+            .pickup_itinerary(
+                &Mood::default(),
+                &recent,
+                NaiveDate::from_ymd_opt(2026, 8, 8).unwrap(),
+                PartOfDay::Morning,
+                &provider,
+                &area_forecast,
+            )
+            .await
+            .unwrap();
+        assert_eq!(itinerary.name, "riverside walk + shopping");
+        let leg_names: Vec<_> = legs.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(leg_names, vec!["Riverside Park", "Shopping Arcade"]);
+    }
+
+    #[test]
+    fn candidates_excludes_the_last_suggested_cluster() {
+        let dir = write_fixture(
+            "cluster",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [[place]]\nname = \"Park A\"\ncluster = \"station\"\n\
+                     [[place]]\nname = \"Cafe B\"\ncluster = \"station\"\n\
+                     [[place]]\nname = \"Museum C\"\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let mut recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        recent.set_last_cluster(Some("station"));
+
+        let names: Vec<_> = places
+            .candidates(&Mood::default(), &recent, NaiveDate::from_ymd_opt(2026, 8, 8).unwrap(), None, None)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["Museum C"]);
+    }
+
+    #[test]
+    fn rank_penalizes_recent_but_does_not_exclude_it() {
+        let dir = write_fixture(
+            "rank",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [[place]]\nname = \"Park A\"\n\
+                     [[place]]\nname = \"Cafe B\"\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let mut recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 8).unwrap();
+        recent.push("Park A", date, None);
+
+        let ranked = places.rank(&Mood::default(), &recent, date, None, None);
+        let names: Vec<_> = ranked.iter().map(|s| s.place.name.as_str()).collect();
+        assert_eq!(names, vec!["Cafe B", "Park A"]);
+        assert!(ranked[1].score < ranked[0].score);
+    }
+
+    #[test]
+    fn explain_says_why_a_place_was_left_out_of_rank() {
+        let dir = write_fixture(
+            "explain",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [[place]]\nname = \"Indoor Museum\"\nindoor = true\n\
+                     [[place]]\nname = \"Outdoor Park\"\nindoor = false\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 8).unwrap();
+
+        let mood = Mood { indoor: Some(true), ..Mood::default() };
+        let ranked = places.rank(&mood, &recent, date, None, None);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].place.name, "Indoor Museum");
+
+        let excluded = places.explain(&mood, &recent, date, None);
+        assert_eq!(excluded.len(), 1);
+        assert_eq!(excluded[0].place, "Outdoor Park");
+        assert!(excluded[0].reason.contains("mood constraint"));
+    }
+
+    #[test]
+    fn rank_and_candidates_exclude_blacklisted_and_snoozed_places() {
+        let dir = write_fixture(
+            "blacklist",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [[place]]\nname = \"Park A\"\n\
+                     [[place]]\nname = \"Cafe B\"\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let mut recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 8).unwrap();
+        recent.blacklist("Park A");
+        recent.snooze("Cafe B", date);
+
+        let ranked = places.rank(&Mood::default(), &recent, date, None, None);
+        assert!(ranked.is_empty());
+        let candidates = places.candidates(&Mood::default(), &recent, date, None, None);
+        assert!(candidates.is_empty());
+    }
+
+    #[test]
+    fn mood_exclude_vetoes_a_place_even_as_the_only_partial_match() {
+        let dir = write_fixture(
+            "mood-exclude",
+            &[("place.toml", &format!("{AREA}\n[[place]]\nname = \"Only Option\"\n"))],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 8).unwrap();
+
+        let mood = Mood { exclude: vec!["Only Option".to_string()], ..Mood::default() };
+        assert!(places.candidates(&mood, &recent, date, None, None).is_empty());
+        assert!(places.partial_matches(&mood, &recent, date, None, None).is_empty());
+        assert!(places.pickup(&mood, &recent, date, None, None).is_none());
+    }
+
+    #[test]
+    fn mood_from_str_parses_every_kind_of_field() {
+        let mood: Mood = "food=yes,walking=no,parking=-,tags=park+kids,by=car,max_budget=2,duration=short"
+            .parse()
+            .unwrap();
+        assert_eq!(mood.food, Some(true));
+        assert_eq!(mood.walking, Some(false));
+        assert_eq!(mood.parking, None);
+        assert_eq!(mood.tags, vec!["park".to_string(), "kids".to_string()]);
+        assert_eq!(mood.by, Some(TravelMode::Car));
+        assert_eq!(mood.max_budget, Some(2));
+        assert_eq!(mood.duration, Some(Duration::Short));
+    }
+
+    #[test]
+    fn mood_from_str_rejects_unknown_keys_and_bad_values() {
+        assert!("frobnicate=yes".parse::<Mood>().is_err());
+        assert!("food=maybe".parse::<Mood>().is_err());
+        assert!("max_budget=lots".parse::<Mood>().is_err());
+    }
+
+    #[test]
+    fn mood_round_trips_through_display_and_from_str() {
+        let mood =
+            Mood { walking: Some(true), tags: vec!["park".to_string()], max_budget: Some(3), ..Mood::default() };
+        let round_tripped: Mood = mood.to_string().parse().unwrap();
+        assert_eq!(round_tripped.walking, mood.walking);
+        assert_eq!(round_tripped.tags, mood.tags);
+        assert_eq!(round_tripped.max_budget, mood.max_budget);
+    }
+
+    #[test]
+    fn mood_filters_by_duration_and_energy() {
+        let dir = write_fixture(
+            "duration-energy",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [[place]]\nname = \"Quick Cafe\"\nduration = \"short\"\nenergy = \"lazy\"\n\
+                     [[place]]\nname = \"Mountain Hike\"\nduration = \"half-day\"\nenergy = \"active\"\n\
+                     [[place]]\nname = \"Flexible Park\"\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 8).unwrap();
+
+        let mood = Mood { duration: Some(Duration::Short), ..Mood::default() };
+        let mut names: Vec<String> = places.candidates(&mood, &recent, date, None, None).into_iter().map(|p| p.name).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["Flexible Park", "Quick Cafe"]);
+
+        let mood = Mood { energy: Some(Energy::Active), ..Mood::default() };
+        let mut names: Vec<String> = places.candidates(&mood, &recent, date, None, None).into_iter().map(|p| p.name).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["Flexible Park", "Mountain Hike"]);
+    }
+
+    #[test]
+    fn pickup_falls_back_to_the_closest_partial_match() {
+        let dir = write_fixture(
+            "soft-mood-fallback",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [[place]]\nname = \"Close Miss\"\nwalking = true\nindoor = true\n\
+                     [[place]]\nname = \"Far Miss\"\nwalking = false\nindoor = false\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 8).unwrap();
+
+        // Nothing is both walking and cycling, so the strict filter finds
+        // nothing -- but "Close Miss" only misses on `cycling`, while "Far
+        // Miss" misses on both `walking` and `cycling`, so it should win.
+        let mood = Mood { walking: Some(true), cycling: Some(true), ..Mood::default() };
+        assert!(places.candidates(&mood, &recent, date, None, None).is_empty());
+        let picked = places.pickup(&mood, &recent, date, None, None).unwrap();
+        assert_eq!(picked.name, "Close Miss");
+
+        let partial = places.partial_matches(&mood, &recent, date, None, None);
+        assert_eq!(partial[0].place.name, "Close Miss");
+        assert_eq!(partial[1].place.name, "Far Miss");
+    }
+
+    #[test]
+    fn rank_ignores_acceptance_when_learning_is_disabled() {
+        let dir = write_fixture(
+            "learning-disabled",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [[place]]\nname = \"Park A\"\n\
+                     [[place]]\nname = \"Cafe B\"\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 8).unwrap();
+        let acceptance = HashMap::from([("Park A".to_string(), 0.0), ("Cafe B".to_string(), 1.0)]);
+
+        let ranked = places.rank(&Mood::default(), &recent, date, None, Some(&acceptance));
+        assert_eq!(ranked[0].score, ranked[1].score);
+    }
+
+    #[test]
+    fn rank_biases_toward_accepted_places_when_learning_is_enabled() {
+        let dir = write_fixture(
+            "learning-enabled",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\n\
+                     [learning]\nenabled = true\nexploration = 0.0\n\
+                     [[place]]\nname = \"Park A\"\n\
+                     [[place]]\nname = \"Cafe B\"\n\
+                     [[place]]\nname = \"Unseen Museum\"\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 8).unwrap();
+        let acceptance = HashMap::from([("Park A".to_string(), 0.0), ("Cafe B".to_string(), 1.0)]);
+
+        let ranked = places.rank(&Mood::default(), &recent, date, None, Some(&acceptance));
+        let names: Vec<_> = ranked.iter().map(|s| s.place.name.as_str()).collect();
+        // Never-answered places keep their full weight, same as Cafe B.
+        assert_eq!(names, vec!["Cafe B", "Unseen Museum", "Park A"]);
+        assert_eq!(ranked[2].score, 0.0);
+    }
+
+    #[test]
+    fn cooldown_days_overrides_rotation_days_per_place() {
+        let dir = write_fixture(
+            "cooldown",
+            &[(
+                "place.toml",
+                &format!(
+                    "{AREA}\nrotation_days = 30\n\
+                     [[place]]\nname = \"Corner Cafe\"\ncooldown_days = 2\n"
+                ),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let mut recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        recent.push("Corner Cafe", NaiveDate::from_ymd_opt(2026, 8, 5).unwrap(), None);
+
+        // 3 days later: past the place's own 2-day cooldown, even though
+        // the place list's global rotation_days (30) would still exclude it.
+        let names: Vec<_> = places
+            .candidates(&Mood::default(), &recent, NaiveDate::from_ymd_opt(2026, 8, 8).unwrap(), None, None)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["Corner Cafe"]);
+    }
+
+    #[test]
+    fn dedup_same_day_excludes_a_zero_cooldown_place_suggested_earlier_today() {
+        let dir = write_fixture(
+            "dedup-same-day",
+            &[(
+                "place.toml",
+                &format!("{AREA}\n[[place]]\nname = \"Corner Cafe\"\ncooldown_days = 0\n"),
+            )],
+        );
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let mut recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 8).unwrap();
+        recent.push("Corner Cafe", date, Some(PartOfDay::Morning));
+
+        assert!(places.candidates(&Mood::default(), &recent, date, Some(PartOfDay::Afternoon), None).is_empty());
+        assert!(places.rank(&Mood::default(), &recent, date, Some(PartOfDay::Afternoon), None).is_empty());
+    }
+
+    #[test]
+    fn rejects_out_of_range_coordinates() {
+        let dir = write_fixture(
+            "bad-coords",
+            &[(
+                "place.toml",
+                &format!("{AREA}\n[[place]]\nname = \"Off the Map\"\nlat = 200.0\nlon = 0.0\n"),
+            )],
+        );
+        let err = Places::read(dir.join("place.toml")).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+    }
+}
@@ -0,0 +1,148 @@
+//! Mock/fixture backend: reads canned forecasts (and, optionally, real
+//! `rasrf` tile PNGs) from a directory, so the suggestion pipeline and the
+//! web UI can run and be integration-tested with no network access.
+//!
+//! A fixture directory looks like:
+//!
+//! ```text
+//! fixtures/
+//!   forecast.json
+//! ```
+//!
+//! `forecast.json` maps a part-of-day name (`"morning"`/`"afternoon"`/
+//! `"evening"`) to either an explicit precipitation value or a tile PNG to
+//! decode (e.g. one of the ones under `share/`), plus a `pop` percentage:
+//!
+//! ```json
+//! {
+//!   "morning": { "pop": 10, "tile": "share/rasrf_clear.png" },
+//!   "afternoon": { "pop": 80, "tile": "share/rasrf_rain.png" }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+
+#[cfg(feature = "image")]
+use crate::jma::count_precipitation;
+use crate::jma::{AreaCode, PartOfDay};
+use crate::provider::{Forecast, WeatherProvider};
+
+#[derive(Debug, Deserialize)]
+struct FixtureEntry {
+    pop: u32,
+    #[serde(default)]
+    precipitation: Option<f64>,
+    #[serde(default)]
+    tile: Option<PathBuf>,
+    #[serde(default)]
+    wind_speed: f64,
+}
+
+fn part_key(part: PartOfDay) -> &'static str {
+    match part {
+        PartOfDay::Morning => "morning",
+        PartOfDay::Afternoon => "afternoon",
+        PartOfDay::Evening => "evening",
+    }
+}
+
+/// [`WeatherProvider`] that serves canned forecasts from a fixture
+/// directory instead of calling out to a real weather service.
+pub struct FixtureProvider {
+    forecasts: HashMap<String, FixtureEntry>,
+    dir: PathBuf,
+}
+
+impl FixtureProvider {
+    /// Load `forecast.json` from `dir`.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let text = std::fs::read_to_string(dir.join("forecast.json"))
+            .with_context(|| format!("reading {}", dir.join("forecast.json").display()))?;
+        let forecasts = serde_json::from_str(&text).context("parsing fixture forecast.json")?;
+        Ok(FixtureProvider { forecasts, dir })
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for FixtureProvider {
+    fn name(&self) -> &'static str {
+        "fixture"
+    }
+
+    async fn forecast(&self, _area: &AreaCode, part: PartOfDay, _lat_lon: Option<(f64, f64)>) -> Result<Forecast> {
+        let entry = self
+            .forecasts
+            .get(part_key(part))
+            .ok_or_else(|| anyhow!("no fixture entry for {:?}", part))?;
+
+        let precipitation = if let Some(tile) = &entry.tile {
+            #[cfg(feature = "image")]
+            {
+                let path = self.dir.join(tile);
+                let bytes = std::fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+                count_precipitation(&bytes)?
+            }
+            #[cfg(not(feature = "image"))]
+            {
+                return Err(anyhow!("fixture entry for {:?} needs a decoded tile, which requires the \"image\" feature", part));
+            }
+        } else {
+            entry.precipitation.unwrap_or(0.0)
+        };
+
+        Ok(Forecast {
+            pop: entry.pop,
+            precipitation,
+            wind_speed: entry.wind_speed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area() -> AreaCode {
+        AreaCode {
+            offices: "130000".to_string(),
+            class10s: "130010".to_string(),
+            lat: 35.6895,
+            lon: 139.6917,
+            precipitation: 1.0,
+            max_wind: 10.0,
+            respect_warnings: true,
+            timezone: chrono_tz::Asia::Tokyo,
+            sun_aware: false,
+            pop_limit: None,
+            precipitation_by_part: Default::default(),
+            precipitation_aggregation: Default::default(),
+            roi_window_px: 16,
+            roi_radius_m: None,
+            max_forecast_age_minutes: None,
+            wbgt_limit: None,
+            snow_limit: None,
+            typhoon_distance_km: None,
+            max_pm25: None,
+            units: Default::default(),
+            decision_script: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_tiles_from_the_share_fixtures() {
+        let provider = FixtureProvider::new(concat!(env!("CARGO_MANIFEST_DIR"), "/share")).unwrap();
+        let morning = provider.forecast(&area(), PartOfDay::Morning, None).await.unwrap();
+        assert_eq!(morning.pop, 10);
+        assert_eq!(morning.precipitation, 0.0);
+
+        let afternoon = provider.forecast(&area(), PartOfDay::Afternoon, None).await.unwrap();
+        assert_eq!(afternoon.pop, 80);
+        assert!(afternoon.precipitation > 0.0);
+    }
+}
@@ -0,0 +1,180 @@
+//! met.no (Yr) Locationforecast backend: another no-API-key option, with
+//! good coverage in Europe.
+//!
+//! The met.no terms of service require a descriptive `User-Agent` and
+//! reward `If-Modified-Since` caching with a `304 Not Modified`, so a
+//! response cache keyed by URL is kept alongside the last-modified header.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::Timelike;
+use serde::Deserialize;
+
+use crate::jma::{AreaCode, PartOfDay};
+use crate::provider::{Forecast, WeatherProvider};
+
+const LOCATIONFORECAST_URL: &str = "https://api.met.no/weatherapi/locationforecast/2.0/compact";
+const USER_AGENT: &str = "sunnyday/0.1 (https://github.com/kumanofoo/sunnyday)";
+
+struct CachedResponse {
+    last_modified: String,
+    body: Bytes,
+}
+
+type Bytes = Vec<u8>;
+
+static RESPONSE_CACHE: OnceLock<Mutex<HashMap<String, CachedResponse>>> = OnceLock::new();
+
+fn response_cache() -> &'static Mutex<HashMap<String, CachedResponse>> {
+    RESPONSE_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Deserialize)]
+struct LocationforecastResponse {
+    properties: Properties,
+}
+
+#[derive(Debug, Deserialize)]
+struct Properties {
+    timeseries: Vec<TimeseriesEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeseriesEntry {
+    time: String,
+    data: TimeseriesData,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeseriesData {
+    instant: Option<Instant>,
+    next_1_hours: Option<NextHours>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Instant {
+    details: InstantDetails,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstantDetails {
+    #[serde(default)]
+    wind_speed: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct NextHours {
+    details: NextHoursDetails,
+}
+
+#[derive(Debug, Deserialize)]
+struct NextHoursDetails {
+    #[serde(default)]
+    precipitation_amount: f64,
+}
+
+/// [`WeatherProvider`] backed by the Norwegian Meteorological Institute's
+/// Locationforecast API.
+pub struct MetNoProvider {
+    client: reqwest::Client,
+}
+
+impl MetNoProvider {
+    pub fn new() -> Self {
+        MetNoProvider {
+            client: crate::http::client(),
+        }
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Bytes> {
+        let mut request = self.client.get(url).header(reqwest::header::USER_AGENT, USER_AGENT);
+        let cached_last_modified = response_cache().lock().unwrap().get(url).map(|c| c.last_modified.clone());
+        if let Some(last_modified) = &cached_last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let response = request.send().await?;
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return response_cache()
+                .lock()
+                .unwrap()
+                .get(url)
+                .map(|c| c.body.clone())
+                .ok_or_else(|| anyhow!("met.no returned 304 but nothing is cached for {url}"));
+        }
+
+        let response = response.error_for_status()?;
+        let last_modified = response
+            .headers()
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes().await?.to_vec();
+        if let Some(last_modified) = last_modified {
+            response_cache().lock().unwrap().insert(
+                url.to_string(),
+                CachedResponse {
+                    last_modified,
+                    body: body.clone(),
+                },
+            );
+        }
+        Ok(body)
+    }
+}
+
+impl Default for MetNoProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for MetNoProvider {
+    fn name(&self) -> &'static str {
+        "met.no"
+    }
+
+    async fn forecast(&self, area: &AreaCode, part: PartOfDay, lat_lon: Option<(f64, f64)>) -> Result<Forecast> {
+        let (lat, lon) = lat_lon.unwrap_or((area.lat, area.lon));
+        let url = format!("{LOCATIONFORECAST_URL}?lat={lat}&lon={lon}");
+        let body = self.fetch(&url).await?;
+        let resp: LocationforecastResponse =
+            serde_json::from_slice(&body).context("parsing met.no Locationforecast response")?;
+
+        let today = area.now().date_naive();
+        let target_hour = part.jma_hour();
+        let entry = resp
+            .properties
+            .timeseries
+            .iter()
+            .find(|e| {
+                chrono::DateTime::parse_from_rfc3339(&e.time)
+                    .map(|dt| {
+                        let local = dt.with_timezone(&area.timezone);
+                        local.date_naive() == today && local.hour() == target_hour
+                    })
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| anyhow!("met.no response has no timeseries entry for {:?}", part))?;
+
+        let precipitation = entry
+            .data
+            .next_1_hours
+            .as_ref()
+            .map(|h| h.details.precipitation_amount)
+            .unwrap_or(0.0);
+        let wind_speed = entry.data.instant.as_ref().map(|i| i.details.wind_speed).unwrap_or(0.0);
+
+        Ok(Forecast {
+            // met.no doesn't publish a probability of precipitation; treat
+            // any forecast rain as certain for the purposes of `pop`.
+            pop: if precipitation > 0.0 { 100 } else { 0 },
+            precipitation,
+            wind_speed,
+        })
+    }
+}
@@ -0,0 +1,122 @@
+//! Pluggable weather backends.
+//!
+//! [`WeatherProvider`] is the seam between the place-suggestion logic and
+//! whatever service actually knows about rain. The built-in implementation,
+//! [`crate::jma::JmaProvider`], talks to the Japan Meteorological Agency;
+//! other providers live alongside this module (e.g. [`open_meteo`]).
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::jma::{AreaCode, PartOfDay};
+
+pub mod fixture;
+pub mod met_no;
+pub mod open_meteo;
+pub mod openweathermap;
+
+/// A single weather estimate for one part of a day. The all-zero
+/// [`Default`] is only meaningful as a placeholder for when there's no
+/// real forecast at all (see [`crate::suggester::Suggestion::weather_available`]),
+/// not as a "calm and dry" reading.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Default)]
+pub struct Forecast {
+    /// Probability of precipitation, 0-100.
+    pub pop: u32,
+    /// Estimated precipitation intensity in mm/h.
+    pub precipitation: f64,
+    /// Wind speed, m/s. `0.0` for providers/areas that don't report it.
+    pub wind_speed: f64,
+}
+
+impl Forecast {
+    /// Coarse weather condition for this forecast, banded on
+    /// `pop`/`precipitation` alone (see [`WeatherCondition::from_forecast`]).
+    /// Never [`WeatherCondition::Snow`] -- a bare forecast doesn't carry
+    /// that, see the `snow_forced` checks in the console/web binaries, which
+    /// know to override this with `Snow` when `[area] snow_limit` fires.
+    pub fn condition(&self) -> WeatherCondition {
+        WeatherCondition::from_forecast(self)
+    }
+
+    /// A single emoji summarizing this forecast, for console/web output --
+    /// plain eyeballing alongside the pop/precipitation numbers, not a
+    /// substitute for [`crate::jma::AreaCode::is_rainy`]'s actual walking
+    /// decision (which also weighs warnings/WBGT/snow/typhoon/PM2.5).
+    /// Shorthand for `self.condition().icon()`, for call sites that don't
+    /// need to distinguish snow from plain rain.
+    pub fn icon(&self) -> &'static str {
+        self.condition().icon()
+    }
+}
+
+/// A coarse weather condition for console/web display and JSON output --
+/// not a substitute for [`crate::jma::AreaCode::is_rainy`]'s actual walking
+/// decision (which also weighs warnings/WBGT/snow/typhoon/PM2.5).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherCondition {
+    Sunny,
+    Cloudy,
+    LightRain,
+    HeavyRain,
+    Snow,
+    /// Parts of a day being summarized together disagree (see
+    /// [`Self::combine`]) -- e.g. rain forecast for the morning but not the
+    /// afternoon.
+    MixedByPart,
+}
+
+impl WeatherCondition {
+    /// Band a forecast's `pop`/`precipitation` into a condition. Never
+    /// returns [`Self::Snow`] or [`Self::MixedByPart`] -- those need context
+    /// a single forecast doesn't carry (see [`Forecast::condition`] and
+    /// [`Self::combine`]).
+    pub fn from_forecast(forecast: &Forecast) -> Self {
+        if forecast.precipitation >= 10.0 || forecast.pop >= 70 {
+            WeatherCondition::HeavyRain
+        } else if forecast.precipitation >= 1.0 || forecast.pop >= 50 {
+            WeatherCondition::LightRain
+        } else if forecast.precipitation > 0.0 || forecast.pop >= 30 {
+            WeatherCondition::Cloudy
+        } else {
+            WeatherCondition::Sunny
+        }
+    }
+
+    /// Combine several parts' conditions into one day-level summary:
+    /// [`Self::MixedByPart`] if they disagree, otherwise whichever
+    /// condition they all share. `None` for an empty iterator.
+    pub fn combine(conditions: impl IntoIterator<Item = WeatherCondition>) -> Option<Self> {
+        let mut conditions = conditions.into_iter();
+        let first = conditions.next()?;
+        Some(if conditions.all(|c| c == first) { first } else { WeatherCondition::MixedByPart })
+    }
+
+    /// A single emoji matching this condition, for console/web output.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            WeatherCondition::Sunny => "☀️",
+            WeatherCondition::Cloudy => "⛅",
+            WeatherCondition::LightRain => "🌦️",
+            WeatherCondition::HeavyRain => "⛈️",
+            WeatherCondition::Snow => "❄️",
+            WeatherCondition::MixedByPart => "🌤️",
+        }
+    }
+}
+
+/// A source of weather forecasts for a configured area.
+///
+/// Implementations are expected to be cheap to construct and safe to share
+/// across requests; any caching they need is internal.
+#[async_trait]
+pub trait WeatherProvider: Send + Sync {
+    /// Short name used in config (`provider = "..."`) and diagnostics.
+    fn name(&self) -> &'static str;
+
+    /// Fetch the forecast for `area` during `part` of the current day, at
+    /// `lat_lon` if given (e.g. a specific place being considered), or at
+    /// `area`'s own point otherwise.
+    async fn forecast(&self, area: &AreaCode, part: PartOfDay, lat_lon: Option<(f64, f64)>) -> anyhow::Result<Forecast>;
+}
@@ -0,0 +1,95 @@
+//! Open-Meteo backend: a no-API-key forecast source, used anywhere JMA
+//! tiles don't cover (i.e. outside Japan).
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::Timelike;
+use serde::Deserialize;
+
+use crate::jma::{AreaCode, PartOfDay};
+use crate::provider::{Forecast, WeatherProvider};
+
+const OPEN_METEO_BASE: &str = "https://api.open-meteo.com/v1/forecast";
+
+#[derive(Debug, Deserialize)]
+struct HourlyResponse {
+    hourly: Hourly,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hourly {
+    time: Vec<String>,
+    precipitation: Vec<f64>,
+    precipitation_probability: Vec<u32>,
+    #[serde(default)]
+    windspeed_10m: Vec<f64>,
+}
+
+/// [`WeatherProvider`] backed by the [Open-Meteo](https://open-meteo.com)
+/// forecast API, keyed on `area`'s `lat`/`lon` rather than any JMA code.
+pub struct OpenMeteoProvider {
+    client: reqwest::Client,
+}
+
+impl OpenMeteoProvider {
+    pub fn new() -> Self {
+        OpenMeteoProvider {
+            client: crate::http::client(),
+        }
+    }
+}
+
+impl Default for OpenMeteoProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for OpenMeteoProvider {
+    fn name(&self) -> &'static str {
+        "open-meteo"
+    }
+
+    async fn forecast(&self, area: &AreaCode, part: PartOfDay, lat_lon: Option<(f64, f64)>) -> Result<Forecast> {
+        let (lat, lon) = lat_lon.unwrap_or((area.lat, area.lon));
+        let resp: HourlyResponse = self
+            .client
+            .get(OPEN_METEO_BASE)
+            .query(&[
+                ("latitude", lat.to_string()),
+                ("longitude", lon.to_string()),
+                (
+                    "hourly",
+                    "precipitation,precipitation_probability,windspeed_10m".to_string(),
+                ),
+                ("timezone", area.timezone.name().to_string()),
+                ("forecast_days", "1".to_string()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("parsing Open-Meteo response")?;
+
+        let today = area.now().date_naive();
+        let target_hour = part.jma_hour();
+        let idx = resp
+            .hourly
+            .time
+            .iter()
+            .position(|t| {
+                chrono::NaiveDateTime::parse_from_str(t, "%Y-%m-%dT%H:%M")
+                    .map(|dt| dt.date() == today && dt.hour() == target_hour)
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| anyhow!("Open-Meteo response has no hourly slot for {:?}", part))?;
+
+        Ok(Forecast {
+            pop: *resp.hourly.precipitation_probability.get(idx).unwrap_or(&0),
+            precipitation: *resp.hourly.precipitation.get(idx).unwrap_or(&0.0),
+            wind_speed: *resp.hourly.windspeed_10m.get(idx).unwrap_or(&0.0),
+        })
+    }
+}
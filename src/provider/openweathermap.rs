@@ -0,0 +1,98 @@
+//! OpenWeatherMap backend, for users who already have an OWM account.
+//!
+//! Uses the One Call API's hourly block, which reports both probability of
+//! precipitation (`pop`, 0-1) and the expected rain volume in mm.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::Timelike;
+use serde::Deserialize;
+
+use crate::jma::{AreaCode, PartOfDay};
+use crate::provider::{Forecast, WeatherProvider};
+
+const ONECALL_URL: &str = "https://api.openweathermap.org/data/3.0/onecall";
+
+#[derive(Debug, Deserialize)]
+struct OneCallResponse {
+    hourly: Vec<HourlyEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HourlyEntry {
+    dt: i64,
+    pop: f64,
+    #[serde(default)]
+    rain: Option<Rain>,
+    #[serde(default)]
+    wind_speed: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Rain {
+    #[serde(rename = "1h", default)]
+    one_hour: f64,
+}
+
+/// [`WeatherProvider`] backed by OpenWeatherMap's One Call API.
+pub struct OpenWeatherMapProvider {
+    client: reqwest::Client,
+    api_key: String,
+}
+
+impl OpenWeatherMapProvider {
+    pub fn new(api_key: String) -> Self {
+        OpenWeatherMapProvider {
+            client: crate::http::client(),
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for OpenWeatherMapProvider {
+    fn name(&self) -> &'static str {
+        "openweathermap"
+    }
+
+    async fn forecast(&self, area: &AreaCode, part: PartOfDay, lat_lon: Option<(f64, f64)>) -> Result<Forecast> {
+        let (lat, lon) = lat_lon.unwrap_or((area.lat, area.lon));
+        let resp: OneCallResponse = self
+            .client
+            .get(ONECALL_URL)
+            .query(&[
+                ("lat", lat.to_string()),
+                ("lon", lon.to_string()),
+                ("exclude", "current,minutely,daily,alerts".to_string()),
+                ("units", "metric".to_string()),
+                ("appid", self.api_key.clone()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("parsing OpenWeatherMap response")?;
+
+        let today = area.now().date_naive();
+        let target_hour = part.jma_hour();
+        let entry = resp
+            .hourly
+            .iter()
+            .find(|h| {
+                chrono::DateTime::from_timestamp(h.dt, 0)
+                    .map(|dt| {
+                        let local = dt.with_timezone(&area.timezone);
+                        local.date_naive() == today && local.hour() == target_hour
+                    })
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| anyhow!("OpenWeatherMap response has no hourly slot for {:?}", part))?;
+
+        Ok(Forecast {
+            pop: (entry.pop * 100.0).round() as u32,
+            precipitation: entry.rain.as_ref().map(|r| r.one_hour).unwrap_or(0.0),
+            wind_speed: entry.wind_speed,
+        })
+    }
+}
@@ -0,0 +1,21 @@
+//! QR codes encoding a place's map link, for scanning a suggestion from
+//! a terminal or web page straight onto a phone.
+
+use anyhow::Result;
+
+/// Render `url` as a QR code drawn with Unicode half-block characters,
+/// suitable for printing straight to a terminal.
+pub fn terminal_qr(url: &str) -> Result<String> {
+    let code = qrcode::QrCode::new(url)?;
+    Ok(code.render::<qrcode::render::unicode::Dense1x2>().build())
+}
+
+/// Render `url` as a QR code PNG, for embedding in the web UI.
+#[cfg(feature = "image")]
+pub fn png_qr(url: &str) -> Result<Vec<u8>> {
+    let code = qrcode::QrCode::new(url)?;
+    let image = code.render::<image::Luma<u8>>().build();
+    let mut out = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageLuma8(image).write_to(&mut out, image::ImageFormat::Png)?;
+    Ok(out.into_inner())
+}
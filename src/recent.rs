@@ -0,0 +1,467 @@
+//! Rotation history: places suggested recently, so we don't repeat
+//! ourselves too often.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::jma::PartOfDay;
+
+/// How many recently-suggested shop names to remember by default.
+pub const DEFAULT_CAPACITY: usize = 5;
+
+/// How many calendar days a suggested place stays excluded by default, if
+/// `place.toml` doesn't set its own `rotation_days`.
+pub const DEFAULT_ROTATION_DAYS: u32 = 5;
+
+/// How long a [`RecentEntry`] is kept around at all, regardless of any
+/// `rotation_days` ever configured -- just housekeeping so the history
+/// file doesn't grow forever, not part of the actual exclusion window
+/// (see [`RecentPlace::check`]).
+const MAX_HISTORY_DAYS: i64 = 90;
+
+fn default_capacity() -> usize {
+    DEFAULT_CAPACITY
+}
+
+/// [`crate::place::Places::rotation_days`]'s serde default.
+pub(crate) fn default_rotation_days() -> u32 {
+    DEFAULT_ROTATION_DAYS
+}
+
+/// A place suggested on a given day, for calendar-based expiry (see
+/// [`RecentPlace::check`]) instead of trimming a fixed-size window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecentEntry {
+    name: String,
+    date: NaiveDate,
+    #[serde(default)]
+    part: Option<PartOfDay>,
+}
+
+/// A place temporarily excluded from suggestion, independent of the
+/// ordinary rotation window -- see [`RecentPlace::snooze`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnoozeEntry {
+    name: String,
+    until: NaiveDate,
+}
+
+/// A suggestion already decided for a given `date`/`part`, so repeat calls
+/// on the same day return it again instead of picking a new one -- see
+/// [`RecentPlace::today_pick`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TodayPick {
+    date: NaiveDate,
+    part: PartOfDay,
+    /// Set if a single place (not an itinerary) was picked.
+    place: Option<String>,
+    /// Set if an itinerary was picked instead.
+    itinerary: Option<String>,
+}
+
+/// Rotation history, persisted to disk between runs: suggested places,
+/// each timestamped so a `rotation_days`-based expiry (see
+/// [`Self::check`]) can tell a suggestion from yesterday apart from one
+/// from last month, plus a fixed-size window of suggested shop names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentPlace {
+    names: Vec<RecentEntry>,
+    /// Suggested shop names (see [`Self::push_shop`]) -- tracked
+    /// separately since a shop name and a place name can collide, and
+    /// still a plain fixed-size window rather than calendar-based, since
+    /// shops don't have their own `rotation_days`/`cooldown_days`.
+    #[serde(default)]
+    shops: VecDeque<String>,
+    /// The most recently suggested [`crate::Place::cluster`], so the
+    /// rotation can avoid a whole neighborhood on the next suggestion, not
+    /// just the exact same place -- see [`Self::last_cluster`]. Unlike
+    /// `names`/`shops` this isn't a window: there's only ever one "last
+    /// cluster".
+    #[serde(default)]
+    cluster: Option<String>,
+    /// Forced next suggestion (any part), set by `sunnyday place pin`;
+    /// cleared the first time [`crate::suggester::Suggester::suggest`]
+    /// consumes it (see [`Self::take_pinned`]).
+    #[serde(default)]
+    pinned: Option<String>,
+    /// Places temporarily excluded from suggestion until a given date, set
+    /// by `sunnyday place snooze`.
+    #[serde(default)]
+    snoozed: Vec<SnoozeEntry>,
+    /// Places excluded from suggestion indefinitely, set by
+    /// `sunnyday place blacklist`.
+    #[serde(default)]
+    blacklisted: Vec<String>,
+    /// Suggestions already decided today, for same-day idempotency -- see
+    /// [`Self::today_pick`].
+    #[serde(default)]
+    today: Vec<TodayPick>,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip, default = "default_capacity")]
+    capacity: usize,
+}
+
+impl RecentPlace {
+    /// Load recent-place history from `path` (toml, yaml, or json, picked
+    /// by extension; see [`crate::format`]), or start empty if the file
+    /// doesn't exist yet.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<RecentPlace> {
+        let path = path.as_ref().to_path_buf();
+        let mut recent = if path.exists() {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            crate::format::parse::<RecentPlace>(&path, &text).context("parsing recent-place history")?
+        } else {
+            RecentPlace {
+                names: Vec::new(),
+                shops: VecDeque::new(),
+                cluster: None,
+                pinned: None,
+                snoozed: Vec::new(),
+                blacklisted: Vec::new(),
+                today: Vec::new(),
+                path: PathBuf::new(),
+                capacity: DEFAULT_CAPACITY,
+            }
+        };
+        recent.path = path;
+        Ok(recent)
+    }
+
+    /// Persist history back to disk, in the same format [`Self::read`]
+    /// loaded it from. Takes an advisory lock on [`Self::lock_path`] for the
+    /// duration of the write, so a concurrent `sunnyday`/`sunnyday-web` run
+    /// saving at the same time waits its turn instead of interleaving, and
+    /// writes through a temp file and rename so a crash mid-write can't
+    /// leave a half-written, unparseable history file behind. On wasm32
+    /// there's no `fd-lock` (and no second process to race with), so the
+    /// lock is skipped there.
+    pub fn save(&self) -> Result<()> {
+        let text = crate::format::to_string(&self.path, self).context("serializing recent-place history")?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let lock_path = self.lock_path();
+        #[cfg(not(target_arch = "wasm32"))]
+        let lock_file = std::fs::File::create(&lock_path).with_context(|| format!("opening {}", lock_path.display()))?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut lock = fd_lock::RwLock::new(lock_file);
+        #[cfg(not(target_arch = "wasm32"))]
+        let _guard = lock.write().with_context(|| format!("locking {}", lock_path.display()))?;
+
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, text).with_context(|| format!("writing {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("renaming {} to {}", tmp_path.display(), self.path.display()))?;
+        Ok(())
+    }
+
+    /// Sidecar lock file path for [`Self::save`] -- a separate file rather
+    /// than the history file itself, since the latter gets replaced by
+    /// `save`'s rename and a lock on a since-replaced file wouldn't be seen
+    /// by the next process to open it.
+    fn lock_path(&self) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(".lock");
+        PathBuf::from(name)
+    }
+
+    /// Whether `name` was suggested within `rotation_days` calendar days of
+    /// `today` -- suggested today counts as day 0, so `rotation_days = 1`
+    /// excludes only same-day repeats.
+    pub fn check(&self, name: &str, today: NaiveDate, rotation_days: u32) -> bool {
+        self.names.iter().any(|e| e.name == name && (today - e.date).num_days() < i64::from(rotation_days))
+    }
+
+    /// Whether `name` was already suggested today for a different part of
+    /// day than `part` -- unlike [`Self::check`] this ignores
+    /// `rotation_days`/`cooldown_days` entirely, so a place exempted from
+    /// the ordinary rotation still doesn't get suggested for both morning
+    /// and afternoon of the same day (see
+    /// [`crate::place::Places::dedup_same_day`]).
+    pub fn suggested_for_another_part_today(&self, name: &str, today: NaiveDate, part: Option<PartOfDay>) -> bool {
+        self.names.iter().any(|e| e.name == name && e.date == today && e.part != part)
+    }
+
+    /// Whether `name` was suggested at all, regardless of when -- kept for
+    /// callers (e.g. statistics) that want the plain fact rather than a
+    /// rotation-window check.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|e| e.name == name)
+    }
+
+    /// Record a new suggestion for `date`/`part`. A second suggestion of
+    /// the same place on the same day is a no-op, so running the tool
+    /// several times in one morning doesn't pile up redundant entries.
+    /// Entries older than [`MAX_HISTORY_DAYS`] are dropped, independent of
+    /// whatever `rotation_days` [`Self::check`] is actually called with.
+    pub fn push(&mut self, name: &str, date: NaiveDate, part: Option<PartOfDay>) {
+        self.names.retain(|e| (date - e.date).num_days() <= MAX_HISTORY_DAYS);
+        if self.names.iter().any(|e| e.name == name && e.date == date) {
+            return;
+        }
+        self.names.push(RecentEntry { name: name.to_string(), date, part });
+    }
+
+    /// Remove the most recent rotation entry for `part`, returning the date
+    /// and place name that were removed, if any -- for `sunnyday recent
+    /// undo`, when a suggestion was run by mistake or not actually acted
+    /// on.
+    pub fn undo(&mut self, part: PartOfDay) -> Option<(NaiveDate, String)> {
+        let pos = self.names.iter().rposition(|e| e.part == Some(part))?;
+        let entry = self.names.remove(pos);
+        Some((entry.date, entry.name))
+    }
+
+    /// The most recent place suggested for `date`/`part`, if any -- how
+    /// `sunnyday accept`/`skip` (and the web equivalent) find out what a
+    /// feedback call is actually about, without the caller having to pass
+    /// the place name itself.
+    pub fn last_for(&self, date: NaiveDate, part: PartOfDay) -> Option<&str> {
+        self.names.iter().rev().find(|e| e.date == date && e.part == Some(part)).map(|e| e.name.as_str())
+    }
+
+    /// The suggestion already decided for `date`/`part`, if any -- a
+    /// single place's name, or an itinerary's name, never both. Used for
+    /// same-day idempotency: a repeat `sunnyday`/`sunnyday-web` run for the
+    /// same part returns this instead of picking again, unless
+    /// `--reroll` is passed.
+    pub fn today_pick(&self, date: NaiveDate, part: PartOfDay) -> Option<(Option<&str>, Option<&str>)> {
+        self.today
+            .iter()
+            .find(|p| p.date == date && p.part == part)
+            .map(|p| (p.place.as_deref(), p.itinerary.as_deref()))
+    }
+
+    /// Record today's pick for `date`/`part`, replacing any earlier one for
+    /// the same part (e.g. from a `--reroll`). Also drops entries older
+    /// than [`MAX_HISTORY_DAYS`], same housekeeping as [`Self::push`].
+    pub fn record_today_pick(&mut self, date: NaiveDate, part: PartOfDay, place: Option<&str>, itinerary: Option<&str>) {
+        self.today.retain(|p| (date - p.date).num_days() <= MAX_HISTORY_DAYS && !(p.date == date && p.part == part));
+        self.today.push(TodayPick {
+            date,
+            part,
+            place: place.map(str::to_string),
+            itinerary: itinerary.map(str::to_string),
+        });
+    }
+
+    /// Force `name` to be the next suggestion, regardless of mood or
+    /// weather, for any part of day -- see [`Self::take_pinned`].
+    pub fn pin(&mut self, name: &str) {
+        self.pinned = Some(name.to_string());
+    }
+
+    /// Consume and return the pinned place, if any -- a pin is good for
+    /// one suggestion only.
+    pub fn take_pinned(&mut self) -> Option<String> {
+        self.pinned.take()
+    }
+
+    /// Exclude `name` from suggestion until `until` (inclusive).
+    /// Re-snoozing replaces any existing snooze for the same place rather
+    /// than stacking.
+    pub fn snooze(&mut self, name: &str, until: NaiveDate) {
+        self.snoozed.retain(|e| e.name != name);
+        self.snoozed.push(SnoozeEntry { name: name.to_string(), until });
+    }
+
+    /// Exclude `name` from suggestion indefinitely.
+    pub fn blacklist(&mut self, name: &str) {
+        if !self.blacklisted.iter().any(|n| n == name) {
+            self.blacklisted.push(name.to_string());
+        }
+    }
+
+    /// Whether `name` is excluded from ordinary suggestion right now:
+    /// blacklisted outright, or still within its snooze window.
+    pub fn excluded(&self, name: &str, today: NaiveDate) -> bool {
+        self.blacklisted.iter().any(|n| n == name) || self.snoozed.iter().any(|e| e.name == name && e.until >= today)
+    }
+
+    /// Whether `name` (a shop) was suggested recently.
+    pub fn contains_shop(&self, name: &str) -> bool {
+        self.shops.iter().any(|n| n == name)
+    }
+
+    /// Record a newly-suggested shop, trimming the oldest entry once the
+    /// window of [`DEFAULT_CAPACITY`] is exceeded.
+    pub fn push_shop(&mut self, name: &str) {
+        self.shops.push_back(name.to_string());
+        while self.shops.len() > self.capacity {
+            self.shops.pop_front();
+        }
+    }
+
+    /// The cluster most recently suggested, if any; see
+    /// [`Self::set_last_cluster`].
+    pub fn last_cluster(&self) -> Option<&str> {
+        self.cluster.as_deref()
+    }
+
+    /// Record the cluster of a newly-suggested place, overwriting whatever
+    /// was there before -- there's no window to trim, just the one most
+    /// recent value.
+    pub fn set_last_cluster(&mut self, cluster: Option<&str>) {
+        self.cluster = cluster.map(str::to_string);
+    }
+
+    /// Merge another device's exported history into this one, for
+    /// `sunnyday recent import` (see `sunnyday recent export`): rotation
+    /// entries, snoozes, and today's picks are unioned by date+part,
+    /// `other`'s entry winning a conflict since it's the one being
+    /// explicitly synced in; blacklist and recent shops are a plain set
+    /// union; the pin and last-suggested cluster are overwritten if
+    /// `other` has one.
+    pub fn merge(&mut self, other: RecentPlace) {
+        for entry in other.names {
+            self.names.retain(|e| !(e.date == entry.date && e.part == entry.part && e.name == entry.name));
+            self.names.push(entry);
+        }
+        for shop in other.shops {
+            if !self.shops.contains(&shop) {
+                self.shops.push_back(shop);
+            }
+        }
+        while self.shops.len() > self.capacity {
+            self.shops.pop_front();
+        }
+        if other.cluster.is_some() {
+            self.cluster = other.cluster;
+        }
+        if other.pinned.is_some() {
+            self.pinned = other.pinned;
+        }
+        for snooze in other.snoozed {
+            self.snoozed.retain(|e| e.name != snooze.name);
+            self.snoozed.push(snooze);
+        }
+        for name in other.blacklisted {
+            if !self.blacklisted.contains(&name) {
+                self.blacklisted.push(name);
+            }
+        }
+        for pick in other.today {
+            self.today.retain(|p| !(p.date == pick.date && p.part == pick.part));
+            self.today.push(pick);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 8, day).unwrap()
+    }
+
+    #[test]
+    fn pushing_the_same_place_twice_in_one_day_is_a_no_op() {
+        let mut recent = RecentPlace::read(std::env::temp_dir().join("sunnyday-test-recent-dedupe.toml")).unwrap();
+        recent.push("Riverside Park", date(8), Some(PartOfDay::Morning));
+        recent.push("Riverside Park", date(8), Some(PartOfDay::Afternoon));
+        assert_eq!(recent.names.len(), 1);
+    }
+
+    #[test]
+    fn check_expires_by_rotation_days_not_by_count() {
+        let mut recent = RecentPlace::read(std::env::temp_dir().join("sunnyday-test-recent-expiry.toml")).unwrap();
+        recent.push("Riverside Park", date(1), None);
+
+        assert!(recent.check("Riverside Park", date(3), 5));
+        assert!(!recent.check("Riverside Park", date(8), 5));
+    }
+
+    #[test]
+    fn pin_is_consumed_by_take_pinned() {
+        let mut recent = RecentPlace::read(std::env::temp_dir().join("sunnyday-test-recent-pin.toml")).unwrap();
+        recent.pin("Riverside Park");
+
+        assert_eq!(recent.take_pinned(), Some("Riverside Park".to_string()));
+        assert_eq!(recent.take_pinned(), None);
+    }
+
+    #[test]
+    fn snooze_excludes_until_its_date_then_stops() {
+        let mut recent = RecentPlace::read(std::env::temp_dir().join("sunnyday-test-recent-snooze.toml")).unwrap();
+        recent.snooze("City Library", date(10));
+
+        assert!(recent.excluded("City Library", date(8)));
+        assert!(recent.excluded("City Library", date(10)));
+        assert!(!recent.excluded("City Library", date(11)));
+    }
+
+    #[test]
+    fn save_and_read_round_trip_through_the_temp_file_and_rename() {
+        let path = std::env::temp_dir().join("sunnyday-test-recent-save.toml");
+        let _ = std::fs::remove_file(&path);
+        let mut recent = RecentPlace::read(&path).unwrap();
+        recent.push("Riverside Park", date(1), Some(PartOfDay::Morning));
+        recent.save().unwrap();
+
+        assert!(!path.with_extension("tmp").exists());
+        let reread = RecentPlace::read(&path).unwrap();
+        assert!(reread.contains("Riverside Park"));
+    }
+
+    #[test]
+    fn today_pick_round_trips_and_a_reroll_replaces_it() {
+        let mut recent = RecentPlace::read(std::env::temp_dir().join("sunnyday-test-recent-today.toml")).unwrap();
+        assert_eq!(recent.today_pick(date(1), PartOfDay::Morning), None);
+
+        recent.record_today_pick(date(1), PartOfDay::Morning, Some("Riverside Park"), None);
+        assert_eq!(recent.today_pick(date(1), PartOfDay::Morning), Some((Some("Riverside Park"), None)));
+
+        recent.record_today_pick(date(1), PartOfDay::Morning, None, Some("riverside walk + shopping"));
+        assert_eq!(recent.today_pick(date(1), PartOfDay::Morning), Some((None, Some("riverside walk + shopping"))));
+    }
+
+    #[test]
+    fn merge_unions_history_and_lets_the_other_side_win_conflicts() {
+        let mut mine = RecentPlace::read(std::env::temp_dir().join("sunnyday-test-recent-merge-mine.toml")).unwrap();
+        mine.push("Riverside Park", date(1), Some(PartOfDay::Morning));
+        mine.blacklist("Shopping Arcade");
+
+        let mut theirs = RecentPlace::read(std::env::temp_dir().join("sunnyday-test-recent-merge-theirs.toml")).unwrap();
+        theirs.push("City Library", date(2), Some(PartOfDay::Evening));
+        theirs.blacklist("Mountainside Trail");
+        theirs.snooze("City Library", date(10));
+
+        mine.merge(theirs);
+
+        assert!(mine.contains("Riverside Park"));
+        assert!(mine.contains("City Library"));
+        assert!(mine.excluded("Shopping Arcade", date(1)));
+        assert!(mine.excluded("Mountainside Trail", date(1)));
+        assert!(mine.excluded("City Library", date(5)));
+    }
+
+    #[test]
+    fn undo_removes_the_most_recent_entry_for_that_part() {
+        let mut recent = RecentPlace::read(std::env::temp_dir().join("sunnyday-test-recent-undo.toml")).unwrap();
+        recent.push("Riverside Park", date(1), Some(PartOfDay::Morning));
+        recent.push("City Library", date(2), Some(PartOfDay::Morning));
+        recent.push("Shopping Arcade", date(2), Some(PartOfDay::Evening));
+
+        assert_eq!(recent.undo(PartOfDay::Morning), Some((date(2), "City Library".to_string())));
+        assert!(recent.contains("Riverside Park"));
+        assert!(!recent.contains("City Library"));
+        assert!(recent.contains("Shopping Arcade"));
+        assert_eq!(recent.undo(PartOfDay::Morning), Some((date(1), "Riverside Park".to_string())));
+        assert_eq!(recent.undo(PartOfDay::Morning), None);
+    }
+
+    #[test]
+    fn blacklist_excludes_indefinitely() {
+        let mut recent = RecentPlace::read(std::env::temp_dir().join("sunnyday-test-recent-blacklist.toml")).unwrap();
+        recent.blacklist("Shopping Arcade");
+
+        assert!(recent.excluded("Shopping Arcade", date(1)));
+        assert!(recent.excluded("Shopping Arcade", date(31)));
+    }
+}
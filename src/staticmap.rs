@@ -0,0 +1,62 @@
+//! OpenStreetMap static tile thumbnails for a place's coordinates.
+//!
+//! Raster tiles are raster tiles whichever service serves them, so
+//! [`place_thumbnail`] reuses [`crate::jma`]'s process-wide tile cache and
+//! [`crate::jma::Tile`]'s slippy-map math rather than inventing its own.
+
+use anyhow::Result;
+#[cfg(feature = "image")]
+use image::GenericImage;
+
+use crate::jma::Tile;
+
+/// OpenStreetMap's standard raster tile server.
+const OSM_TILE_BASE: &str = "https://tile.openstreetmap.org";
+
+/// Zoom level for a place thumbnail -- close enough to show the
+/// immediate neighborhood without stitching multiple tiles together.
+const THUMBNAIL_ZOOM: u8 = 15;
+
+/// Marker dot radius, in pixels.
+const MARKER_RADIUS: i64 = 5;
+
+/// Fetch the single OSM tile covering `(lat, lon)`, with a marker dot
+/// composited at its exact pixel, as PNG bytes. Returns the image itself
+/// rather than a hotlinked URL (contrast [`crate::jma::himawari_tile_url`])
+/// since compositing the marker needs the bytes in hand anyway, and OSM's
+/// tile usage policy expects requests to come from the server, not every
+/// visitor's browser.
+#[cfg(feature = "image")]
+pub async fn place_thumbnail(lat: f64, lon: f64) -> Result<Vec<u8>> {
+    let client = crate::http::client();
+    let tile = Tile::from_lat_lon(lat, lon, THUMBNAIL_ZOOM);
+    let url = format!("{OSM_TILE_BASE}/{}/{}/{}.png", tile.z, tile.x, tile.y);
+    let bytes = crate::jma::fetch_bytes_cached(&client, url).await?;
+
+    let mut image = image::load_from_memory(&bytes)?;
+    let (px, py) = Tile::pixel_for(lat, lon, THUMBNAIL_ZOOM);
+    mark(&mut image, px, py);
+
+    let mut out = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut out, image::ImageFormat::Png)?;
+    Ok(out.into_inner())
+}
+
+/// Paint a small solid marker dot centered on `(px, py)`, clipped to the
+/// tile's edges so a place near a tile boundary doesn't panic.
+#[cfg(feature = "image")]
+fn mark(image: &mut image::DynamicImage, px: u32, py: u32) {
+    let color = image::Rgba([220u8, 30, 30, 255]);
+    let (width, height) = (image.width() as i64, image.height() as i64);
+    for dy in -MARKER_RADIUS..=MARKER_RADIUS {
+        for dx in -MARKER_RADIUS..=MARKER_RADIUS {
+            if dx * dx + dy * dy > MARKER_RADIUS * MARKER_RADIUS {
+                continue;
+            }
+            let (x, y) = (px as i64 + dx, py as i64 + dy);
+            if (0..width).contains(&x) && (0..height).contains(&y) {
+                image.put_pixel(x as u32, y as u32, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Summary statistics over the visit log (see [`crate::visit`]), for the
+//! `sunnyday stats` subcommand.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::visit::{VisitOutcome, VisitRecord};
+
+/// Visit count for one place, as used by [`Stats::by_place`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaceCount {
+    pub place: String,
+    pub count: usize,
+}
+
+/// Visit count for one calendar month (`YYYY-MM`), as used by
+/// [`Stats::by_month`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthCount {
+    pub month: String,
+    pub count: usize,
+}
+
+/// Computed once from the whole visit log; see [`Stats::compute`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub total: usize,
+    pub accepted: usize,
+    pub declined: usize,
+    /// `accepted / total`, or 0.0 if the log is empty.
+    pub acceptance_rate: f64,
+    /// Among declined suggestions, the fraction that weren't walkable --
+    /// a rough answer to "how often did rain change plans".
+    pub rain_decline_rate: f64,
+    /// Most-visited first.
+    pub by_place: Vec<PlaceCount>,
+    /// Oldest month first.
+    pub by_month: Vec<MonthCount>,
+}
+
+impl Stats {
+    pub fn compute(records: &[VisitRecord]) -> Self {
+        let total = records.len();
+        let accepted = records.iter().filter(|r| r.outcome == VisitOutcome::Accepted).count();
+        let declined = total - accepted;
+
+        let mut by_place: HashMap<&str, usize> = HashMap::new();
+        let mut by_month: HashMap<String, usize> = HashMap::new();
+        let mut rained_declines = 0;
+        for record in records {
+            *by_place.entry(record.place.as_str()).or_default() += 1;
+            *by_month.entry(record.date.format("%Y-%m").to_string()).or_default() += 1;
+            if record.outcome == VisitOutcome::Declined && !record.walkable {
+                rained_declines += 1;
+            }
+        }
+
+        let mut by_place: Vec<PlaceCount> =
+            by_place.into_iter().map(|(place, count)| PlaceCount { place: place.to_string(), count }).collect();
+        by_place.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.place.cmp(&b.place)));
+
+        let mut by_month: Vec<MonthCount> =
+            by_month.into_iter().map(|(month, count)| MonthCount { month, count }).collect();
+        by_month.sort_by(|a, b| a.month.cmp(&b.month));
+
+        Stats {
+            total,
+            accepted,
+            declined,
+            acceptance_rate: if total == 0 { 0.0 } else { accepted as f64 / total as f64 },
+            rain_decline_rate: if declined == 0 { 0.0 } else { rained_declines as f64 / declined as f64 },
+            by_place,
+            by_month,
+        }
+    }
+
+    /// The place visited most often, if the log has anything in it.
+    pub fn most_visited(&self) -> Option<&PlaceCount> {
+        self.by_place.first()
+    }
+
+    /// The place visited least often, if the log has anything in it.
+    pub fn least_visited(&self) -> Option<&PlaceCount> {
+        self.by_place.last()
+    }
+}
+
+/// Render the raw visit log as CSV (date, part, place, forecast values,
+/// accepted flag), for `sunnyday stats export --format csv` -- unlike
+/// [`Stats`] this is one row per visit rather than a summary, meant for
+/// further analysis in a spreadsheet.
+pub fn to_csv(records: &[VisitRecord]) -> String {
+    let mut csv = String::from("date,part,place,pop,precipitation,wind_speed,accepted\n");
+    for record in records {
+        csv.push_str(&format!(
+            "{},{:?},{},{},{},{},{}\n",
+            record.date,
+            record.part,
+            csv_field(&record.place),
+            record.pop,
+            record.precipitation,
+            record.wind_speed,
+            record.outcome == VisitOutcome::Accepted,
+        ));
+    }
+    csv
+}
+
+/// Quote a CSV field if it contains a comma, quote, or newline, doubling
+/// any quotes inside it -- a place's name is the only field here that
+/// isn't already comma/newline-safe by construction.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One calendar day's worth of [`VisitRecord`]s, for the web UI's
+/// `/history` charts.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayStat {
+    pub date: chrono::NaiveDate,
+    /// The heaviest precipitation seen that day, across every record.
+    pub precipitation: f64,
+    /// How many suggestions were accepted that day.
+    pub visited: usize,
+}
+
+/// One entry per day that has at least one [`VisitRecord`], oldest first --
+/// unlike [`Stats::by_month`]'s totals, a day chart needs one point per day
+/// rather than a single count.
+pub fn by_day(records: &[VisitRecord]) -> Vec<DayStat> {
+    let mut by_day: HashMap<chrono::NaiveDate, (f64, usize)> = HashMap::new();
+    for record in records {
+        let entry = by_day.entry(record.date).or_default();
+        entry.0 = entry.0.max(record.precipitation);
+        if record.outcome == VisitOutcome::Accepted {
+            entry.1 += 1;
+        }
+    }
+    let mut days: Vec<DayStat> =
+        by_day.into_iter().map(|(date, (precipitation, visited))| DayStat { date, precipitation, visited }).collect();
+    days.sort_by_key(|d| d.date);
+    days
+}
+
+/// Per-place `accepted / (accepted + declined)`, for
+/// [`crate::bandit::LearningConfig`] to bias selection toward places
+/// actually visited. A place never answered doesn't appear at all, so the
+/// bandit can tell "no opinion yet" apart from "never accepted".
+pub fn acceptance_rates(records: &[VisitRecord]) -> HashMap<String, f64> {
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for record in records {
+        let (accepted, declined) = counts.entry(record.place.as_str()).or_default();
+        match record.outcome {
+            VisitOutcome::Accepted => *accepted += 1,
+            VisitOutcome::Declined => *declined += 1,
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(place, (accepted, declined))| (place.to_string(), accepted as f64 / (accepted + declined) as f64))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::jma::PartOfDay;
+
+    fn record(place: &str, date: &str, outcome: VisitOutcome, walkable: bool) -> VisitRecord {
+        VisitRecord {
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            part: PartOfDay::Morning,
+            place: place.to_string(),
+            outcome,
+            walkable,
+            pop: 0,
+            precipitation: 0.0,
+            wind_speed: 0.0,
+        }
+    }
+
+    #[test]
+    fn ranks_places_and_months_by_count() {
+        let records = vec![
+            record("Riverside Park", "2026-07-01", VisitOutcome::Accepted, true),
+            record("Riverside Park", "2026-08-01", VisitOutcome::Accepted, true),
+            record("City Library", "2026-08-02", VisitOutcome::Accepted, true),
+        ];
+        let stats = Stats::compute(&records);
+
+        assert_eq!(stats.most_visited().unwrap().place, "Riverside Park");
+        assert_eq!(stats.least_visited().unwrap().place, "City Library");
+        assert_eq!(stats.by_month, vec![
+            MonthCount { month: "2026-07".into(), count: 1 },
+            MonthCount { month: "2026-08".into(), count: 2 },
+        ]);
+    }
+
+    #[test]
+    fn acceptance_and_rain_decline_rates() {
+        let records = vec![
+            record("Riverside Park", "2026-08-01", VisitOutcome::Accepted, true),
+            record("City Library", "2026-08-02", VisitOutcome::Declined, false),
+            record("City Library", "2026-08-03", VisitOutcome::Declined, true),
+        ];
+        let stats = Stats::compute(&records);
+
+        assert_eq!(stats.acceptance_rate, 1.0 / 3.0);
+        assert_eq!(stats.rain_decline_rate, 0.5);
+    }
+
+    #[test]
+    fn acceptance_rates_are_per_place_and_omit_the_unanswered() {
+        let records = vec![
+            record("Riverside Park", "2026-08-01", VisitOutcome::Accepted, true),
+            record("Riverside Park", "2026-08-02", VisitOutcome::Declined, true),
+            record("City Library", "2026-08-03", VisitOutcome::Accepted, true),
+        ];
+        let rates = acceptance_rates(&records);
+        assert_eq!(rates.get("Riverside Park"), Some(&0.5));
+        assert_eq!(rates.get("City Library"), Some(&1.0));
+        assert_eq!(rates.get("Shopping Arcade"), None);
+    }
+
+    #[test]
+    fn csv_export_quotes_place_names_with_commas_and_marks_accepted() {
+        let mut declined = record("Shop, Downtown", "2026-08-01", VisitOutcome::Declined, false);
+        declined.pop = 80;
+        declined.precipitation = 4.5;
+        declined.wind_speed = 6.0;
+        let csv = to_csv(&[record("Riverside Park", "2026-08-02", VisitOutcome::Accepted, true), declined]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "date,part,place,pop,precipitation,wind_speed,accepted");
+        assert_eq!(lines[1], "2026-08-02,Morning,Riverside Park,0,0,0,true");
+        assert_eq!(lines[2], "2026-08-01,Morning,\"Shop, Downtown\",80,4.5,6,false");
+    }
+
+    #[test]
+    fn by_day_takes_the_heaviest_precipitation_and_counts_accepted_visits() {
+        let mut rainy = record("City Library", "2026-08-01", VisitOutcome::Declined, false);
+        rainy.precipitation = 5.0;
+        let mut light = record("Riverside Park", "2026-08-01", VisitOutcome::Accepted, true);
+        light.precipitation = 1.0;
+        let days = by_day(&[rainy, light, record("Shopping Arcade", "2026-08-02", VisitOutcome::Declined, true)]);
+
+        assert_eq!(days, vec![
+            DayStat { date: NaiveDate::parse_from_str("2026-08-01", "%Y-%m-%d").unwrap(), precipitation: 5.0, visited: 1 },
+            DayStat { date: NaiveDate::parse_from_str("2026-08-02", "%Y-%m-%d").unwrap(), precipitation: 0.0, visited: 0 },
+        ]);
+    }
+
+    #[test]
+    fn empty_log_has_zero_rates_not_a_division_error() {
+        let stats = Stats::compute(&[]);
+        assert_eq!(stats.acceptance_rate, 0.0);
+        assert_eq!(stats.rain_decline_rate, 0.0);
+    }
+}
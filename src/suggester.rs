@@ -0,0 +1,553 @@
+//! High-level facade over [`Places`]/[`RecentPlace`]/[`WeatherProvider`],
+//! for a consumer (e.g. a chat bot) that wants one call in and a rich
+//! answer out, rather than wiring the pieces together itself the way both
+//! bundled binaries used to.
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::jma::PartOfDay;
+use crate::journal::{ForecastJournal, ForecastRecord};
+use crate::place::{Exclusion, Itinerary, Mood, Place, Places, ScoredPlace};
+use crate::provider::{Forecast, WeatherProvider};
+use crate::recent::RecentPlace;
+use crate::visit::VisitLog;
+
+/// The result of one [`Suggester::suggest`] call: the pick (a single place,
+/// an itinerary, or neither), the runner-up candidates it was chosen over
+/// (see [`Places::rank`]), the forecast that was checked, and a short trail
+/// explaining the decision -- meant to be shown to a user, not parsed.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub part: PartOfDay,
+    pub walkable: bool,
+    pub forecast: Forecast,
+    /// `false` when the [`WeatherProvider`] lookup itself failed and this
+    /// suggestion fell back to mood-only filtering (see
+    /// [`Suggester::suggest_degraded`]) -- [`Self::forecast`] is a zeroed
+    /// placeholder in that case, not a real "clear skies" reading. A
+    /// consumer should show a "weather unavailable" notice rather than
+    /// `forecast`'s numbers when this is `false`.
+    pub weather_available: bool,
+    pub place: Option<Place>,
+    pub itinerary: Option<(Itinerary, Vec<Place>)>,
+    pub alternates: Vec<ScoredPlace>,
+    pub reasoning: Vec<String>,
+    /// Structured form of the same decision, for a consumer that wants more
+    /// than [`Self::reasoning`]'s plain trail -- `--explain` on the console
+    /// binary, and the web UI's collapsible detail section.
+    pub explanation: Reasoning,
+}
+
+/// Structured explanation of a [`Suggestion`]: the precipitation/wind
+/// values checked against the area's own walkability thresholds, plus why
+/// every place [`Places::rank`] left out didn't make it (see
+/// [`Places::explain`]).
+#[derive(Debug, Clone)]
+pub struct Reasoning {
+    pub precipitation: f64,
+    pub precipitation_threshold: f64,
+    /// Unit suffix matching [`Self::precipitation`]/[`Self::precipitation_threshold`]
+    /// (see [`crate::jma::AreaCode::units`]) -- both are already converted
+    /// to the area's configured display unit, not necessarily mm/h.
+    pub precipitation_unit: &'static str,
+    pub wind_speed: f64,
+    pub wind_threshold: f64,
+    pub excluded: Vec<Exclusion>,
+}
+
+/// Owns a loaded place list, rotation history, visit log, forecast
+/// journal, and weather provider, so a long-running consumer can ask for
+/// suggestions without re-reading place.toml/recent.toml or rebuilding a
+/// provider on every call. Both bundled binaries are thin wrappers over
+/// [`Self::suggest`].
+pub struct Suggester {
+    pub places: Places,
+    pub recent: RecentPlace,
+    visits: VisitLog,
+    journal: ForecastJournal,
+    provider: Box<dyn WeatherProvider>,
+}
+
+impl Suggester {
+    /// Load `places_path`/`recent_path`, open `visits_path` (see
+    /// [`VisitLog`]) and `journal_path` (see [`ForecastJournal`]), and
+    /// build a [`WeatherProvider`] from the place list's own `[weather]`
+    /// config.
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>, S: AsRef<Path>>(
+        places_path: P,
+        recent_path: Q,
+        visits_path: R,
+        journal_path: S,
+    ) -> Result<Self> {
+        let places = Places::read(places_path)?;
+        let recent = RecentPlace::read(recent_path)?;
+        let visits = VisitLog::open(visits_path);
+        let journal = ForecastJournal::open(journal_path);
+        let provider = places.weather.build()?;
+        Ok(Self { places, recent, visits, journal, provider })
+    }
+
+    /// Build a [`Suggester`] from an already-loaded place list and
+    /// rotation history (e.g. after applying CLI overrides), plus an
+    /// already-opened visit log and forecast journal, building a
+    /// [`WeatherProvider`] from the place list's own `[weather]` config.
+    pub fn from_parts(places: Places, recent: RecentPlace, visits: VisitLog, journal: ForecastJournal) -> Result<Self> {
+        let provider = places.weather.build()?;
+        Ok(Self { places, recent, visits, journal, provider })
+    }
+
+    /// Like [`Self::from_parts`], but with an already-built
+    /// [`WeatherProvider`] instead of building one from the place list's
+    /// `[weather]` config -- for a caller that wants to wrap it first,
+    /// e.g. `sunnyday-web`'s request-coalescing decorator (see
+    /// `CoalescingProvider` in `src/bin/home.rs`).
+    pub fn from_parts_with_provider(places: Places, recent: RecentPlace, visits: VisitLog, journal: ForecastJournal, provider: Box<dyn WeatherProvider>) -> Self {
+        Self { places, recent, visits, journal, provider }
+    }
+
+    /// Fetches the forecast for each of `parts` concurrently and discards
+    /// the results. Meant to run ahead of a loop of sequential
+    /// [`Self::suggest`] calls (one per part of the day) so their own
+    /// fetches land on an already-warm cache instead of paying network
+    /// latency once per part, back to back -- see `place_handler` in
+    /// `src/bin/home.rs`. Only pays off against a caching/coalescing
+    /// [`WeatherProvider`] (see `CoalescingProvider` there); against a
+    /// plain provider this just fetches everything twice.
+    pub async fn prefetch(&self, parts: &[PartOfDay]) {
+        let area = &self.places.area;
+        let fetches = parts.iter().map(|&part| self.provider.forecast(area, part, None));
+        futures_util::future::join_all(fetches).await;
+    }
+
+    /// Decide on a suggestion for `part`, matching `mood`: tries
+    /// [`Places::pickup_itinerary`] first, falling back to
+    /// [`Places::pickup_checked`], same priority both binaries already
+    /// used. `mood.indoor` is forced to `true` when the result isn't
+    /// walkable -- either the forecast itself, or `stay_in_forced` (e.g. an
+    /// active JMA warning, which isn't this crate's concern to fetch, so
+    /// the caller checks and passes the answer in) -- unless `mood`
+    /// already has its own opinion. Whatever is picked is pushed into
+    /// `self.recent` (and its cluster recorded) -- call [`Self::save`] to
+    /// persist that. Also ranks every candidate via [`Places::rank`] for
+    /// [`Suggestion::alternates`], regardless of what was picked. If
+    /// [`crate::bandit::LearningConfig::enabled`] is set, both the pick and
+    /// the alternates are biased by acceptance rates read from the visit
+    /// log (see [`crate::stats::acceptance_rates`]).
+    ///
+    /// Unless `reroll` is set, a repeat call for a `part` already decided
+    /// today returns that same pick again (see
+    /// [`crate::recent::RecentPlace::today_pick`]) instead of consuming
+    /// another rotation entry and potentially flip-flopping.
+    ///
+    /// Every call appends the forecast it fetched and the walkability
+    /// decision it reached to [`crate::journal::ForecastJournal`] -- a
+    /// failure to write it is logged and otherwise ignored, same as the
+    /// rest of this crate's best-effort side data.
+    #[tracing::instrument(skip(self, mood), fields(part = ?part, walkable, outcome))]
+    pub async fn suggest(&mut self, part: PartOfDay, mood: &Mood, stay_in_forced: bool, reroll: bool) -> Result<Suggestion> {
+        let now = self.places.area.now();
+        let date = now.date_naive();
+        let forecast = match self.provider.forecast(&self.places.area, part, None).await {
+            Ok(forecast) => forecast,
+            Err(e) => {
+                tracing::warn!(error = %e, "forecast fetch failed, falling back to mood-only suggestion");
+                return Ok(self.suggest_degraded(part, mood, stay_in_forced, date));
+            }
+        };
+        let built_in_walkable = !self.places.area.is_rainy(part, &forecast) && !self.places.area.is_windy(&forecast);
+        let walkable = !stay_in_forced && !self.places.area.part_not_yet_light(part, now) && self.decide_walkable(part, &forecast, built_in_walkable);
+
+        if let Err(e) = self.journal.append(&ForecastRecord {
+            fetched_at: chrono::Utc::now(),
+            part,
+            pop: forecast.pop,
+            precipitation: forecast.precipitation,
+            walkable,
+        }) {
+            tracing::warn!(error = %e, "failed to append to the forecast journal");
+        }
+
+        let part_mood = if walkable {
+            mood.clone()
+        } else {
+            Mood {
+                indoor: mood.indoor.or(Some(true)),
+                ..mood.clone()
+            }
+        };
+
+        let precipitation_unit = self.places.area.precipitation_unit();
+        let mut reasoning = vec![format!(
+            "{part:?}: {} pop={}% precipitation={:.1}{precipitation_unit} wind={:.1}m/s -> {}",
+            forecast.icon(),
+            forecast.pop,
+            self.places.area.display_precipitation(forecast.precipitation),
+            forecast.wind_speed,
+            if walkable { "walkable" } else { "stay in" }
+        )];
+        tracing::Span::current().record("walkable", walkable);
+        let acceptance = self
+            .places
+            .learning
+            .enabled
+            .then(|| crate::stats::acceptance_rates(&self.visits.read_all().unwrap_or_default()));
+        let alternates = self.places.rank(&part_mood, &self.recent, date, Some(part), acceptance.as_ref());
+        let explanation = Reasoning {
+            precipitation: self.places.area.display_precipitation(forecast.precipitation),
+            precipitation_threshold: self.places.area.display_precipitation(self.places.area.precipitation_threshold(part)),
+            precipitation_unit,
+            wind_speed: forecast.wind_speed,
+            wind_threshold: self.places.area.max_wind,
+            excluded: self.places.explain(&part_mood, &self.recent, date, Some(part)),
+        };
+
+        if !reroll {
+            if let Some((place_name, itinerary_name)) = self.recent.today_pick(date, part) {
+                if let Some(name) = itinerary_name {
+                    if let Some(itinerary) = self.places.itinerary.iter().find(|i| i.name == name).cloned() {
+                        let legs: Vec<Place> =
+                            itinerary.places.iter().filter_map(|n| self.places.place.iter().find(|p| &p.name == n).cloned()).collect();
+                        if legs.len() == itinerary.places.len() {
+                            reasoning.push(format!("{:?} was already decided for today", itinerary.name));
+                            tracing::Span::current().record("outcome", "today_pick_itinerary");
+                            metrics::counter!("sunnyday_suggestion_outcomes_total", "outcome" => "today_pick_itinerary").increment(1);
+                            return Ok(Suggestion {
+                                part,
+                                walkable,
+                                forecast,
+                weather_available: true,
+                                place: None,
+                                itinerary: Some((itinerary, legs)),
+                                alternates,
+                                reasoning,
+                                explanation: explanation.clone(),
+                            });
+                        }
+                    }
+                } else if let Some(name) = place_name {
+                    if let Some(place) = self.places.place.iter().find(|p| p.name == name).cloned() {
+                        reasoning.push(format!("{:?} was already decided for today", place.name));
+                        tracing::Span::current().record("outcome", "today_pick_place");
+                        metrics::counter!("sunnyday_suggestion_outcomes_total", "outcome" => "today_pick_place").increment(1);
+                        return Ok(Suggestion {
+                            part,
+                            walkable,
+                            forecast,
+                weather_available: true,
+                            place: Some(place),
+                            itinerary: None,
+                            alternates,
+                            reasoning,
+                            explanation: explanation.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(name) = self.recent.take_pinned() {
+            if let Some(place) = self.places.place.iter().find(|p| p.name == name).cloned() {
+                reasoning.push(format!("{:?} was pinned for the next suggestion", place.name));
+                self.recent.push(&place.name, date, Some(part));
+                self.recent.set_last_cluster(place.cluster.as_deref());
+                self.recent.record_today_pick(date, part, Some(&place.name), None);
+                tracing::Span::current().record("outcome", "pinned");
+                metrics::counter!("sunnyday_suggestion_outcomes_total", "outcome" => "pinned").increment(1);
+                return Ok(Suggestion {
+                    part,
+                    walkable,
+                    forecast,
+                weather_available: true,
+                    place: Some(place),
+                    itinerary: None,
+                    alternates,
+                    reasoning,
+                    explanation: explanation.clone(),
+                });
+            }
+            reasoning.push(format!("pinned place {name:?} no longer exists, ignoring"));
+        }
+
+        if let Some((itinerary, legs)) =
+            self.places.pickup_itinerary(&part_mood, &self.recent, date, part, self.provider.as_ref(), &forecast).await
+        {
+            reasoning.push(format!("itinerary {:?} has every leg open and clear", itinerary.name));
+            let itinerary = itinerary.clone();
+            for leg in &legs {
+                self.recent.push(&leg.name, date, Some(part));
+                self.recent.set_last_cluster(leg.cluster.as_deref());
+            }
+            self.recent.record_today_pick(date, part, None, Some(&itinerary.name));
+            tracing::Span::current().record("outcome", "itinerary");
+            metrics::counter!("sunnyday_suggestion_outcomes_total", "outcome" => "itinerary").increment(1);
+            return Ok(Suggestion {
+                part,
+                walkable,
+                forecast,
+                weather_available: true,
+                place: None,
+                itinerary: Some((itinerary, legs)),
+                alternates,
+                reasoning,
+                explanation: explanation.clone(),
+            });
+        }
+
+        if let Some(place) = self
+            .places
+            .pickup_checked(&part_mood, &self.recent, date, part, self.provider.as_ref(), &forecast, acceptance.as_ref())
+            .await
+        {
+            reasoning.push(format!("{:?} matched the mood and cleared the precipitation check", place.name));
+            self.recent.push(&place.name, date, Some(part));
+            self.recent.set_last_cluster(place.cluster.as_deref());
+            self.recent.record_today_pick(date, part, Some(&place.name), None);
+            tracing::Span::current().record("outcome", "place");
+            metrics::counter!("sunnyday_suggestion_outcomes_total", "outcome" => "place").increment(1);
+            return Ok(Suggestion {
+                part,
+                walkable,
+                forecast,
+                weather_available: true,
+                place: Some(place),
+                itinerary: None,
+                alternates,
+                reasoning,
+                explanation: explanation.clone(),
+            });
+        }
+
+        reasoning.push("no place matches the mood right now".to_string());
+        tracing::Span::current().record("outcome", "none");
+        metrics::counter!("sunnyday_suggestion_outcomes_total", "outcome" => "none").increment(1);
+        Ok(Suggestion {
+            part,
+            walkable,
+            forecast,
+                weather_available: true,
+            place: None,
+            itinerary: None,
+            alternates,
+            reasoning,
+            explanation,
+        })
+    }
+
+    /// Persist any picks [`Self::suggest`] made to the rotation history.
+    pub fn save(&self) -> Result<()> {
+        self.recent.save()
+    }
+
+    /// Fallback for [`Self::suggest`] when the [`WeatherProvider`] lookup
+    /// itself fails (e.g. JMA is down): picks by [`Places::pickup`] --
+    /// mood only, no precipitation/wind check, and no itinerary (those need
+    /// a per-leg forecast lookup, which is exactly what just failed).
+    /// `stay_in_forced` is still honored, since it comes from JMA
+    /// warnings/WBGT/typhoon/PM2.5 checks the caller made separately, not
+    /// from [`Self::suggest`]'s own forecast fetch. Doesn't touch
+    /// [`crate::journal::ForecastJournal`] -- there's no real forecast to
+    /// record. Doesn't consult or update today's pick (see
+    /// [`crate::recent::RecentPlace::today_pick`]) or a pinned place either,
+    /// so a page reload during an outage may pick something different each
+    /// time; an acceptable trade for how rarely this path runs.
+    fn suggest_degraded(&mut self, part: PartOfDay, mood: &Mood, stay_in_forced: bool, date: chrono::NaiveDate) -> Suggestion {
+        let walkable = !stay_in_forced;
+        let part_mood = if walkable {
+            mood.clone()
+        } else {
+            Mood {
+                indoor: mood.indoor.or(Some(true)),
+                ..mood.clone()
+            }
+        };
+
+        let acceptance = self
+            .places
+            .learning
+            .enabled
+            .then(|| crate::stats::acceptance_rates(&self.visits.read_all().unwrap_or_default()));
+        let alternates = self.places.rank(&part_mood, &self.recent, date, Some(part), acceptance.as_ref());
+        let explanation = Reasoning {
+            precipitation: 0.0,
+            precipitation_threshold: self.places.area.display_precipitation(self.places.area.precipitation_threshold(part)),
+            precipitation_unit: self.places.area.precipitation_unit(),
+            wind_speed: 0.0,
+            wind_threshold: self.places.area.max_wind,
+            excluded: self.places.explain(&part_mood, &self.recent, date, Some(part)),
+        };
+        let mut reasoning = vec![format!("{part:?}: weather data unavailable, suggesting by mood alone")];
+        tracing::Span::current().record("walkable", walkable);
+
+        let place = self.places.pickup(&part_mood, &self.recent, date, Some(part), acceptance.as_ref());
+        if let Some(place) = &place {
+            self.recent.push(&place.name, date, Some(part));
+            self.recent.set_last_cluster(place.cluster.as_deref());
+            self.recent.record_today_pick(date, part, Some(&place.name), None);
+            reasoning.push(format!("{:?} matched the mood (weather unchecked)", place.name));
+            tracing::Span::current().record("outcome", "degraded_place");
+            metrics::counter!("sunnyday_suggestion_outcomes_total", "outcome" => "degraded_place").increment(1);
+        } else {
+            reasoning.push("no place matches the mood right now".to_string());
+            tracing::Span::current().record("outcome", "degraded_none");
+            metrics::counter!("sunnyday_suggestion_outcomes_total", "outcome" => "degraded_none").increment(1);
+        }
+
+        Suggestion {
+            part,
+            walkable,
+            forecast: Forecast::default(),
+            weather_available: false,
+            place,
+            itinerary: None,
+            alternates,
+            reasoning,
+            explanation,
+        }
+    }
+
+    /// Whether `forecast` makes `part` walkable, ignoring `stay_in_forced`
+    /// and daylight -- [`Self::suggest`] folds those in separately. Runs
+    /// [`crate::jma::AreaCode::decision_script`] through
+    /// [`crate::decision::evaluate`] when one is set, falling back to
+    /// `built_in_walkable` (the precipitation/pop/wind thresholds) on any
+    /// script error, or when the `scripting` feature isn't compiled in.
+    fn decide_walkable(&self, part: PartOfDay, forecast: &Forecast, built_in_walkable: bool) -> bool {
+        let Some(script) = &self.places.area.decision_script else {
+            return built_in_walkable;
+        };
+        #[cfg(feature = "scripting")]
+        {
+            match crate::decision::evaluate(script, forecast.pop, forecast.precipitation, forecast.wind_speed, part) {
+                Ok(walkable) => walkable,
+                Err(e) => {
+                    tracing::warn!(error = %e, "decision_script failed, falling back to built-in thresholds");
+                    built_in_walkable
+                }
+            }
+        }
+        #[cfg(not(feature = "scripting"))]
+        {
+            let _ = (part, forecast, script);
+            tracing::warn!("decision_script is set but the scripting feature isn't compiled in, falling back to built-in thresholds");
+            built_in_walkable
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_fixture(contents: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join("sunnyday-test-suggester");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("place.toml"), contents).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn suggest_picks_a_place_and_records_it_in_recent() {
+        let dir = write_fixture(&format!(
+            "[area]\noffices = \"130000\"\nclass10s = \"130010\"\nlat = 35.0\nlon = 139.0\n\
+             precipitation = 1.0\nmax_wind = 8.0\n\
+             [weather]\nprovider = \"fixture\"\nfixture_dir = {:?}\n\
+             [[place]]\nname = \"Riverside Park\"\nwalking = true\n",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/share")
+        ));
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let visits = VisitLog::open(dir.join("visits.jsonl"));
+        let journal = ForecastJournal::open(dir.join("journal.jsonl"));
+        let mut suggester = Suggester::from_parts(places, recent, visits, journal).unwrap();
+
+        let suggestion = suggester.suggest(PartOfDay::Morning, &Mood::default(), false, false).await.unwrap();
+        assert_eq!(suggestion.place.as_ref().map(|p| p.name.as_str()), Some("Riverside Park"));
+        assert!(suggester.recent.contains("Riverside Park"));
+    }
+
+    #[tokio::test]
+    async fn suggest_returns_the_same_pick_again_the_same_day_unless_rerolled() {
+        let dir = write_fixture(&format!(
+            "[area]\noffices = \"130000\"\nclass10s = \"130010\"\nlat = 35.0\nlon = 139.0\n\
+             precipitation = 1.0\nmax_wind = 8.0\n\
+             [weather]\nprovider = \"fixture\"\nfixture_dir = {:?}\n\
+             [[place]]\nname = \"Riverside Park\"\nwalking = true\n\
+             [[place]]\nname = \"Mountainside Trail\"\nwalking = true\n",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/share")
+        ));
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent2.toml")).unwrap();
+        let visits = VisitLog::open(dir.join("visits2.jsonl"));
+        let journal = ForecastJournal::open(dir.join("journal2.jsonl"));
+        let mut suggester = Suggester::from_parts(places, recent, visits, journal).unwrap();
+
+        let first = suggester.suggest(PartOfDay::Morning, &Mood::default(), false, false).await.unwrap();
+        let second = suggester.suggest(PartOfDay::Morning, &Mood::default(), false, false).await.unwrap();
+        assert_eq!(first.place.map(|p| p.name), second.place.map(|p| p.name));
+
+        let rerolled = suggester.suggest(PartOfDay::Morning, &Mood::default(), false, true).await.unwrap();
+        assert!(rerolled.reasoning.iter().all(|line| !line.contains("already decided")));
+    }
+
+    #[tokio::test]
+    async fn high_wind_turns_off_walkable_and_filters_out_a_cycling_place() {
+        let dir = std::env::temp_dir().join("sunnyday-test-suggester-wind");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("forecast.json"), r#"{"morning": {"pop": 0, "precipitation": 0.0, "wind_speed": 10.0}}"#).unwrap();
+        std::fs::write(
+            dir.join("place.toml"),
+            format!(
+                "[area]\noffices = \"130000\"\nclass10s = \"130010\"\nlat = 35.0\nlon = 139.0\n\
+                 precipitation = 1.0\nmax_wind = 8.0\n\
+                 [weather]\nprovider = \"fixture\"\nfixture_dir = {:?}\n\
+                 [[place]]\nname = \"Riverside Park\"\nwalking = true\ncycling = true\n\
+                 [[place]]\nname = \"City Library\"\nindoor = true\n",
+                dir.display()
+            ),
+        )
+        .unwrap();
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let visits = VisitLog::open(dir.join("visits.jsonl"));
+        let journal = ForecastJournal::open(dir.join("journal.jsonl"));
+        let mut suggester = Suggester::from_parts(places, recent, visits, journal).unwrap();
+
+        let suggestion = suggester.suggest(PartOfDay::Morning, &Mood::default(), false, false).await.unwrap();
+        assert!(suggestion.reasoning.iter().any(|line| line.contains("stay in")));
+        // The windy outdoor/cycling place is filtered out by the same
+        // indoor-only fallback mood that rain triggers; only the indoor
+        // place is left to suggest.
+        assert_eq!(suggestion.place.as_ref().map(|p| p.name.as_str()), Some("City Library"));
+    }
+
+    #[tokio::test]
+    async fn a_failed_forecast_fetch_still_suggests_by_mood_alone() {
+        let dir = std::env::temp_dir().join("sunnyday-test-suggester-degraded");
+        std::fs::create_dir_all(&dir).unwrap();
+        // No "morning" entry, so `FixtureProvider::forecast` errors for it.
+        std::fs::write(dir.join("forecast.json"), r#"{"afternoon": {"pop": 10, "precipitation": 0.0}}"#).unwrap();
+        std::fs::write(
+            dir.join("place.toml"),
+            format!(
+                "[area]\noffices = \"130000\"\nclass10s = \"130010\"\nlat = 35.0\nlon = 139.0\n\
+                 precipitation = 1.0\nmax_wind = 8.0\n\
+                 [weather]\nprovider = \"fixture\"\nfixture_dir = {:?}\n\
+                 [[place]]\nname = \"Riverside Park\"\nwalking = true\n",
+                dir.display()
+            ),
+        )
+        .unwrap();
+        let places = Places::read(dir.join("place.toml")).unwrap();
+        let recent = RecentPlace::read(dir.join("recent.toml")).unwrap();
+        let visits = VisitLog::open(dir.join("visits.jsonl"));
+        let journal = ForecastJournal::open(dir.join("journal.jsonl"));
+        let mut suggester = Suggester::from_parts(places, recent, visits, journal).unwrap();
+
+        let suggestion = suggester.suggest(PartOfDay::Morning, &Mood::default(), false, false).await.unwrap();
+        assert!(!suggestion.weather_available);
+        assert_eq!(suggestion.place.as_ref().map(|p| p.name.as_str()), Some("Riverside Park"));
+        assert!(suggestion.reasoning.iter().any(|line| line.contains("weather data unavailable")));
+    }
+}
@@ -0,0 +1,81 @@
+//! Sunrise/sunset calculation, used to sun-adjust the Morning/Evening
+//! windows when [`crate::jma::AreaCode::sun_aware`] is enabled.
+//!
+//! Uses the standard "sunrise equation" (NOAA/Meeus), accurate to within a
+//! few minutes -- plenty for deciding whether a park will still be lit.
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+/// Geometric sunrise/sunset, i.e. the sun's center crossing the horizon
+/// with the standard atmospheric-refraction correction. Ignores civil
+/// twilight.
+const SOLAR_ELEVATION: f64 = -0.833;
+
+/// Julian day number at UTC noon of `date`. Julian days conventionally turn
+/// over at noon, so this is an integer (up to the small `J0` correction
+/// callers add), unlike midnight which would land on a half-day.
+fn julian_day(date: NaiveDate) -> f64 {
+    date.and_hms_opt(12, 0, 0).expect("noon is always valid").and_utc().timestamp() as f64 / 86400.0
+        + 2440587.5
+}
+
+fn from_julian_day(jd: f64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(((jd - 2440587.5) * 86400.0).round() as i64, 0).single()
+}
+
+/// Sunrise and sunset, in `tz`, for `date` at `(lat, lon)`. Returns `None`
+/// during polar day/night, where the sun never crosses the horizon.
+pub fn sunrise_sunset<Tz: TimeZone>(
+    lat: f64,
+    lon: f64,
+    date: NaiveDate,
+    tz: Tz,
+) -> Option<(DateTime<Tz>, DateTime<Tz>)> {
+    let n = julian_day(date) - 2451545.0 + 0.0008;
+    // The sunrise equation is conventionally stated in terms of longitude
+    // measured positive *west*; `lon` here is positive east, so the sign
+    // is flipped relative to most references.
+    let j_star = n - lon / 360.0;
+
+    let mean_anomaly = (357.5291 + 0.98560028 * j_star).rem_euclid(360.0);
+    let m = mean_anomaly.to_radians();
+    let equation_of_center = 1.9148 * m.sin() + 0.0200 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let ecliptic_longitude = (mean_anomaly + 102.9372 + equation_of_center + 180.0).rem_euclid(360.0);
+    let lambda = ecliptic_longitude.to_radians();
+
+    let solar_transit = 2451545.0 + j_star + 0.0053 * m.sin() - 0.0069 * (2.0 * lambda).sin();
+    let declination = (lambda.sin() * 23.44f64.to_radians().sin()).asin();
+
+    let lat_rad = lat.to_radians();
+    let cos_hour_angle = (SOLAR_ELEVATION.to_radians().sin() - lat_rad.sin() * declination.sin())
+        / (lat_rad.cos() * declination.cos());
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    let sunrise = from_julian_day(solar_transit - hour_angle / 360.0)?.with_timezone(&tz);
+    let sunset = from_julian_day(solar_transit + hour_angle / 360.0)?.with_timezone(&tz);
+    Some((sunrise, sunset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+
+    #[test]
+    fn tokyo_midsummer_sunrise_and_sunset_are_plausible() {
+        let date = NaiveDate::from_ymd_opt(2026, 6, 21).unwrap();
+        let (sunrise, sunset) = sunrise_sunset(35.6895, 139.6917, date, chrono_tz::Asia::Tokyo).unwrap();
+        assert!(sunrise < sunset);
+        assert!((4..=5).contains(&sunrise.hour()));
+        assert!((18..=19).contains(&sunset.hour()));
+    }
+
+    #[test]
+    fn polar_night_has_no_sunrise() {
+        let date = NaiveDate::from_ymd_opt(2026, 12, 21).unwrap();
+        assert!(sunrise_sunset(78.0, 15.0, date, chrono_tz::Arctic::Longyearbyen).is_none());
+    }
+}
@@ -0,0 +1,110 @@
+//! systemd service-manager integration for a long-running daemon
+//! (`sunnyday-web`): socket activation (`LISTEN_FDS`/`LISTEN_PID`) and
+//! `sd_notify(3)` readiness/watchdog pings. Both are implemented by hand
+//! against systemd's plain environment-variable/datagram-socket protocol
+//! -- neither needs `libsystemd` itself, so this pulls in no new
+//! dependency for what's really just an env var read and a socket write.
+//! See [`example_units`] for a starting-point `.socket`/`.service` pair.
+
+use std::os::fd::FromRawFd;
+use std::os::unix::net::UnixDatagram;
+
+/// The first passed file descriptor's number, per the `sd_listen_fds(3)`
+/// convention (fds 0-2 are stdio).
+const SD_LISTEN_FDS_START: i32 = 3;
+
+/// Takes over the first socket systemd passed this process via socket
+/// activation, if any -- `LISTEN_FDS`/`LISTEN_PID` both set, and
+/// `LISTEN_PID` naming this process. `None` otherwise (not started under
+/// systemd, or without socket activation configured), meaning the caller
+/// should bind its own listener instead.
+///
+/// Only the first passed fd is used -- this service only ever listens on
+/// one socket, so multiple `ListenStream=` entries aren't supported.
+pub fn listener_from_env() -> Option<std::io::Result<std::net::TcpListener>> {
+    let fds: usize = std::env::var("LISTEN_FDS").ok()?.parse().ok()?;
+    let pid: u32 = std::env::var("LISTEN_PID").ok()?.parse().ok()?;
+    if fds == 0 || pid != std::process::id() {
+        return None;
+    }
+    // SAFETY: systemd's socket-activation protocol guarantees fd
+    // SD_LISTEN_FDS_START is open and already bound/listening for this
+    // process once LISTEN_PID/LISTEN_FDS check out as above.
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(SD_LISTEN_FDS_START) };
+    Some(listener.set_nonblocking(true).map(|()| listener))
+}
+
+/// Best-effort `sd_notify(3)`: sends `state` (e.g. `"READY=1"`,
+/// `"WATCHDOG=1"`) to the datagram socket systemd names in
+/// `NOTIFY_SOCKET`. Silently does nothing outside systemd (no such env
+/// var) or on any send failure -- this is a supervision nicety, not
+/// something the service should fail to start or run over.
+fn notify(state: &str) {
+    let Ok(path) = std::env::var("NOTIFY_SOCKET") else { return };
+    let Ok(socket) = UnixDatagram::unbound() else { return };
+    let _ = socket.send_to(state.as_bytes(), path);
+}
+
+/// Tell systemd this service finished starting (`Type=notify` in the
+/// unit). See [`notify`].
+pub fn notify_ready() {
+    notify("READY=1");
+}
+
+/// How often to ping systemd's watchdog, if `WatchdogSec=` is configured:
+/// half of `WATCHDOG_USEC`, systemd's own recommendation so a missed tick
+/// or two still lands inside the deadline. `None` if no watchdog is
+/// configured.
+pub fn watchdog_interval() -> Option<std::time::Duration> {
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    Some(std::time::Duration::from_micros(usec) / 2)
+}
+
+/// Runs forever, pinging systemd's watchdog every `interval`. Meant to be
+/// spawned once at startup, only when [`watchdog_interval`] returned
+/// `Some` -- it doesn't check again itself.
+pub async fn run_watchdog_pings(interval: std::time::Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        notify("WATCHDOG=1");
+    }
+}
+
+/// An example `.socket`/`.service` unit pair wiring up socket activation,
+/// `Type=notify`, and a watchdog for `sunnyday-web`, for
+/// `--print-systemd-unit` to print. Paths/user are placeholders -- meant
+/// to be copied and edited, not installed verbatim.
+pub fn example_units() -> String {
+    let exe = std::env::current_exe()
+        .ok()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "/usr/local/bin/sunnyday-web".to_string());
+    format!(
+        "# /etc/systemd/system/sunnyday-web.socket\n\
+         [Unit]\n\
+         Description=sunnyday-web listening socket\n\
+         \n\
+         [Socket]\n\
+         ListenStream=3000\n\
+         \n\
+         [Install]\n\
+         WantedBy=sockets.target\n\
+         \n\
+         # /etc/systemd/system/sunnyday-web.service\n\
+         [Unit]\n\
+         Description=sunnyday weather/suggestion web server\n\
+         After=network.target\n\
+         Requires=sunnyday-web.socket\n\
+         \n\
+         [Service]\n\
+         Type=notify\n\
+         WatchdogSec=30\n\
+         ExecStart={exe}\n\
+         WorkingDirectory=/etc/sunnyday\n\
+         User=sunnyday\n\
+         Restart=on-failure\n\
+         \n\
+         [Install]\n\
+         WantedBy=multi-user.target\n"
+    )
+}
@@ -0,0 +1,116 @@
+//! JMA typhoon track forecasts.
+//!
+//! JMA's warning feed (see `warning::classify`'s `"14"`/`"15"` storm codes)
+//! only flags a storm warning once a typhoon is already close enough to
+//! trigger one. This module reads JMA's typhoon track forecast directly,
+//! so an approaching typhoon that's still days and many km out can be
+//! flagged as an advisory well before any warning would fire.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::distance::distance_km;
+use crate::jma::JMA_BASE;
+
+/// How far ahead [`nearest_approach`] looks along each typhoon's forecast
+/// track -- long enough to cover today and tomorrow's suggestions, short
+/// enough that a distant, highly uncertain track position doesn't trigger
+/// an advisory.
+pub const LOOKAHEAD_HOURS: i64 = 48;
+
+#[derive(Debug, Deserialize)]
+struct TyphoonDoc {
+    typhoons: Vec<TyphoonInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TyphoonInfo {
+    name: String,
+    track: Vec<TrackPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrackPoint {
+    time: String,
+    lat: f64,
+    lon: f64,
+}
+
+/// The closest any currently-tracked typhoon's forecast track comes to
+/// `(lat, lon)` within the next [`LOOKAHEAD_HOURS`], as `(name, km)` --
+/// or `None` if no typhoon is being tracked, or none of their tracks pass
+/// anywhere near that window. Callers should treat a request failure the
+/// same as "no typhoon data available" -- this is a best-effort advisory
+/// on top of the forecast, not a requirement, the same as
+/// [`crate::wbgt::current_wbgt`].
+pub async fn nearest_approach(client: &reqwest::Client, lat: f64, lon: f64) -> Result<Option<(String, f64)>> {
+    let url = format!("{JMA_BASE}/typhoon/data/typhoon.json");
+    let doc: TyphoonDoc =
+        client.get(&url).send().await?.error_for_status()?.json().await.context("parsing JMA typhoon response")?;
+    Ok(nearest_approach_in(&doc.typhoons, lat, lon, chrono::Utc::now()))
+}
+
+/// The parsing/filtering half of [`nearest_approach`], split out so it can
+/// be exercised against a hand-built typhoon list without a network call.
+fn nearest_approach_in(typhoons: &[TyphoonInfo], lat: f64, lon: f64, now: chrono::DateTime<chrono::Utc>) -> Option<(String, f64)> {
+    let horizon = now + chrono::Duration::hours(LOOKAHEAD_HOURS);
+    let mut nearest: Option<(String, f64)> = None;
+    for typhoon in typhoons {
+        for point in &typhoon.track {
+            let Ok(time) = chrono::DateTime::parse_from_rfc3339(&point.time) else { continue };
+            let time = time.with_timezone(&chrono::Utc);
+            if time < now || time > horizon {
+                continue;
+            }
+            let km = distance_km((lat, lon), (point.lat, point.lon));
+            if nearest.as_ref().is_none_or(|(_, d)| km < *d) {
+                nearest = Some((typhoon.name.clone(), km));
+            }
+        }
+    }
+    nearest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_closest_track_point_within_the_lookahead_and_ignores_the_past() {
+        let typhoons: Vec<TyphoonInfo> = serde_json::from_str(
+            r#"[
+                {
+                    "name": "Typhoon Distant",
+                    "track": [{"time": "2026-08-09T00:00:00Z", "lat": 10.0, "lon": 130.0}]
+                },
+                {
+                    "name": "Typhoon Close",
+                    "track": [
+                        {"time": "2026-08-08T00:00:00Z", "lat": 35.0, "lon": 139.0},
+                        {"time": "2026-08-10T00:00:00Z", "lat": 35.5, "lon": 139.5}
+                    ]
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let now = chrono::DateTime::parse_from_rfc3339("2026-08-09T00:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        let (name, km) = nearest_approach_in(&typhoons, 35.0, 139.0, now).unwrap();
+        assert_eq!(name, "Typhoon Close");
+        assert!(km < 100.0, "expected a close approach, got {km}km");
+    }
+
+    #[test]
+    fn returns_none_when_every_track_point_is_outside_the_lookahead() {
+        let typhoons: Vec<TyphoonInfo> = serde_json::from_str(
+            r#"[{
+                "name": "Typhoon Far Off",
+                "track": [{"time": "2026-08-20T00:00:00Z", "lat": 35.0, "lon": 139.0}]
+            }]"#,
+        )
+        .unwrap();
+
+        let now = chrono::DateTime::parse_from_rfc3339("2026-08-09T00:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        assert!(nearest_approach_in(&typhoons, 35.0, 139.0, now).is_none());
+    }
+}
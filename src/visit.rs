@@ -0,0 +1,154 @@
+//! Long-term, append-only record of whether a suggestion was actually acted
+//! on. Kept separate from [`crate::recent::RecentPlace`], which only
+//! remembers enough to drive the rotation window and gets pruned -- this
+//! log is never pruned, and is the raw material for `sunnyday stats` and
+//! any future learning from it.
+
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::jma::PartOfDay;
+
+/// Whether a suggestion was followed or passed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VisitOutcome {
+    Accepted,
+    Declined,
+}
+
+/// One line of [`VisitLog`]: a suggestion, and how it was answered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisitRecord {
+    pub date: NaiveDate,
+    pub part: PartOfDay,
+    pub place: String,
+    pub outcome: VisitOutcome,
+    /// Whether the suggestion was walkable at the time, so statistics can
+    /// correlate declines with weather (see
+    /// `kumanofoo/sunnyday#synth-373`'s "how often rain changed plans").
+    pub walkable: bool,
+    /// The forecast at the time `accept`/`skip` was run, for `sunnyday
+    /// stats export` -- a fresh lookup, not the one `suggest` originally
+    /// saw, same caveat as `walkable`'s live AMeDAS check.
+    #[serde(default)]
+    pub pop: u32,
+    #[serde(default)]
+    pub precipitation: f64,
+    #[serde(default)]
+    pub wind_speed: f64,
+}
+
+/// Append-only JSON-lines log at `path`. Unlike [`crate::recent::RecentPlace`]
+/// there's no in-memory state to mutate and save -- each [`Self::append`]
+/// call writes its line immediately.
+pub struct VisitLog {
+    path: PathBuf,
+}
+
+impl VisitLog {
+    pub fn open<P: AsRef<Path>>(path: P) -> Self {
+        VisitLog { path: path.as_ref().to_path_buf() }
+    }
+
+    /// Append `record` as one JSON line, creating the file if it doesn't
+    /// exist yet.
+    pub fn append(&self, record: &VisitRecord) -> Result<()> {
+        let line = serde_json::to_string(record).context("serializing visit record")?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("opening {}", self.path.display()))?;
+        writeln!(file, "{line}").with_context(|| format!("writing to {}", self.path.display()))
+    }
+
+    /// Remove the most recent record for `date`/`part`, if any, e.g. to
+    /// undo an accidental `accept`/`skip` alongside
+    /// [`crate::recent::RecentPlace::undo`]. Returns `false` without
+    /// touching the file if there's no matching record -- not every
+    /// suggestion gets logged here, only ones actually `accept`ed or
+    /// `skip`ped.
+    pub fn undo(&self, date: NaiveDate, part: PartOfDay) -> Result<bool> {
+        let mut records = self.read_all()?;
+        let Some(pos) = records.iter().rposition(|r| r.date == date && r.part == part) else {
+            return Ok(false);
+        };
+        records.remove(pos);
+
+        let mut file = std::fs::File::create(&self.path).with_context(|| format!("writing {}", self.path.display()))?;
+        for record in &records {
+            let line = serde_json::to_string(record).context("serializing visit record")?;
+            writeln!(file, "{line}")?;
+        }
+        Ok(true)
+    }
+
+    /// Read every record logged so far, oldest first. A missing file reads
+    /// as empty, same convention as [`crate::recent::RecentPlace::read`].
+    pub fn read_all(&self) -> Result<Vec<VisitRecord>> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("opening {}", self.path.display())),
+        };
+        BufReader::new(file)
+            .lines()
+            .map(|line| {
+                let line = line.with_context(|| format!("reading {}", self.path.display()))?;
+                serde_json::from_str(&line).with_context(|| format!("parsing {}", self.path.display()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 8, day).unwrap()
+    }
+
+    #[test]
+    fn appended_records_read_back_in_order() {
+        let path = std::env::temp_dir().join("sunnyday-test-visit-log.jsonl");
+        let _ = std::fs::remove_file(&path);
+        let log = VisitLog::open(&path);
+
+        log.append(&VisitRecord { date: date(1), part: PartOfDay::Morning, place: "Riverside Park".into(), outcome: VisitOutcome::Accepted, walkable: true, pop: 10, precipitation: 0.0, wind_speed: 2.0 }).unwrap();
+        log.append(&VisitRecord { date: date(2), part: PartOfDay::Evening, place: "City Library".into(), outcome: VisitOutcome::Declined, walkable: false, pop: 80, precipitation: 5.0, wind_speed: 6.0 }).unwrap();
+
+        let records = log.read_all().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].place, "Riverside Park");
+        assert_eq!(records[1].outcome, VisitOutcome::Declined);
+    }
+
+    #[test]
+    fn undo_removes_only_the_matching_record() {
+        let path = std::env::temp_dir().join("sunnyday-test-visit-log-undo.jsonl");
+        let _ = std::fs::remove_file(&path);
+        let log = VisitLog::open(&path);
+        log.append(&VisitRecord { date: date(1), part: PartOfDay::Morning, place: "Riverside Park".into(), outcome: VisitOutcome::Accepted, walkable: true, pop: 10, precipitation: 0.0, wind_speed: 2.0 }).unwrap();
+        log.append(&VisitRecord { date: date(2), part: PartOfDay::Evening, place: "City Library".into(), outcome: VisitOutcome::Declined, walkable: false, pop: 80, precipitation: 5.0, wind_speed: 6.0 }).unwrap();
+
+        assert!(log.undo(date(2), PartOfDay::Evening).unwrap());
+        let records = log.read_all().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].place, "Riverside Park");
+
+        assert!(!log.undo(date(2), PartOfDay::Evening).unwrap());
+    }
+
+    #[test]
+    fn reading_a_missing_log_is_empty_not_an_error() {
+        let log = VisitLog::open(std::env::temp_dir().join("sunnyday-test-visit-log-missing.jsonl"));
+        assert!(log.read_all().unwrap().is_empty());
+    }
+}
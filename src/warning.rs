@@ -0,0 +1,96 @@
+//! JMA weather warnings and advisories ("keihou"/"chuuihou").
+//!
+//! Forecasts and tile-derived precipitation estimates are useful, but JMA
+//! also publishes a separate warning feed per `offices` code; a heavy-rain
+//! or storm warning is a stronger "stay in" signal than any threshold this
+//! tool computes on its own, so it's surfaced alongside the forecast rather
+//! than folded into it.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::jma::JMA_BASE;
+
+/// Status JMA uses to mark a warning as no longer in effect.
+const CLEARED_STATUS: &str = "解除";
+
+#[derive(Debug, Deserialize)]
+struct WarningDoc {
+    #[serde(rename = "areaTypes")]
+    area_types: Vec<AreaType>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AreaType {
+    areas: Vec<WarningArea>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WarningArea {
+    #[serde(default)]
+    warnings: Vec<WarningEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WarningEntry {
+    code: String,
+    status: String,
+}
+
+/// One active warning or advisory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// Human-readable name, e.g. "heavy rain warning".
+    pub name: String,
+    /// Whether this kind is severe enough that a suggestion should be
+    /// downgraded regardless of what the rain/wind thresholds say.
+    pub forces_stay_in: bool,
+}
+
+/// Map a JMA warning code to a name and severity. Only the codes relevant
+/// to an outing decision are covered; anything else is ignored.
+fn classify(code: &str) -> Option<(&'static str, bool)> {
+    match code {
+        "03" => Some(("heavy rain warning", true)),
+        "04" => Some(("heavy rain advisory", true)),
+        "14" => Some(("storm warning", true)),
+        "15" => Some(("storm advisory", true)),
+        "32" => Some(("heat advisory", true)),
+        "06" => Some(("flood advisory", false)),
+        _ => None,
+    }
+}
+
+/// Fetch the warnings/advisories currently active for `offices`.
+pub async fn active_warnings(client: &reqwest::Client, offices: &str) -> Result<Vec<Warning>> {
+    let url = format!("{JMA_BASE}/warning/data/warning/{offices}.json");
+    let doc: WarningDoc = client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .context("parsing JMA warning response")?;
+
+    let mut warnings = Vec::new();
+    for area_type in &doc.area_types {
+        for area in &area_type.areas {
+            for entry in &area.warnings {
+                if entry.status == CLEARED_STATUS {
+                    continue;
+                }
+                if let Some((name, forces_stay_in)) = classify(&entry.code) {
+                    let warning = Warning {
+                        name: name.to_string(),
+                        forces_stay_in,
+                    };
+                    if !warnings.contains(&warning) {
+                        warnings.push(warning);
+                    }
+                }
+            }
+        }
+    }
+    Ok(warnings)
+}
@@ -0,0 +1,76 @@
+//! Ministry of Environment (MOE) WBGT heat-stress index.
+//!
+//! JMA's own warning feed only has a coarse "heat advisory" flag (see
+//! `warning::classify`'s `"32"` code); the actual WBGT value -- the
+//! combined heat/humidity/radiation index heat-stroke guidance is based
+//! on -- comes from the Ministry of Environment's separate WBGT service,
+//! not JMA. MOE publishes WBGT by observation point rather than at
+//! arbitrary coordinates, so [`nearest_station`] picks the closest one the
+//! same way [`crate::amedas::nearest_station`] does for AMeDAS.
+
+use anyhow::{anyhow, Result};
+use chrono::Datelike;
+use serde::Deserialize;
+
+/// Root of the Ministry of Environment's WBGT ("暑さ指数") service.
+pub const WBGT_BASE: &str = "https://www.wbgt.env.go.jp";
+
+/// Whether MOE's WBGT service is expected to be running for `date` --
+/// it only operates from late April through October, matching the
+/// service's real-world season. Callers should skip fetching outside this
+/// window rather than pay for a request that's only going to fail.
+pub fn in_season(date: chrono::NaiveDate) -> bool {
+    (4..=10).contains(&date.month())
+}
+
+#[derive(Debug, Deserialize)]
+struct StationInfo {
+    lat: f64,
+    lon: f64,
+}
+
+/// Find the WBGT observation point nearest `(lat, lon)`, by simple planar
+/// distance -- same approach as AMeDAS's station lookup, but over MOE's
+/// own, much sparser, station list.
+pub async fn nearest_station(client: &reqwest::Client, lat: f64, lon: f64) -> Result<String> {
+    let url = format!("{WBGT_BASE}/wbgt_data/stations.json");
+    let table: std::collections::HashMap<String, StationInfo> =
+        client.get(&url).send().await?.error_for_status()?.json().await?;
+
+    table
+        .iter()
+        .min_by(|(_, a), (_, b)| {
+            let da = (a.lat - lat).powi(2) + (a.lon - lon).powi(2);
+            let db = (b.lat - lat).powi(2) + (b.lon - lon).powi(2);
+            da.partial_cmp(&db).unwrap()
+        })
+        .map(|(code, _)| code.clone())
+        .ok_or_else(|| anyhow!("WBGT station list is empty"))
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestWbgt {
+    wbgt: f64,
+}
+
+/// Fetch `station`'s current WBGT value, in degrees Celsius-equivalent.
+/// Out of season (MOE only runs this service through the summer months)
+/// this is expected to fail -- callers should treat an error as "no WBGT
+/// guidance right now" rather than surfacing it as a hard failure.
+pub async fn current_wbgt(client: &reqwest::Client, station: &str) -> Result<f64> {
+    let url = format!("{WBGT_BASE}/wbgt_data/{station}/latest.json");
+    let latest: LatestWbgt = client.get(&url).send().await?.error_for_status()?.json().await?;
+    Ok(latest.wbgt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_season_covers_late_april_through_october_only() {
+        assert!(!in_season(chrono::NaiveDate::from_ymd_opt(2026, 3, 31).unwrap()));
+        assert!(in_season(chrono::NaiveDate::from_ymd_opt(2026, 8, 9).unwrap()));
+        assert!(!in_season(chrono::NaiveDate::from_ymd_opt(2026, 11, 1).unwrap()));
+    }
+}